@@ -0,0 +1,76 @@
+#![feature(test)]
+
+extern crate test;
+
+use test::Bencher;
+
+use hyperon::*;
+use std::collections::HashSet;
+
+const NAME: &str = "a-rather-long-symbol-name-from-the-knowledge-base";
+
+fn vocabulary(size: usize) -> Vec<Atom> {
+    (0..size).map(|i| Atom::sym(format!("{}-{}", NAME, i))).collect()
+}
+
+#[bench]
+fn symbol_eq_same_name(bencher: &mut Bencher) {
+    let a = Atom::sym(NAME);
+    let b = Atom::sym(String::from(NAME));
+    bencher.iter(|| {
+        assert!(test::black_box(&a) == test::black_box(&b));
+    })
+}
+
+#[bench]
+fn symbol_eq_different_name(bencher: &mut Bencher) {
+    let a = Atom::sym(format!("{}-a", NAME));
+    let b = Atom::sym(format!("{}-b", NAME));
+    bencher.iter(|| {
+        assert!(test::black_box(&a) != test::black_box(&b));
+    })
+}
+
+#[bench]
+fn symbol_eq_const_and_parsed(bencher: &mut Bencher) {
+    const EQUAL: Atom = sym!("=");
+    let parsed = Atom::sym("=");
+    bencher.iter(|| {
+        assert!(test::black_box(&EQUAL) == test::black_box(&parsed));
+    })
+}
+
+#[bench]
+fn symbol_new_existing_name(bencher: &mut Bencher) {
+    let _vocabulary = vocabulary(1000);
+    let name = format!("{}-500", NAME);
+    bencher.iter(|| {
+        test::black_box(Atom::sym(name.as_str()))
+    })
+}
+
+fn symbols(atoms: Vec<Atom>) -> Vec<SymbolAtom> {
+    atoms.into_iter().filter_map(|atom| match atom {
+        Atom::Symbol(sym) => Some(sym),
+        _ => None,
+    }).collect()
+}
+
+#[bench]
+fn symbol_hash_set_lookup_x1000(bencher: &mut Bencher) {
+    let set: HashSet<SymbolAtom> = symbols(vocabulary(1000)).into_iter().collect();
+    let keys = symbols(vocabulary(1000));
+    bencher.iter(|| {
+        assert!(keys.iter().all(|key| set.contains(key)));
+    })
+}
+
+#[bench]
+fn symbol_match_expression(bencher: &mut Bencher) {
+    let vocabulary = vocabulary(8);
+    let atom = Atom::expr(vocabulary.clone());
+    let pattern = Atom::expr(vocabulary);
+    bencher.iter(|| {
+        assert!(hyperon::atom::matcher::match_atoms(&atom, &pattern).next().is_some());
+    })
+}
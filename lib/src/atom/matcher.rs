@@ -1155,12 +1155,9 @@ pub fn apply_bindings_to_atom_mut(atom: &mut Atom, bindings: &Bindings) {
         false => None,
     };
     if !bindings.is_empty() {
-        atom.iter_mut().for_each(|atom| match atom {
-            Atom::Variable(var) => {
-                bindings.resolve(var).map(|value| *atom = value);
-            },
-            _ => {},
-        });
+        if let Some(replaced) = replace_variables(atom, |var| bindings.resolve(var)) {
+            *atom = replaced;
+        }
     }
     if let Some(atom_copy) = trace_atom {
         log::trace!("apply_bindings_to_atom: {} | {} -> {}", atom_copy, bindings, atom);
@@ -90,7 +90,8 @@ macro_rules! expr {
 #[macro_export]
 macro_rules! constexpr {
     () => { $crate::Atom::Expression($crate::ExpressionAtom::new($crate::common::collections::CowArray::Literal(&[]))) };
-    ($x:literal) => { $crate::Atom::Symbol($crate::SymbolAtom::new($crate::common::collections::ImmutableString::Literal($x))) };
+    ($x:literal) => { $crate::Atom::Symbol($crate::SymbolAtom::new($crate::common::collections::ImmutableString::Interned(
+        $crate::common::collections::InternedString::from_static(const { &$crate::common::collections::InternedEntry::literal($x) })))) };
     (($($x:tt)*)) => { $crate::Atom::Expression($crate::ExpressionAtom::new($crate::common::collections::CowArray::Literal(const { &[ $( constexpr!($x) , )* ] }))) };
     ($($x:tt)*) => { $crate::Atom::Expression($crate::ExpressionAtom::new($crate::common::collections::CowArray::Literal(const { &[ $( constexpr!($x) , )* ] }))) };
 }
@@ -111,7 +112,8 @@ macro_rules! constexpr {
 /// ```
 #[macro_export]
 macro_rules! sym {
-    ($x:literal) => { $crate::Atom::Symbol($crate::SymbolAtom::new($crate::common::collections::ImmutableString::Literal($x))) };
+    ($x:literal) => { $crate::Atom::Symbol($crate::SymbolAtom::new($crate::common::collections::ImmutableString::Interned(
+        $crate::common::collections::InternedString::from_static(const { &$crate::common::collections::InternedEntry::literal($x) })))) };
 }
 
 pub mod matcher;
@@ -124,6 +126,8 @@ pub use iter::*;
 use std::any::Any;
 use std::fmt::{Display, Debug};
 use std::convert::TryFrom;
+use std::collections::HashMap;
+use std::hash::{DefaultHasher, Hash, Hasher};
 
 use crate::common::collections::{ImmutableString, CowArray};
 
@@ -137,7 +141,8 @@ pub struct SymbolAtom {
 
 impl SymbolAtom {
     /// Constructs new symbol from `name`. Not intended to be used directly,
-    /// use [sym!] or [Atom::sym] instead.
+    /// use [sym!] or [Atom::sym] instead. Both intern the name, names which
+    /// are not interned are compared as strings.
     pub const fn new(name: ImmutableString) -> Self {
         Self{ name }
     }
@@ -318,10 +323,184 @@ impl Display for VariableAtom {
 }
 
 /// Returns `atom` with all variables replaced by unique instances.
-pub fn make_variables_unique(mut atom: Atom) -> Atom {
+pub fn make_variables_unique(atom: Atom) -> Atom {
     let mut mapper = crate::common::CachingMapper::new(VariableAtom::make_unique);
-    atom.iter_mut().filter_type::<&mut VariableAtom>().for_each(|var| *var = mapper.replace(var.clone()));
-    atom
+    replace_variables(&atom, |var| Some(Atom::Variable(mapper.replace(var.clone()))))
+        .unwrap_or(atom)
+}
+
+/// Replaces variables inside `atom` by the atoms returned from `replace`.
+/// Returns [None] when `replace` returns [None] for each variable, i.e. atom
+/// is not changed. Sub-expressions without replaced variables are not copied,
+/// they share children with the original `atom`.
+///
+/// # Examples
+///
+/// ```
+/// use hyperon::{expr, Atom};
+/// use hyperon::atom::replace_variables;
+///
+/// let atom = expr!("foo" x ("bar" "baz"));
+/// let replaced = replace_variables(&atom, |v| match v.name().as_str() {
+///     "x" => Some(expr!("X")),
+///     _ => None,
+/// });
+///
+/// assert_eq!(replaced, Some(expr!("foo" "X" ("bar" "baz"))));
+/// assert_eq!(replace_variables(&atom, |_| None), None);
+/// ```
+pub fn replace_variables<F>(atom: &Atom, mut replace: F) -> Option<Atom>
+    where F: FnMut(&VariableAtom) -> Option<Atom>
+{
+    replace_variables_internal(atom, &mut replace)
+}
+
+fn replace_variables_internal<F>(atom: &Atom, replace: &mut F) -> Option<Atom>
+    where F: FnMut(&VariableAtom) -> Option<Atom>
+{
+    match atom {
+        Atom::Variable(var) => replace(var),
+        Atom::Expression(expr) => {
+            let children = expr.children();
+            let mut replaced: Option<Vec<Atom>> = None;
+            for (i, child) in children.iter().enumerate() {
+                match replace_variables_internal(child, replace) {
+                    Some(child) => replaced
+                        .get_or_insert_with(|| {
+                            let mut copy = Vec::with_capacity(children.len());
+                            copy.extend_from_slice(&children[..i]);
+                            copy
+                        })
+                        .push(child),
+                    None => if let Some(replaced) = replaced.as_mut() {
+                        replaced.push(child.clone());
+                    },
+                }
+            }
+            replaced.map(Atom::expr)
+        },
+        Atom::Symbol(_) | Atom::Grounded(_) => None,
+    }
+}
+
+/// Table of expressions which is used to share memory between equal
+/// sub-expressions (hash consing). Only expressions without grounded atoms
+/// are shared because grounded atoms can have an internal state.
+///
+/// The table is bounded: expressions which contain more than `max_size`
+/// atoms are not kept because they rarely repeat, and the table is cleared
+/// when it reaches `capacity` expressions.
+///
+/// # Examples
+///
+/// ```
+/// use hyperon::expr;
+/// use hyperon::atom::ExpressionTable;
+///
+/// let mut table = ExpressionTable::new();
+/// let a = table.share(expr!("foo" ("->" "A" "B")));
+/// let b = table.share(expr!("bar" ("->" "A" "B")));
+///
+/// assert_eq!(a, expr!("foo" ("->" "A" "B")));
+/// assert_eq!(table.len(), 3);
+/// ```
+#[derive(Debug)]
+pub struct ExpressionTable {
+    exprs: HashMap<u64, Vec<Atom>>,
+    len: usize,
+    max_size: usize,
+    capacity: usize,
+}
+
+impl Default for ExpressionTable {
+    fn default() -> Self {
+        Self::with_limits(Self::DEFAULT_MAX_SIZE, Self::DEFAULT_CAPACITY)
+    }
+}
+
+impl ExpressionTable {
+    /// Default maximal number of atoms in a shared expression.
+    pub const DEFAULT_MAX_SIZE: usize = 64;
+    /// Default maximal number of expressions kept in the table.
+    pub const DEFAULT_CAPACITY: usize = 64 * 1024;
+
+    /// Constructs new empty table.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Constructs new empty table which shares expressions containing at
+    /// most `max_size` atoms and keeps at most `capacity` expressions.
+    pub fn with_limits(max_size: usize, capacity: usize) -> Self {
+        Self{ exprs: HashMap::new(), len: 0, max_size, capacity }
+    }
+
+    /// Returns atom equal to the passed one which shares equal sub-expressions
+    /// with atoms previously passed to the table.
+    pub fn share(&mut self, atom: Atom) -> Atom {
+        self.share_internal(atom).0
+    }
+
+    /// Returns number of different expressions in the table.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if table doesn't contain expressions.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Removes all expressions from the table.
+    pub fn clear(&mut self) {
+        self.exprs.clear();
+        self.len = 0;
+    }
+
+    fn share_internal(&mut self, atom: Atom) -> (Atom, Option<(u64, usize)>) {
+        let mut hasher = DefaultHasher::new();
+        match atom {
+            Atom::Symbol(ref sym) => {
+                sym.hash(&mut hasher);
+                (atom, Some((hasher.finish(), 1)))
+            },
+            Atom::Variable(ref var) => {
+                var.hash(&mut hasher);
+                (atom, Some((hasher.finish(), 1)))
+            },
+            Atom::Grounded(_) => (atom, None),
+            Atom::Expression(expr) => {
+                let mut hashable = true;
+                let mut size = 1;
+                let children: Vec<Atom> = expr.into_children().into_iter()
+                    .map(|child| {
+                        let (child, hash) = self.share_internal(child);
+                        match hash {
+                            Some((hash, child_size)) => {
+                                hasher.write_u64(hash);
+                                size += child_size;
+                            },
+                            None => hashable = false,
+                        }
+                        child
+                    }).collect();
+                let atom = Atom::expr(children);
+                if !hashable || size > self.max_size {
+                    return (atom, None)
+                }
+                let hash = hasher.finish();
+                if let Some(shared) = self.exprs.get(&hash).and_then(|exprs| exprs.iter().find(|e| **e == atom)) {
+                    return (shared.clone(), Some((hash, size)))
+                }
+                if self.len >= self.capacity {
+                    self.clear();
+                }
+                self.exprs.entry(hash).or_default().push(atom.clone());
+                self.len += 1;
+                (atom, Some((hash, size)))
+            },
+        }
+    }
 }
 
 // Grounded atom
@@ -810,7 +989,9 @@ pub enum Atom {
 }
 
 impl Atom {
-    /// Constructs new symbol atom with given `name`.
+    /// Constructs new symbol atom with given `name`. Name is interned, thus
+    /// symbols with the same name share the same memory and are compared
+    /// by pointer.
     ///
     /// # Examples
     ///
@@ -825,10 +1006,8 @@ impl Atom {
     /// assert_eq!(a, aa);
     /// assert_ne!(a, b);
     /// ```
-    // TODO: can be rewritten using Into<ImmutableString> to convert &str
-    // literasl into ImmutableString::Literal.
     pub fn sym<T: Into<String>>(name: T) -> Self {
-        Self::Symbol(SymbolAtom::new(ImmutableString::Allocated(name.into())))
+        Self::Symbol(SymbolAtom::new(ImmutableString::interned(&name.into())))
     }
 
     /// Constructs expression out of array of children.
@@ -1094,12 +1273,12 @@ mod test {
 
     #[inline]
     fn symbol(name: &'static str) -> Atom {
-        Atom::Symbol(SymbolAtom::new(ImmutableString::Literal(name)))
+        Atom::Symbol(SymbolAtom::new(ImmutableString::interned(name)))
     }
 
     #[inline]
     fn expression<const N: usize>(children: [Atom; N]) -> Atom {
-        Atom::Expression(ExpressionAtom::new(CowArray::Allocated(Box::new(children))))
+        Atom::Expression(ExpressionAtom::new(CowArray::from(children)))
     }
 
     #[inline]
@@ -1296,4 +1475,90 @@ mod test {
             Err("Atom is not an ExpressionAtom"));
     }
 
+    fn shares_children(a: &Atom, b: &Atom) -> bool {
+        match (a, b) {
+            (Atom::Expression(a), Atom::Expression(b)) => a.children.ptr_eq(&b.children),
+            _ => false,
+        }
+    }
+
+    #[test]
+    fn expression_clone_shares_children() {
+        let atom = Atom::expr([sym!("A"), Atom::expr([sym!("B"), sym!("C")])]);
+        let mut copy = atom.clone();
+        assert!(shares_children(&atom, &copy));
+
+        if let Atom::Expression(expr) = &mut copy {
+            expr.children_mut();
+        }
+        assert!(!shares_children(&atom, &copy));
+        assert_eq!(atom, copy);
+    }
+
+    #[test]
+    fn replace_variables_shares_unchanged_subexpressions() {
+        let unchanged = Atom::expr([sym!("B"), sym!("C")]);
+        let atom = Atom::expr([unchanged.clone(), Atom::expr([sym!("D"), Atom::var("x")])]);
+
+        let replaced = replace_variables(&atom, |_| Some(sym!("X"))).unwrap();
+
+        assert_eq!(replaced, expr!(("B" "C") ("D" "X")));
+        let children = <&[Atom]>::try_from(&replaced).unwrap();
+        assert!(shares_children(&children[0], &unchanged));
+    }
+
+    #[test]
+    fn make_variables_unique_keeps_atom_without_variables() {
+        let atom = Atom::expr([sym!("A"), Atom::expr([sym!("B"), sym!("C")])]);
+        let unique = make_variables_unique(atom.clone());
+        assert!(shares_children(&atom, &unique));
+    }
+
+    #[test]
+    fn expression_table_shares_equal_subexpressions() {
+        let mut table = ExpressionTable::new();
+        let a = table.share(Atom::expr([sym!("foo"), Atom::expr([sym!("A"), Atom::var("x")])]));
+        let b = table.share(Atom::expr([sym!("bar"), Atom::expr([sym!("A"), Atom::var("x")])]));
+
+        assert_eq!(table.len(), 3);
+        let a = <&[Atom]>::try_from(&a).unwrap();
+        let b = <&[Atom]>::try_from(&b).unwrap();
+        assert!(shares_children(&a[1], &b[1]));
+    }
+
+    #[test]
+    fn expression_table_does_not_share_grounded_atoms() {
+        let mut table = ExpressionTable::new();
+        let a = table.share(Atom::expr([sym!("A"), Atom::value(1)]));
+        let b = table.share(Atom::expr([sym!("A"), Atom::value(1)]));
+
+        assert!(table.is_empty());
+        assert_eq!(a, b);
+        assert!(!shares_children(&a, &b));
+    }
+
+    #[test]
+    fn expression_table_does_not_share_large_expressions() {
+        let mut table = ExpressionTable::with_limits(3, 16);
+        let a = table.share(Atom::expr([sym!("foo"), Atom::expr([sym!("A"), sym!("B")])]));
+        let b = table.share(Atom::expr([sym!("foo"), Atom::expr([sym!("A"), sym!("B")])]));
+
+        assert_eq!(table.len(), 1);
+        assert_eq!(a, b);
+        assert!(!shares_children(&a, &b));
+        let a = <&[Atom]>::try_from(&a).unwrap();
+        let b = <&[Atom]>::try_from(&b).unwrap();
+        assert!(shares_children(&a[1], &b[1]));
+    }
+
+    #[test]
+    fn expression_table_is_cleared_when_capacity_is_reached() {
+        let mut table = ExpressionTable::with_limits(16, 2);
+        table.share(Atom::expr([sym!("A")]));
+        table.share(Atom::expr([sym!("B")]));
+        assert_eq!(table.len(), 2);
+
+        table.share(Atom::expr([sym!("C")]));
+        assert_eq!(table.len(), 1);
+    }
 }
@@ -27,6 +27,9 @@ pub trait Serializer {
     fn serialize_f64(&mut self, _v: f64) -> Result { Err(Error::NotSupported) }
     /// Serialize string value.
     fn serialize_str(&mut self, _v: &str) -> Result { Err(Error::NotSupported) }
+    /// Serialize exact number which doesn't fit into i64: integer as a
+    /// decimal string or rational number as `<numerator>/<denominator>`.
+    fn serialize_number_str(&mut self, _v: &str) -> Result { Err(Error::NotSupported) }
 }
 
 /// Serialization error code
@@ -81,6 +84,7 @@ impl<H: PrivHasher> Serializer for H {
     fn serialize_i64(&mut self, v: i64) -> Result { Ok(self.write_i64(v)) }
     fn serialize_f64(&mut self, v: f64) -> Result { Ok(self.write_u64(v as u64)) }
     fn serialize_str(&mut self, v: &str) -> Result { Ok(v.bytes().for_each(|b| self.write_u8(b))) }
+    fn serialize_number_str(&mut self, v: &str) -> Result { self.serialize_str(v) }
 }
 
 // for debugging
@@ -89,6 +93,7 @@ impl Serializer for String {
     fn serialize_i64(&mut self, v: i64) -> Result { Ok(self.push_str(&*v.to_string())) }
     fn serialize_f64(&mut self, v: f64) -> Result { Ok(self.push_str(&*v.to_string())) }
     fn serialize_str(&mut self, v: &str) -> Result { Ok(self.push_str(v)) }
+    fn serialize_number_str(&mut self, v: &str) -> Result { self.serialize_str(v) }
 }
 
 // for speed, but is technically unsafe at usage site because not a valid utf-8 string
@@ -97,6 +102,7 @@ impl Serializer for Vec<u8> {
     fn serialize_i64(&mut self, v: i64) -> Result { Ok(self.extend(v.to_le_bytes())) }
     fn serialize_f64(&mut self, v: f64) -> Result { Ok(self.extend(v.to_le_bytes())) }
     fn serialize_str(&mut self, v: &str) -> Result { Ok(self.extend(v.bytes())) }
+    fn serialize_number_str(&mut self, v: &str) -> Result { self.serialize_str(v) }
 }
 
 #[derive(Default)]
@@ -107,6 +113,7 @@ impl Serializer for NullSerializer {
     fn serialize_i64(&mut self, _v: i64) -> Result { Ok(()) }
     fn serialize_f64(&mut self, _v: f64) -> Result { Ok(()) }
     fn serialize_str(&mut self, _v: &str) -> Result { Ok(()) }
+    fn serialize_number_str(&mut self, _v: &str) -> Result { Ok(()) }
 }
 
 #[cfg(test)]
@@ -49,6 +49,20 @@ impl<T, E: Equality<T>> VecDiff<T, E> {
         !self.diff.is_empty()
     }
 
+    /// Returns expected items which are absent in the actual collection
+    pub fn missed(&self) -> impl Iterator<Item=&T> {
+        self.diff.iter()
+            .filter(|(_v, c)| c.actual < c.expected)
+            .flat_map(|(v, c)| std::iter::repeat_n(v, c.expected - c.actual))
+    }
+
+    /// Returns actual items which are absent in the expected collection
+    pub fn excessive(&self) -> impl Iterator<Item=&T> {
+        self.diff.iter()
+            .filter(|(_v, c)| c.actual > c.expected)
+            .flat_map(|(v, c)| std::iter::repeat_n(v, c.actual - c.expected))
+    }
+
     pub fn as_display(&self) -> Option<String> where T: Display {
         self.as_string(FormatAsDisplay)
     }
@@ -62,16 +76,8 @@ impl<T, E: Equality<T>> VecDiff<T, E> {
     {
         let mut diff = String::new();
         if self.has_diff() {
-            let mut missed = self.diff.iter()
-                .filter(|(_v, c)| c.actual < c.expected)
-                .flat_map(|(v, c)| std::iter::repeat_n(v, c.expected - c.actual))
-                .map(f)
-                .peekable();
-            let mut excessive = self.diff.iter()
-                .filter(|(_v, c)| c.actual > c.expected)
-                .flat_map(|(v, c)| std::iter::repeat_n(v, c.actual - c.expected))
-                .map(f)
-                .peekable();
+            let mut missed = self.missed().map(f).peekable();
+            let mut excessive = self.excessive().map(f).peekable();
             if missed.peek().is_some() {
                 diff.push_str(format!("Missed results: {}", missed.format(", ")).as_str());
             }
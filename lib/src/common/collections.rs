@@ -1,4 +1,7 @@
 use std::fmt::Display;
+use std::collections::HashSet;
+use std::rc::Rc;
+use std::sync::{Arc, RwLock, OnceLock};
 use itertools::Itertools;
 
 pub trait Equality<T> {
@@ -156,20 +159,31 @@ impl<K, V, E: Equality<K>> FromIterator<(K, V)> for ListMap<K, V, E> {
 pub enum ImmutableString {
     Allocated(String),
     Literal(&'static str),
+    Interned(InternedString),
 }
 
 impl ImmutableString {
+    /// Returns the string from the global intern table, adds the string into
+    /// the table if it is not there yet.
+    pub fn interned(s: &str) -> Self {
+        ImmutableString::Interned(InternedString::new(s))
+    }
+
     pub fn as_str(&self) -> &str {
         match self {
             Self::Allocated(name) => name.as_str(),
             Self::Literal(name) => name,
+            Self::Interned(name) => name.as_str(),
         }
     }
 }
 
 impl PartialEq for ImmutableString {
     fn eq(&self, other: &Self) -> bool {
-        self.as_str() == other.as_str()
+        match (self, other) {
+            (Self::Interned(a), Self::Interned(b)) => a == b,
+            _ => self.as_str() == other.as_str(),
+        }
     }
 }
 
@@ -177,7 +191,13 @@ impl Eq for ImmutableString {}
 
 impl std::hash::Hash for ImmutableString {
     fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
-        self.as_str().hash(state)
+        // Interned strings write the precomputed hash, other variants should
+        // write the same value to keep the hash consistent with equality
+        let hash = match self {
+            Self::Interned(name) => name.entry().hash,
+            _ => string_hash(self.as_str()),
+        };
+        state.write_u64(hash)
     }
 }
 
@@ -199,9 +219,155 @@ impl From<String> for ImmutableString {
     }
 }
 
+/// 64-bit FNV-1a hash, it is a `const fn` to precompute hashes of the
+/// interned literals at compile time
+const fn string_hash(s: &str) -> u64 {
+    let bytes = s.as_bytes();
+    let mut hash = 0xcbf29ce484222325u64;
+    let mut i = 0;
+    while i < bytes.len() {
+        hash ^= bytes[i] as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+        i += 1;
+    }
+    hash
+}
+
+/// Entry of the intern table. Not intended to be used directly, it is public
+/// to construct `const` interned literals, see [InternedString::from_static].
+#[derive(Debug)]
+pub struct InternedEntry {
+    hash: u64,
+    value: std::borrow::Cow<'static, str>,
+}
+
+impl InternedEntry {
+    pub const fn literal(s: &'static str) -> Self {
+        Self{ hash: string_hash(s), value: std::borrow::Cow::Borrowed(s) }
+    }
+}
+
+impl std::borrow::Borrow<str> for InternedKey {
+    fn borrow(&self) -> &str {
+        &self.0.value
+    }
+}
+
+#[derive(Debug)]
+struct InternedKey(Arc<InternedEntry>);
+
+impl PartialEq for InternedKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.value == other.0.value
+    }
+}
+
+impl Eq for InternedKey {}
+
+impl std::hash::Hash for InternedKey {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        let value: &str = &self.0.value;
+        value.hash(state)
+    }
+}
+
+/// Table of the interned strings. Strings which are referenced by the table
+/// only are removed when the table grows twice since the previous cleanup.
+#[derive(Default)]
+struct InternTable {
+    strings: HashSet<InternedKey>,
+    cleanup_size: usize,
+}
+
+impl InternTable {
+    const MIN_CLEANUP_SIZE: usize = 1024;
+
+    fn get(&self, s: &str) -> Option<Arc<InternedEntry>> {
+        self.strings.get(s).map(|key| key.0.clone())
+    }
+
+    fn intern(&mut self, s: &str) -> Arc<InternedEntry> {
+        if let Some(entry) = self.get(s) {
+            return entry;
+        }
+        if self.strings.len() >= self.cleanup_size.max(Self::MIN_CLEANUP_SIZE) {
+            self.strings.retain(|key| Arc::strong_count(&key.0) > 1);
+            self.cleanup_size = self.strings.len() * 2;
+        }
+        let entry = Arc::new(InternedEntry{ hash: string_hash(s), value: s.to_string().into() });
+        self.strings.insert(InternedKey(entry.clone()));
+        entry
+    }
+
+    fn global() -> &'static RwLock<InternTable> {
+        static TABLE: OnceLock<RwLock<InternTable>> = OnceLock::new();
+        TABLE.get_or_init(|| RwLock::new(InternTable::default()))
+    }
+}
+
+#[derive(Clone)]
+enum InternedRef {
+    Static(&'static InternedEntry),
+    Shared(Arc<InternedEntry>),
+}
+
+/// String stored in the global intern table or interned at compile time.
+/// Interned strings with the same content share the same memory. Strings are
+/// compared by pointer first and by the precomputed hash next, thus the
+/// content is compared only for the equal strings which are interned
+/// separately: a `const` literal and a string from the table.
+#[derive(Clone)]
+pub struct InternedString(InternedRef);
+
+impl InternedString {
+    pub fn new(s: &str) -> Self {
+        // Vocabulary is usually small, thus most of the strings are in the
+        // table already and the shared lock is enough to find them
+        let table = InternTable::global();
+        let entry = table.read().unwrap_or_else(|e| e.into_inner()).get(s);
+        let entry = entry.unwrap_or_else(|| table.write().unwrap_or_else(|e| e.into_inner()).intern(s));
+        Self(InternedRef::Shared(entry))
+    }
+
+    /// Constructs interned string from the entry created at compile time.
+    /// Used by [crate::sym!] to construct `const` symbols.
+    pub const fn from_static(entry: &'static InternedEntry) -> Self {
+        Self(InternedRef::Static(entry))
+    }
+
+    fn entry(&self) -> &InternedEntry {
+        match &self.0 {
+            InternedRef::Static(entry) => entry,
+            InternedRef::Shared(entry) => entry,
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.entry().value
+    }
+}
+
+impl PartialEq for InternedString {
+    fn eq(&self, other: &Self) -> bool {
+        let (a, b) = (self.entry(), other.entry());
+        std::ptr::eq(a, b) || (a.hash == b.hash && a.value == b.value)
+    }
+}
+
+impl Eq for InternedString {}
+
+impl std::fmt::Debug for InternedString {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self.as_str())
+    }
+}
+
+/// Array which is either a static literal or a reference counted allocated
+/// array. Cloning doesn't copy the elements: allocated array is shared
+/// between clones and copied only when one of them is modified.
 #[derive(Debug, Clone)]
 pub enum CowArray<T: 'static> {
-    Allocated(Box<[T]>),
+    Allocated(Rc<Vec<T>>),
     Literal(&'static [T]),
 }
 
@@ -213,16 +379,16 @@ impl<T: 'static> CowArray<T> {
 
     pub fn as_slice(&self) -> &[T] {
         match self {
-            Self::Allocated(array) => &*array,
+            Self::Allocated(array) => array.as_slice(),
             Self::Literal(array) => array,
         }
     }
 
     pub fn as_slice_mut(&mut self) -> &mut [T] where T: Clone {
         match self {
-            Self::Allocated(array) => &mut *array,
+            Self::Allocated(array) => Rc::make_mut(array).as_mut_slice(),
             Self::Literal(array) => {
-                *self = Self::Allocated((*array).into());
+                *self = Self::Allocated(Rc::new(array.to_vec()));
                 self.as_slice_mut()
             }
         }
@@ -235,11 +401,16 @@ impl<T: 'static> CowArray<T> {
     pub fn iter(&self) -> impl Iterator<Item=&T> {
         self.as_slice().iter()
     }
+
+    /// Returns true if both arrays point to the same memory.
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        std::ptr::eq(self.as_slice(), other.as_slice())
+    }
 }
 
 impl<T: PartialEq> PartialEq for CowArray<T> {
     fn eq(&self, other: &Self) -> bool {
-        self.as_slice() == other.as_slice()
+        self.ptr_eq(other) || self.as_slice() == other.as_slice()
     }
 }
 
@@ -264,20 +435,21 @@ impl<T: 'static> From<&'static [T]> for CowArray<T> {
 
 impl<T, const N: usize> From<[T; N]> for CowArray<T> {
     fn from(a: [T; N]) -> Self {
-        CowArray::Allocated(Box::new(a))
+        CowArray::Allocated(Rc::new(a.into()))
     }
 }
 
 impl<T> From<Vec<T>> for CowArray<T> {
     fn from(v: Vec<T>) -> Self {
-        CowArray::Allocated(v.into_boxed_slice())
+        CowArray::Allocated(Rc::new(v))
     }
 }
 
 impl<T: Clone> Into<Vec<T>> for CowArray<T> {
     fn into(self) -> Vec<T> {
         match self {
-            Self::Allocated(array) => array.into(),
+            Self::Allocated(array) => Rc::try_unwrap(array)
+                .unwrap_or_else(|array| (*array).clone()),
             Self::Literal(array) => array.into(),
         }
     }
@@ -318,6 +490,44 @@ pub fn write_mapping<A, B, I>(f: &mut std::fmt::Formatter, it: I) -> std::fmt::R
 mod test {
     use super::*;
 
+    #[test]
+    fn interned_string_is_shared() {
+        let a = ImmutableString::interned("interned_string_is_shared");
+        let b = ImmutableString::interned(&String::from("interned_string_is_shared"));
+        match (&a, &b) {
+            (ImmutableString::Interned(a), ImmutableString::Interned(b)) =>
+                assert!(std::ptr::eq(a.entry(), b.entry())),
+            _ => panic!("Interned strings are expected"),
+        }
+        assert_eq!(a, b);
+        assert_eq!(a, ImmutableString::Literal("interned_string_is_shared"));
+        assert_eq!(a.to_string(), "interned_string_is_shared");
+    }
+
+    #[test]
+    fn static_interned_string_equals_shared() {
+        static ENTRY: InternedEntry = InternedEntry::literal("static_interned_string");
+        let literal = ImmutableString::Interned(InternedString::from_static(&ENTRY));
+        let shared = ImmutableString::interned("static_interned_string");
+        assert_eq!(literal, shared);
+        assert_ne!(literal, ImmutableString::interned("static_interned_string_"));
+
+        use std::hash::{BuildHasher, RandomState};
+        let hasher = RandomState::new();
+        assert_eq!(hasher.hash_one(&literal), hasher.hash_one(&shared));
+    }
+
+    #[test]
+    fn interned_string_hash_is_consistent() {
+        use std::hash::{BuildHasher, RandomState};
+        let hasher = RandomState::new();
+        let interned = ImmutableString::interned("hash_is_consistent");
+        let literal = ImmutableString::Literal("hash_is_consistent");
+        let allocated = ImmutableString::Allocated("hash_is_consistent".into());
+        assert_eq!(hasher.hash_one(&interned), hasher.hash_one(&literal));
+        assert_eq!(hasher.hash_one(&interned), hasher.hash_one(&allocated));
+    }
+
     #[test]
     fn list_map_eq() {
         let a = ListMap::from(vec![("A", 1), ("B", 2)]);
@@ -561,6 +561,7 @@ fn query<'a, T: Space>(space: T, prev: Option<Rc<RefCell<Stack>>>, to_eval: Atom
     let var_x = &VariableAtom::new("X").make_unique();
     let query = Atom::expr([EQUAL_SYMBOL, to_eval.clone(), Atom::Variable(var_x.clone())]);
     let results = space.query(&query);
+    crate::metta::runner::coverage::record_call(&query, &results);
     log::debug!("interpreter::query: query: {}", query);
     log::debug!("interpreter::query: results.len(): {}, bindings.len(): {}, results: {} bindings: {}",
         results.len(), bindings.len(), results, bindings);
@@ -1007,6 +1008,25 @@ fn match_types(type1: &Atom, type2: &Atom, bindings: Bindings) -> Result<MatchRe
     }
 }
 
+/// Matches the `actual` type with the `expected` one, if they are not matched
+/// then tries to match super types of the `actual` type declared in the
+/// `space` using `:<`.
+fn match_types_or_super_types(space: &DynSpace, actual: &Atom, expected: &Atom, bindings: Bindings) -> Result<MatchResultIter, MatchResultIter> {
+    let nomatch = match match_types(actual, expected, bindings.clone()) {
+        Ok(matches) => return Ok(matches),
+        Err(nomatch) => nomatch,
+    };
+    let matches: Vec<Bindings> = get_super_types(space, actual).into_iter().skip(1)
+        .filter_map(|super_type| match_types(&super_type, expected, bindings.clone()).ok())
+        .flatten()
+        .collect();
+    if matches.is_empty() {
+        Err(nomatch)
+    } else {
+        Ok(Box::new(matches.into_iter()))
+    }
+}
+
 fn check_alternatives(args: Atom, bindings: Bindings) -> MettaResult {
     let expr = match_atom!{
         args ~ [Atom::Expression(expr)] => expr,
@@ -1080,6 +1100,18 @@ fn interpret_expression(args: Atom, bindings: Bindings) -> MettaResult {
                 for res in type_check_results {
                     log::debug!("interpret_expression: function type check: expr: {} type: {:?}", expr, res);
                     match res {
+                        (Ok(op_type), bindings) if split_constrained_type(&op_type).is_some() => {
+                            let (constraint, _) = split_constrained_type(&op_type).unwrap();
+                            match resolve_instance_method(&expr, constraint, space_ref, &bindings) {
+                                Ok(call) => {
+                                    let result = Atom::Variable(VariableAtom::new("result").make_unique());
+                                    return once((Atom::expr([CHAIN_SYMBOL, Atom::expr([METTA_SYMBOL, call, expr_typ, space.clone()]), result.clone(),
+                                        return_atom(result)
+                                    ]), bindings));
+                                },
+                                Err(err) => errors.push((err, bindings)),
+                            }
+                        },
                         (Ok(op_type), bindings) => {
                             let reduced = Atom::Variable(VariableAtom::new("reduced").make_unique());
                             let result = Atom::Variable(VariableAtom::new("result").make_unique());
@@ -1092,6 +1124,17 @@ fn interpret_expression(args: Atom, bindings: Bindings) -> MettaResult {
                         (Err(err), bindings) => errors.push((err, bindings)),
                     }
                 }
+                // The same error can be reported for the several types of the
+                // function, for instance when a type class method is declared
+                // for a grounded function
+                let mut reported: Vec<Atom> = Vec::new();
+                errors.retain(|(err, _)| {
+                    let duplicate = reported.contains(err);
+                    if !duplicate {
+                        reported.push(err.clone());
+                    }
+                    !duplicate
+                });
                 Box::new(errors.into_iter()
                     .map(move |(err, bindings)| (return_atom(err), bindings)))
             } else {
@@ -1104,6 +1147,30 @@ fn interpret_expression(args: Atom, bindings: Bindings) -> MettaResult {
     }
 }
 
+/// Replaces the operation of the type class method call by the method
+/// implementation from the instance which matches the constraint. Returns
+/// `BadType` error when there is no instance or instance cannot be chosen
+/// unambiguously, and an error message when instance doesn't conform to the
+/// class declaration.
+fn resolve_instance_method(expr: &Atom, constraint: &Atom, space: &DynSpace, bindings: &Bindings) -> Result<Atom, Atom> {
+    let (op, args) = match atom_as_slice(expr) {
+        Some([op, args @ ..]) => (op, args),
+        _ => panic!("Unexpected state"),
+    };
+    let constraint = apply_bindings_to_atom_move(constraint.clone(), bindings);
+    let mut methods = get_instance_methods(space, &constraint, op);
+    log::debug!("resolve_instance_method: expr: {}, constraint: {}, methods: [{}]", expr, constraint, methods.iter().format(", "));
+    match methods.len() {
+        1 => {
+            check_instance(space, &constraint).map_err(|err| error_msg(expr.clone(), err))?;
+            let mut call = vec![methods.remove(0)];
+            call.extend(args.iter().cloned());
+            Ok(Atom::expr(call))
+        },
+        _ => Err(error_atom(expr.clone(), BAD_TYPE_SYMBOL)),
+    }
+}
+
 fn interpret_tuple(args: Atom, bindings: Bindings) -> MettaResult {
     let (expr, space) = match_atom!{
         args ~ [Atom::Expression(expr), space]
@@ -1183,7 +1250,8 @@ fn check_if_function_type_is_applicable<'a>(expr: &'a Atom, op_type: Atom, expec
         Some([_op, actual_args @ ..]) => actual_args,
         _ => panic!("Unexpected state"),
     };
-    let arg_types: ExpressionAtom = op_type.clone().try_into().unwrap();
+    let fn_type = split_constrained_type(&op_type).map_or(&op_type, |(_, typ)| typ);
+    let arg_types: ExpressionAtom = fn_type.clone().try_into().unwrap();
     let mut arg_types = arg_types.into_children(); 
     let arrow = arg_types.remove(0);
     assert_eq!(arrow, ARROW_SYMBOL);
@@ -1226,7 +1294,7 @@ fn check_if_function_type_is_applicable_<'a>(expr: &'a Atom, op_type: Atom, mut
                     // in metta_impl() Rust function which compares it with passed expression
                     // meta-type. Thus if expected_type is meta-type it is always first compared to the
                     // expression's meta-type and type check finishes.
-                    match match_types(&ret_type, expected_type, bindings) {
+                    match match_types_or_super_types(space, &ret_type, expected_type, bindings) {
                         Ok(matches) => Box::new(matches.map(move |bindings| (Ok(op_type.clone()), bindings))),
                         Err(nomatch) => Box::new(nomatch.map(move |bindings| (Err(error_atom(expr.clone(), BAD_TYPE_SYMBOL)), bindings))),
                     }
@@ -1281,7 +1349,8 @@ fn interpret_args(args_: Atom, bindings: Bindings) -> MettaResult {
     let types_tail = types;
     if args.children().is_empty() {
         if types_tail.is_empty() {
-            match match_types(&types_head, &ret_type, bindings) {
+            let space = space.as_gnd::<DynSpace>().unwrap();
+            match match_types_or_super_types(space, &types_head, &ret_type, bindings) {
                 Ok(matches) => Box::new(matches.map(move |bindings| (return_atom(Atom::expr([Atom::sym("Ok"), Atom::Expression(args.clone())])), bindings))),
                 Err(nomatch) => Box::new(nomatch.map(move |bindings| (return_atom(error_atom(atom.clone(), BAD_TYPE_SYMBOL)), bindings))),
             }
@@ -20,9 +20,18 @@ pub const HAS_TYPE_SYMBOL : Atom = sym!(":");
 pub const SUB_TYPE_SYMBOL : Atom = sym!(":<");
 pub const EQUAL_SYMBOL : Atom = sym!("=");
 pub const ARROW_SYMBOL : Atom = sym!("->");
+pub const CONSTRAINT_SYMBOL : Atom = sym!("=>");
+pub const INSTANCE_SYMBOL : Atom = sym!("@instance");
+pub const CLASS_SYMBOL : Atom = sym!("@class");
 pub const ERROR_SYMBOL : Atom = sym!("Error");
 pub const BAD_TYPE_SYMBOL : Atom = sym!("BadType");
+pub const SUBTYPE_CYCLE_SYMBOL : Atom = sym!("SubtypeCycle");
 pub const INCORRECT_NUMBER_OF_ARGUMENTS_SYMBOL : Atom = sym!("IncorrectNumberOfArguments");
+pub const ARITHMETIC_OVERFLOW_SYMBOL : Atom = sym!("ArithmeticOverflow");
+pub const DIVISION_BY_ZERO_SYMBOL : Atom = sym!("DivisionByZero");
+pub const STEP_LIMIT_EXCEEDED_SYMBOL : Atom = sym!("StepLimitExceeded");
+pub const TIME_LIMIT_EXCEEDED_SYMBOL : Atom = sym!("TimeLimitExceeded");
+pub const END_OF_INPUT_SYMBOL : Atom = sym!("EndOfInput");
 pub const NOT_REDUCIBLE_SYMBOL : Atom = sym!("NotReducible");
 pub const NO_VALID_ALTERNATIVES : Atom = sym!("NoValidAlternatives");
 
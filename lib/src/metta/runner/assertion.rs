@@ -0,0 +1,75 @@
+//! Assertion listener receives the outcome of each assertion made by MeTTa
+//! operations like `assertEqual`. It allows host to count passed and failed
+//! assertions, for example to report results of the MeTTa tests.
+
+use std::sync::Mutex;
+
+/// Outcome of a single assertion
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AssertionOutcome {
+    /// Text of the assertion expression, for example `(assertEqual (foo) A)`
+    pub assertion: String,
+    /// Failure report or `None` if assertion passed
+    pub failure: Option<String>,
+}
+
+impl AssertionOutcome {
+    pub fn is_passed(&self) -> bool {
+        self.failure.is_none()
+    }
+}
+
+/// Receives the outcomes of assertions made by MeTTa operations
+pub trait AssertionListener: std::fmt::Debug + Send + Sync {
+    fn on_assertion(&self, outcome: AssertionOutcome);
+}
+
+/// Listener which collects the outcomes in memory
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+/// use hyperon::metta::runner::{Metta, EnvBuilder};
+/// use hyperon::metta::runner::assertion::AssertionCollector;
+/// use hyperon::metta::text::SExprParser;
+///
+/// let assertions = Arc::new(AssertionCollector::new());
+/// let metta = Metta::new(Some(EnvBuilder::test_env()));
+/// metta.set_assertion_listener(Some(assertions.clone()));
+/// metta.run(SExprParser::new("!(assertEqual (+ 1 1) 2) !(assertEqual A B)")).unwrap();
+///
+/// assert_eq!(assertions.passed(), 1);
+/// assert_eq!(assertions.failed(), 1);
+/// ```
+#[derive(Debug, Default)]
+pub struct AssertionCollector {
+    outcomes: Mutex<Vec<AssertionOutcome>>,
+}
+
+impl AssertionCollector {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Returns number of passed assertions
+    pub fn passed(&self) -> usize {
+        self.outcomes.lock().unwrap().iter().filter(|o| o.is_passed()).count()
+    }
+
+    /// Returns number of failed assertions
+    pub fn failed(&self) -> usize {
+        self.outcomes.lock().unwrap().iter().filter(|o| !o.is_passed()).count()
+    }
+
+    /// Returns all collected outcomes in order and clears the collector
+    pub fn take(&self) -> Vec<AssertionOutcome> {
+        std::mem::take(&mut *self.outcomes.lock().unwrap())
+    }
+}
+
+impl AssertionListener for AssertionCollector {
+    fn on_assertion(&self, outcome: AssertionOutcome) {
+        self.outcomes.lock().unwrap().push(outcome);
+    }
+}
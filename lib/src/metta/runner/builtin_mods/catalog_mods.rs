@@ -3,6 +3,7 @@ use crate::space::grounding::GroundingSpace;
 use crate::metta::{ARROW_SYMBOL, ATOM_TYPE_SYMBOL, UNIT_TYPE};
 use crate::metta::runner::{Metta, ModuleLoader, RunContext, DynSpace};
 use crate::metta::runner::pkg_mgmt::{UpdateMode, ManagedCatalog};
+use crate::metta::runner::output::OutputStream;
 use crate::metta::runner::stdlib::{regex, unit_result};
 
 //DISCUSSION: We want to expose more of the pkg_mgmt / catalog system to MeTTa through programmatic
@@ -106,14 +107,15 @@ impl CustomExecute for CatalogListOp {
             return Err(ExecError::from(arg_error));
         };
 
-        fn list_catalog(cat: &dyn crate::metta::runner::ModuleCatalog) {
+        let output = self.metta.output_sink();
+        let list_catalog = |cat: &dyn crate::metta::runner::ModuleCatalog| {
             if let Some(cat_iter) = cat.list() {
-                println!("{}:", cat.display_name());
+                output.write(OutputStream::Stdout, &format!("{}:\n", cat.display_name()));
                 for desc in cat_iter {
-                    println!("   {desc}");
+                    output.write(OutputStream::Stdout, &format!("   {desc}\n"));
                 }
             }
-        }
+        };
 
         let mut found_one = false;
         if cat_name == "all" || cat_name == "specified-mods" {
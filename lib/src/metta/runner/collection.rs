@@ -0,0 +1,284 @@
+use crate::*;
+use crate::atom::serial;
+use crate::common::collections::write_mapping;
+use super::number::Number;
+
+use std::fmt::Display;
+use std::hash::{Hash, Hasher};
+
+pub const ATOM_TYPE_MAP : Atom = sym!("Map");
+pub const ATOM_TYPE_SET : Atom = sym!("Set");
+
+/// Wrapper which makes any atom usable as a key of [Map] or an item of [Set].
+/// Hash of the grounded atom is calculated using its serialized value, thus
+/// grounded atoms which are not serializable are compared by equality only.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AtomKey(pub Atom);
+
+impl Hash for AtomKey {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        hash_atom(&self.0, state)
+    }
+}
+
+fn hash_atom<H: Hasher>(atom: &Atom, state: &mut H) {
+    match atom {
+        Atom::Symbol(sym) => {
+            state.write_u8(0);
+            sym.hash(state);
+        },
+        Atom::Variable(var) => {
+            state.write_u8(1);
+            var.hash(state);
+        },
+        Atom::Expression(expr) => {
+            state.write_u8(2);
+            state.write_usize(expr.children().len());
+            expr.children().iter().for_each(|child| hash_atom(child, state));
+        },
+        Atom::Grounded(gnd) => {
+            state.write_u8(3);
+            let _ = gnd.serialize(&mut GroundedHasher(state));
+        },
+    }
+}
+
+/// Serializer which writes grounded value into a hasher. Integer and float
+/// values are written in the same way because equal numbers should have equal
+/// hashes.
+struct GroundedHasher<'a, H: Hasher>(&'a mut H);
+
+impl<H: Hasher> serial::Serializer for GroundedHasher<'_, H> {
+    fn serialize_bool(&mut self, v: bool) -> serial::Result {
+        self.0.write_u8(v as u8);
+        Ok(())
+    }
+    fn serialize_i64(&mut self, v: i64) -> serial::Result {
+        self.serialize_f64(v as f64)
+    }
+    fn serialize_f64(&mut self, v: f64) -> serial::Result {
+        // -0.0 is equal to 0.0
+        let v = if v == 0.0 { 0.0 } else { v };
+        self.0.write_u64(v.to_bits());
+        Ok(())
+    }
+    fn serialize_str(&mut self, v: &str) -> serial::Result {
+        v.hash(self.0);
+        Ok(())
+    }
+    fn serialize_number_str(&mut self, v: &str) -> serial::Result {
+        // Big numbers are equal to the floats they are rounded to
+        let n = Number::from_rational_str(v).map_err(|_| serial::Error::NotSupported)?;
+        self.serialize_f64(n.into())
+    }
+}
+
+/// Persistent hash map of atoms. Modification returns new map which shares
+/// unchanged part of the data with the original one.
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct Map(im::HashMap<AtomKey, Atom>);
+
+impl Map {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn from_atom(atom: &Atom) -> Option<Self> {
+        atom.as_gnd::<Map>().cloned()
+    }
+
+    pub fn insert(&self, key: Atom, value: Atom) -> Self {
+        Self(self.0.update(AtomKey(key), value))
+    }
+
+    pub fn remove(&self, key: &Atom) -> Self {
+        Self(self.0.without(&AtomKey(key.clone())))
+    }
+
+    pub fn get(&self, key: &Atom) -> Option<&Atom> {
+        self.0.get(&AtomKey(key.clone()))
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns map entries sorted by the textual representation of the keys.
+    /// Sorting makes the order independent of the hash function.
+    pub fn entries(&self) -> Vec<(&Atom, &Atom)> {
+        let mut entries: Vec<(String, (&Atom, &Atom))> = self.0.iter()
+            .map(|(k, v)| (k.0.to_string(), (&k.0, v)))
+            .collect();
+        entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+        entries.into_iter().map(|(_, entry)| entry).collect()
+    }
+}
+
+impl FromIterator<(Atom, Atom)> for Map {
+    fn from_iter<I: IntoIterator<Item=(Atom, Atom)>>(iter: I) -> Self {
+        Self(iter.into_iter().map(|(k, v)| (AtomKey(k), v)).collect())
+    }
+}
+
+impl Grounded for Map {
+    fn type_(&self) -> Atom {
+        ATOM_TYPE_MAP
+    }
+
+    fn as_match(&self) -> Option<&dyn CustomMatch> {
+        Some(self)
+    }
+}
+
+impl CustomMatch for Map {
+    fn match_(&self, other: &Atom) -> matcher::MatchResultIter {
+        match_by_bidirectional_equality(self, other)
+    }
+}
+
+impl Display for Map {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write_mapping(f, self.entries().into_iter())
+    }
+}
+
+/// Persistent hash set of atoms. Modification returns new set which shares
+/// unchanged part of the data with the original one.
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct Set(im::HashSet<AtomKey>);
+
+impl Set {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn from_atom(atom: &Atom) -> Option<Self> {
+        atom.as_gnd::<Set>().cloned()
+    }
+
+    pub fn insert(&self, item: Atom) -> Self {
+        Self(self.0.update(AtomKey(item)))
+    }
+
+    pub fn remove(&self, item: &Atom) -> Self {
+        Self(self.0.without(&AtomKey(item.clone())))
+    }
+
+    pub fn contains(&self, item: &Atom) -> bool {
+        self.0.contains(&AtomKey(item.clone()))
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        Self(self.0.clone().union(other.0.clone()))
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        Self(self.0.clone().intersection(other.0.clone()))
+    }
+
+    pub fn difference(&self, other: &Self) -> Self {
+        Self(self.0.clone().relative_complement(other.0.clone()))
+    }
+
+    /// Returns set items sorted by their textual representation. Sorting
+    /// makes the order independent of the hash function.
+    pub fn items(&self) -> Vec<&Atom> {
+        let mut items: Vec<(String, &Atom)> = self.0.iter()
+            .map(|item| (item.0.to_string(), &item.0))
+            .collect();
+        items.sort_by(|(a, _), (b, _)| a.cmp(b));
+        items.into_iter().map(|(_, item)| item).collect()
+    }
+}
+
+impl FromIterator<Atom> for Set {
+    fn from_iter<I: IntoIterator<Item=Atom>>(iter: I) -> Self {
+        Self(iter.into_iter().map(AtomKey).collect())
+    }
+}
+
+impl Grounded for Set {
+    fn type_(&self) -> Atom {
+        ATOM_TYPE_SET
+    }
+
+    fn as_match(&self) -> Option<&dyn CustomMatch> {
+        Some(self)
+    }
+}
+
+impl CustomMatch for Set {
+    fn match_(&self, other: &Atom) -> matcher::MatchResultIter {
+        match_by_bidirectional_equality(self, other)
+    }
+}
+
+impl Display for Set {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{{")
+            .and_then(|()| self.items().into_iter().enumerate().try_for_each(|(i, item)| {
+                let comma = if i == 0 { "" } else { "," };
+                write!(f, "{} {}", comma, item)
+            }))
+            .and_then(|()| write!(f, " }}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metta::runner::number::Number;
+    use num_rational::BigRational;
+
+    #[test]
+    fn map_is_persistent() {
+        let map = Map::new().insert(sym!("a"), sym!("A"));
+        let updated = map.insert(sym!("b"), sym!("B")).remove(&sym!("a"));
+
+        assert_eq!(map.get(&sym!("a")), Some(&sym!("A")));
+        assert_eq!(updated.get(&sym!("a")), None);
+        assert_eq!(updated.get(&sym!("b")), Some(&sym!("B")));
+        assert_eq!(format!("{}", map.insert(expr!("x" "y"), sym!("B"))), "{ (x y): B, a: A }");
+    }
+
+    #[test]
+    fn set_uses_atom_equality() {
+        let set: Set = [Atom::gnd(Number::Integer(1)), expr!("a" ("b"))].into_iter().collect();
+
+        assert!(set.contains(&Atom::gnd(Number::Float(1.0))));
+        assert!(set.contains(&expr!("a" ("b"))));
+        assert!(!set.contains(&expr!("a" "b")));
+        assert_eq!(format!("{}", set), "{ (a (b)), 1 }");
+    }
+
+    #[test]
+    fn map_finds_rational_key_by_equal_float() {
+        let half = Number::from(BigRational::new(1.into(), 2.into()));
+        let map = Map::new().insert(Atom::gnd(half), sym!("half"));
+
+        assert_eq!(map.get(&Atom::gnd(Number::Float(0.5))), Some(&sym!("half")));
+        assert_eq!(map.get(&Atom::gnd(Number::Float(0.25))), None);
+    }
+
+    #[test]
+    fn set_operations() {
+        let a: Set = [sym!("a"), sym!("b")].into_iter().collect();
+        let b: Set = [sym!("b"), sym!("c")].into_iter().collect();
+
+        assert_eq!(a.union(&b).items(), vec![&sym!("a"), &sym!("b"), &sym!("c")]);
+        assert_eq!(a.intersection(&b).items(), vec![&sym!("b")]);
+        assert_eq!(a.difference(&b).items(), vec![&sym!("a")]);
+    }
+}
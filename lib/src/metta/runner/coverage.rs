@@ -0,0 +1,335 @@
+//! Coverage collector records which `=` definitions of the MeTTa source files
+//! are matched by the interpreter when it evaluates function calls. It allows
+//! host to find definitions which are never exercised by the tests. Source
+//! files of the modules loaded while the collector is set are tracked
+//! automatically, see [crate::metta::runner::modules::ModuleLoader::source_paths].
+
+use std::cell::RefCell;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::*;
+use crate::atom::matcher::{match_atoms, apply_bindings_to_atom_move, BindingsSet};
+use crate::metta::EQUAL_SYMBOL;
+use crate::metta::text::{SExprParser, Tokenizer};
+use crate::metta::types::module_space_deps;
+use crate::metta::runner::modules::MettaMod;
+use crate::space::DynSpace;
+
+/// Coverage of a single `=` definition
+#[derive(Clone, Debug, PartialEq)]
+pub struct DefinitionCoverage {
+    pub atom: Atom,
+    /// One-based number of the line where the definition starts
+    pub line: usize,
+    /// Number of times the definition was matched by a function call
+    pub hits: usize,
+}
+
+/// Coverage of the definitions of a single source file
+#[derive(Clone, Debug, PartialEq)]
+pub struct FileCoverage {
+    pub path: PathBuf,
+    pub definitions: Vec<DefinitionCoverage>,
+}
+
+impl FileCoverage {
+    pub fn covered(&self) -> usize {
+        self.definitions.iter().filter(|d| d.hits > 0).count()
+    }
+}
+
+/// Collects the coverage of the definitions from the tracked source files
+///
+/// # Examples
+///
+/// ```
+/// use std::rc::Rc;
+/// use std::path::Path;
+/// use hyperon::metta::runner::{Metta, EnvBuilder};
+/// use hyperon::metta::runner::modules::ModId;
+/// use hyperon::metta::runner::coverage::CoverageCollector;
+/// use hyperon::metta::text::SExprParser;
+///
+/// let program = "
+///     (= (inc $x) (+ $x 1))
+///     (= (dec $x) (- $x 1))
+///     !(inc 1)
+/// ";
+/// let metta = Metta::new(Some(EnvBuilder::test_env()));
+/// let coverage = Rc::new(CoverageCollector::new());
+/// coverage.track_source(Path::new("test.metta"), program, &metta.tokenizer().borrow(), &metta.module_space(ModId::TOP)).unwrap();
+/// metta.set_coverage_collector(Some(coverage.clone()));
+/// metta.run(SExprParser::new(program)).unwrap();
+///
+/// let files = coverage.files();
+/// assert_eq!(files[0].definitions.iter().map(|d| (d.line, d.hits)).collect::<Vec<_>>(), vec![(2, 1), (3, 0)]);
+/// ```
+#[derive(Debug, Default)]
+pub struct CoverageCollector {
+    files: RefCell<Vec<TrackedFile>>,
+}
+
+/// Source file and the spaces its definitions are loaded into
+#[derive(Debug)]
+struct TrackedFile {
+    coverage: FileCoverage,
+    spaces: Vec<DynSpace>,
+}
+
+impl CoverageCollector {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Parses the source text and starts tracking its top-level `=`
+    /// definitions. The `tokenizer` should be the same as the tokenizer used
+    /// to run the source, otherwise grounded atoms of the definitions are not
+    /// matched. The `space` is the space the source is loaded into, only the
+    /// calls evaluated in this space or in the spaces which import it are
+    /// counted. When the same path is tracked again the definitions are
+    /// counted for the new space as well.
+    pub fn track_source(&self, path: &Path, text: &str, tokenizer: &Tokenizer, space: &DynSpace) -> Result<(), String> {
+        if let Some(file) = self.files.borrow_mut().iter_mut().find(|file| file.coverage.path == path) {
+            if !file.spaces.contains(space) {
+                file.spaces.push(space.clone());
+            }
+            return Ok(())
+        }
+        // Source ranges of the syntax nodes are byte offsets
+        let line_starts: Vec<usize> = std::iter::once(0)
+            .chain(text.bytes().enumerate().filter(|(_, b)| *b == b'\n').map(|(i, _)| i + 1))
+            .collect();
+        let mut parser = SExprParser::new(text);
+        let mut definitions = vec![];
+        while let Some(node) = parser.parse_to_syntax_tree()? {
+            let atom = match node.as_atom(tokenizer)? {
+                Some(atom) if is_definition(&atom) => atom,
+                _ => continue,
+            };
+            let line = line_starts.partition_point(|start| *start <= node.src_range.start);
+            definitions.push(DefinitionCoverage{ atom, line, hits: 0 });
+        }
+        let coverage = FileCoverage{ path: path.into(), definitions };
+        self.files.borrow_mut().push(TrackedFile{ coverage, spaces: vec![space.clone()] });
+        Ok(())
+    }
+
+    /// Tracks the source files of the module reported by its loader
+    pub(crate) fn track_module(&self, module: &MettaMod) {
+        let paths = module.loader().map_or(vec![], |loader| loader.source_paths());
+        for path in paths {
+            let result = std::fs::read_to_string(&path).map_err(|err| err.to_string())
+                .and_then(|text| self.track_source(&path, &text, &module.tokenizer().borrow(), &module.space()));
+            if let Err(err) = result {
+                log::warn!("Cannot track coverage of {}: {err}", path.display());
+            }
+        }
+    }
+
+    /// Returns coverage of the tracked files in order of tracking
+    pub fn files(&self) -> Vec<FileCoverage> {
+        self.files.borrow().iter().map(|file| file.coverage.clone()).collect()
+    }
+
+    /// Formats the coverage in the lcov tracefile format. Each definition is
+    /// reported both as a function and as a line.
+    pub fn to_lcov(&self) -> String {
+        let mut lcov = String::new();
+        for file in self.files.borrow().iter().map(|file| &file.coverage) {
+            lcov.push_str(&format!("TN:\nSF:{}\n", file.path.display()));
+            for def in file.definitions.iter() {
+                lcov.push_str(&format!("FN:{},{}\n", def.line, definition_name(&def.atom)));
+            }
+            for def in file.definitions.iter() {
+                lcov.push_str(&format!("FNDA:{},{}\n", def.hits, definition_name(&def.atom)));
+            }
+            lcov.push_str(&format!("FNF:{}\nFNH:{}\n", file.definitions.len(), file.covered()));
+            for def in file.definitions.iter() {
+                lcov.push_str(&format!("DA:{},{}\n", def.line, def.hits));
+            }
+            lcov.push_str(&format!("LF:{}\nLH:{}\nend_of_record\n", file.definitions.len(), file.covered()));
+        }
+        lcov
+    }
+
+    /// Counts the definitions which produced the results of the `(= <call> $X)`
+    /// query evaluated in the space. Each result is the matched definition
+    /// with the call arguments substituted, it is attributed to the first
+    /// tracked definition it is an instance of and which is not attributed
+    /// to another result yet. The space queries its own atoms and atoms of its
+    /// dependencies, thus only files loaded into these spaces are checked.
+    fn record_call(&self, space: &DynSpace, query: &Atom, results: &BindingsSet) {
+        if results.is_empty() {
+            return;
+        }
+        let spaces: Vec<DynSpace> = std::iter::once(space.clone()).chain(module_space_deps(space)).collect();
+        let mut files = self.files.borrow_mut();
+        let mut definitions: Vec<&mut DefinitionCoverage> = files.iter_mut()
+            .filter(|file| file.spaces.iter().any(|space| spaces.contains(space)))
+            .flat_map(|file| file.coverage.definitions.iter_mut())
+            .collect();
+        let mut attributed = vec![false; definitions.len()];
+        for bindings in results.iter() {
+            let matched = apply_bindings_to_atom_move(query.clone(), bindings);
+            let candidates: Vec<usize> = definitions.iter().enumerate()
+                .filter(|(_, def)| match_atoms(&def.atom, &matched).next().is_some())
+                .map(|(i, _)| i)
+                .collect();
+            let def = candidates.iter().find(|i| !attributed[**i]).or(candidates.first());
+            if let Some(&i) = def {
+                attributed[i] = true;
+                definitions[i].hits += 1;
+            }
+        }
+    }
+}
+
+fn is_definition(atom: &Atom) -> bool {
+    matches!(atom, Atom::Expression(expr) if expr.children().len() == 3 && expr.children()[0] == EQUAL_SYMBOL)
+}
+
+/// Returns name of the definition for the lcov report: `(= (foo $x) ...)`
+/// is named `(foo $x)`. Commas are not allowed in lcov function names.
+fn definition_name(atom: &Atom) -> String {
+    match atom {
+        Atom::Expression(expr) => expr.children()[1].to_string().replace(',', ";"),
+        _ => atom.to_string(),
+    }
+}
+
+thread_local! {
+    static CURRENT_COLLECTOR: RefCell<Option<(Rc<CoverageCollector>, DynSpace)>> = const { RefCell::new(None) };
+}
+
+/// Number of the collectors entered by all threads, it allows skipping the
+/// thread local lookup when the coverage is not collected
+static ENTERED_COLLECTORS: AtomicUsize = AtomicUsize::new(0);
+
+/// Makes the collector current for the interpreter steps performed in the
+/// `space` until the returned guard is dropped, see
+/// [crate::metta::runner::sandbox::enter_meter]
+pub(crate) fn enter_collector(collector: Rc<CoverageCollector>, space: DynSpace) -> CollectorGuard {
+    ENTERED_COLLECTORS.fetch_add(1, Ordering::Relaxed);
+    let prev = CURRENT_COLLECTOR.with(|current| current.replace(Some((collector, space))));
+    CollectorGuard(prev)
+}
+
+pub(crate) struct CollectorGuard(Option<(Rc<CoverageCollector>, DynSpace)>);
+
+impl Drop for CollectorGuard {
+    fn drop(&mut self) {
+        let prev = self.0.take();
+        CURRENT_COLLECTOR.with(|current| *current.borrow_mut() = prev);
+        ENTERED_COLLECTORS.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// Records the results of the `(= <call> $X)` query made by the interpreter
+/// to the current collector if any
+pub(crate) fn record_call(query: &Atom, results: &BindingsSet) {
+    if ENTERED_COLLECTORS.load(Ordering::Relaxed) == 0 {
+        return;
+    }
+    CURRENT_COLLECTOR.with(|current| {
+        if let Some((collector, space)) = &*current.borrow() {
+            collector.record_call(space, query, results);
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metta::runner::{Metta, EnvBuilder};
+    use crate::metta::runner::modules::ModId;
+
+    fn run_tracked(coverage: &Rc<CoverageCollector>, env: EnvBuilder, path: &str, program: &str) -> Metta {
+        let metta = Metta::new(Some(env));
+        coverage.track_source(Path::new(path), program, &metta.tokenizer().borrow(), &metta.module_space(ModId::TOP)).unwrap();
+        metta.set_coverage_collector(Some(coverage.clone()));
+        metta.run(SExprParser::new(program)).unwrap();
+        metta
+    }
+
+    fn collector(program: &str) -> (Metta, Rc<CoverageCollector>) {
+        let coverage = Rc::new(CoverageCollector::new());
+        let metta = run_tracked(&coverage, EnvBuilder::test_env(), "test.metta", program);
+        (metta, coverage)
+    }
+
+    fn hits(file: &FileCoverage) -> Vec<usize> {
+        file.definitions.iter().map(|d| d.hits).collect()
+    }
+
+    #[test]
+    fn coverage_of_definitions() {
+        let (_metta, coverage) = collector("
+            ; comment
+            (= (fact 0) 1)
+            (= (fact $n) (if (> $n 0) (* $n (fact (- $n 1))) 1))
+            (= (unused) A)
+            (: fact (-> Number Number))
+            !(assertEqual (fact 2) 2)
+        ");
+        let files = coverage.files();
+        assert_eq!(files[0].definitions.iter().map(|d| (d.line, d.hits > 0)).collect::<Vec<_>>(),
+            vec![(3, true), (4, true), (5, false)]);
+        assert_eq!(files[0].covered(), 2);
+    }
+
+    #[test]
+    fn coverage_is_counted_for_files_of_queried_space() {
+        let coverage = Rc::new(CoverageCollector::new());
+        let _a = run_tracked(&coverage, EnvBuilder::test_env(), "a.metta", "(= (foo) A) (= (bar) B) !(bar)");
+        let _b = run_tracked(&coverage, EnvBuilder::test_env(), "b.metta", "(= (foo) A) !(foo)");
+
+        let files = coverage.files();
+        assert_eq!(hits(&files[0]), vec![0, 1]);
+        assert_eq!(hits(&files[1]), vec![1]);
+    }
+
+    #[test]
+    fn coverage_of_imported_modules() {
+        let dir = std::env::temp_dir().join(format!("hyperon-coverage-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("lib.metta"), "(= (helper) H)\n(= (unused) U)\n").unwrap();
+
+        let coverage = Rc::new(CoverageCollector::new());
+        let env = EnvBuilder::test_env().set_working_dir(Some(&dir));
+        let _metta = run_tracked(&coverage, env, "test.metta", "!(import! &self lib) !(helper)");
+        let _ = std::fs::remove_dir_all(&dir);
+
+        let files = coverage.files();
+        assert_eq!(files.len(), 2);
+        assert!(files[1].path.ends_with("lib.metta"));
+        assert_eq!(hits(&files[1]), vec![1, 0]);
+    }
+
+    #[test]
+    fn coverage_lines_after_non_ascii_text() {
+        let (_metta, coverage) = collector("; комментарий с юникодом ✓\n(= (foo) \"строка\")\n(= (bar) B)\n!(foo)");
+        let files = coverage.files();
+        assert_eq!(files[0].definitions.iter().map(|d| (d.line, d.hits)).collect::<Vec<_>>(),
+            vec![(2, 1), (3, 0)]);
+    }
+
+    #[test]
+    fn coverage_counts_matched_definitions_only() {
+        let (_metta, coverage) = collector("
+            (= (foo 1) A)
+            (= (foo $x) B)
+            (= (foo 2) C)
+            !(foo 1)
+        ");
+        assert_eq!(hits(&coverage.files()[0]), vec![1, 1, 0]);
+    }
+
+    #[test]
+    fn lcov_report() {
+        let (_metta, coverage) = collector("(= (foo) A)\n(= (bar) B)\n!(foo)\n!(foo)");
+        assert_eq!(coverage.to_lcov(), "TN:\nSF:test.metta\nFN:1,(foo)\nFN:2,(bar)\nFNDA:2,(foo)\nFNDA:0,(bar)\nFNF:2\nFNH:1\nDA:1,2\nDA:2,0\nLF:2\nLH:1\nend_of_record\n");
+    }
+}
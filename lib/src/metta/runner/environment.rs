@@ -5,6 +5,11 @@ use std::fs;
 use std::sync::Arc;
 
 use crate::{sym, ExpressionAtom, metta::GroundingSpace};
+use crate::metta::runner::sandbox::SandboxProfile;
+use crate::metta::runner::output::{OutputSink, StdOutputSink};
+use crate::metta::runner::input::{InputSource, StdInputSource};
+#[cfg(feature = "pkg_mgmt")]
+use crate::metta::runner::sandbox::ModuleSource;
 
 #[cfg(feature = "pkg_mgmt")]
 use crate::metta::runner::pkg_mgmt::{ModuleCatalog, DirCatalog, LocalCatalog, FsModuleFormat, SingleFileModuleFmt, DirModuleFmt, git_catalog::*};
@@ -22,6 +27,11 @@ pub struct Environment {
     init_metta_path: Option<PathBuf>,
     working_dir: Option<PathBuf>,
     is_test: bool,
+    fs_allowed_dirs: Vec<PathBuf>,
+    args: Vec<String>,
+    sandbox: SandboxProfile,
+    output_sink: Arc<dyn OutputSink>,
+    input_source: Arc<dyn InputSource>,
     #[cfg(feature = "pkg_mgmt")]
     catalogs: Vec<Box<dyn ModuleCatalog>>,
     #[cfg(feature = "pkg_mgmt")]
@@ -74,6 +84,58 @@ impl Environment {
         self.init_metta_path.as_deref()
     }
 
+    /// Returns the directories which MeTTa file operations such as `read-file` are allowed to access
+    pub fn fs_allowed_dirs(&self) -> impl Iterator<Item=&'_ Path> + '_ {
+        self.fs_allowed_dirs.iter().map(|dir| dir.as_path())
+    }
+
+    /// Returns the arguments passed to the MeTTa program by the host, see [EnvBuilder::set_args]
+    pub fn args(&self) -> &[String] {
+        &self.args
+    }
+
+    /// Returns the [SandboxProfile] which restricts capabilities of the MeTTa code
+    pub fn sandbox(&self) -> &SandboxProfile {
+        &self.sandbox
+    }
+
+    /// Returns the default [OutputSink] of the runners using the environment
+    pub fn output_sink(&self) -> &Arc<dyn OutputSink> {
+        &self.output_sink
+    }
+
+    /// Returns the default [InputSource] of the runners using the environment
+    pub fn input_source(&self) -> &Arc<dyn InputSource> {
+        &self.input_source
+    }
+
+    /// Checks whether MeTTa file operations are allowed to access the `path`, and returns the
+    /// resolved absolute path if so
+    ///
+    /// Relative paths are resolved against the `working_dir`.  The path is canonicalized before
+    /// the check, so neither `..` components nor symbolic links can be used to escape an allowed
+    /// directory.  A path which does not exist yet is allowed if its parent directory is allowed,
+    /// and a dangling symbolic link is allowed only if its target is allowed.
+    pub fn check_fs_access(&self, path: &Path) -> Result<PathBuf, String> {
+        let denied = || format!("Access to {} is denied", path.display());
+        let resolved = canonicalize_for_access(&self.absolute_path(path)).ok_or_else(denied)?;
+        let allowed = self.fs_allowed_dirs.iter()
+            .filter_map(|dir| self.absolute_path(dir).canonicalize().ok())
+            .any(|dir| resolved.starts_with(dir));
+        if allowed {
+            Ok(resolved)
+        } else {
+            Err(denied())
+        }
+    }
+
+    fn absolute_path(&self, path: &Path) -> PathBuf {
+        match &self.working_dir {
+            Some(working_dir) => working_dir.join(path),
+            None => path.into(),
+        }
+    }
+
     /// Returns the [ModuleCatalog]s from the Environment, in search priority order
     #[cfg(feature = "pkg_mgmt")]
     pub fn catalogs(&self) -> impl Iterator<Item=&'_ dyn ModuleCatalog> + '_ {
@@ -94,6 +156,11 @@ impl Environment {
             init_metta_path: None,
             working_dir: std::env::current_dir().ok(),
             is_test: false,
+            fs_allowed_dirs: vec![],
+            args: vec![],
+            sandbox: SandboxProfile::new(),
+            output_sink: Arc::new(StdOutputSink),
+            input_source: Arc::new(StdInputSource),
             #[cfg(feature = "pkg_mgmt")]
             catalogs: vec![],
             #[cfg(feature = "pkg_mgmt")]
@@ -216,6 +283,54 @@ impl EnvBuilder {
         self
     }
 
+    /// Allows MeTTa file operations such as `read-file` and `write-file` to access the files
+    /// within the `path` directory and its subdirectories
+    ///
+    /// NOTE: File operations are denied for all paths unless at least one allowed directory is added.
+    /// A relative `path` is resolved against the `working_dir`
+    pub fn push_fs_allowed_dir<P: AsRef<Path>>(mut self, path: P) -> Self {
+        self.env.fs_allowed_dirs.push(path.as_ref().into());
+        self
+    }
+
+    /// Sets the arguments passed to the MeTTa program, for example the command-line arguments
+    /// following the program file.  MeTTa code reads them using the `&args` token or the
+    /// `get-args` operation
+    pub fn set_args<I: IntoIterator<Item=S>, S: Into<String>>(mut self, args: I) -> Self {
+        self.env.args = args.into_iter().map(|arg| arg.into()).collect();
+        self
+    }
+
+    /// Sets the [SandboxProfile] which restricts capabilities of the MeTTa code executed by
+    /// runners using the environment
+    ///
+    /// NOTE: If the profile denies the [crate::metta::runner::ModuleSource::FileSystem] source then the `working_dir`,
+    /// include paths and `#includePath` entries of the `environment.metta` are not used to search
+    /// for modules.  If it denies the [crate::metta::runner::ModuleSource::Git] source then git catalogs are not used.
+    pub fn set_sandbox(mut self, profile: SandboxProfile) -> Self {
+        self.env.sandbox = profile;
+        self
+    }
+
+    /// Sets the [OutputSink] which receives text printed by MeTTa operations such as `println!`
+    /// and `trace!`
+    ///
+    /// NOTE: By default the text is written into the process stdout and stderr.  The sink can be
+    /// replaced for a particular runner using [crate::metta::runner::Metta::set_output_sink]
+    pub fn set_output_sink(mut self, sink: Arc<dyn OutputSink>) -> Self {
+        self.env.output_sink = sink;
+        self
+    }
+
+    /// Sets the [InputSource] which provides text read by MeTTa operations such as `read-line`
+    ///
+    /// NOTE: By default the text is read from the process stdin.  The source can be replaced for a
+    /// particular runner using [crate::metta::runner::Metta::set_input_source]
+    pub fn set_input_source(mut self, source: Arc<dyn InputSource>) -> Self {
+        self.env.input_source = source;
+        self
+    }
+
     /// Adds additional search paths to search for MeTTa modules in the file system
     ///
     /// NOTE: include paths are a type of [ModuleCatalog], and the first catalog added will have the highest
@@ -281,6 +396,12 @@ impl EnvBuilder {
             proto_catalogs.insert(0, ProtoCatalog::Path(working_dir.into()));
         }
 
+        //Don't search for modules in the file system if the sandbox denies it
+        #[cfg(feature = "pkg_mgmt")]
+        if !env.sandbox.is_module_source_allowed(ModuleSource::FileSystem) {
+            proto_catalogs.retain(|proto| !matches!(proto, ProtoCatalog::Path(_)));
+        }
+
         //Construct the platform-specific config dir location, if an explicit location wasn't provided
         if !self.no_cfg_dir {
             if env.config_dir.is_none() {
@@ -373,8 +494,10 @@ impl EnvBuilder {
 
         #[cfg(feature = "pkg_mgmt")]
         {
-            //If we have a caches dir to cache modules locally then register remote catalogs
-            if let Some(caches_dir) = &env.caches_dir {
+            //If we have a caches dir to cache modules locally then register remote catalogs,
+            // unless the sandbox denies fetching modules from git
+            let git_allowed = env.sandbox.is_module_source_allowed(ModuleSource::Git);
+            if let Some(caches_dir) = env.caches_dir.as_ref().filter(|_| git_allowed) {
 
                 //Setup the specified_mods managed catalog to hold mods fetched by explicit means
                 let mut specified_mods = LocalCatalog::new(caches_dir, "specified-mods").unwrap();
@@ -389,6 +512,35 @@ impl EnvBuilder {
 
 }
 
+/// Maximal number of dangling symbolic links followed by [canonicalize_for_access]
+const MAX_SYMLINKS: usize = 40;
+
+/// Canonicalizes the path which may not exist yet, by canonicalizing its parent directory
+///
+/// A dangling symbolic link is replaced by its target, because writing to the link creates
+/// the target file, which must be checked against the allowed directories instead of the link
+fn canonicalize_for_access(path: &Path) -> Option<PathBuf> {
+    let mut path = path.to_path_buf();
+    for _ in 0..MAX_SYMLINKS {
+        match path.canonicalize() {
+            Ok(path) => return Some(path),
+            Err(_) => match path.symlink_metadata() {
+                Ok(meta) if meta.file_type().is_symlink() => {
+                    let target = fs::read_link(&path).ok()?;
+                    path = path.parent()?.join(target);
+                },
+                Ok(_) => return None,
+                Err(_) => {
+                    let file_name = path.file_name()?;
+                    let parent = path.parent()?.canonicalize().ok()?;
+                    return Some(parent.join(file_name));
+                },
+            },
+        }
+    }
+    None
+}
+
 /// Interprets the file at `env_metta_path`, and modifies settings in the Environment
 ///
 /// NOTE: I wonder if users will get confused by the fact that the full set of runner
@@ -414,7 +566,9 @@ fn interpret_environment_metta<P: AsRef<Path>>(env_metta_path: P, env: &mut Envi
         match expr.children().get(0) {
             Some(atom_0) if *atom_0 == sym!("#includePath") => {
                 #[cfg(feature = "pkg_mgmt")]
-                env.catalogs.push(include_path_from_cfg_atom(&expr, env)?);
+                if env.sandbox.is_module_source_allowed(ModuleSource::FileSystem) {
+                    env.catalogs.push(include_path_from_cfg_atom(&expr, env)?);
+                }
                 #[cfg(not(feature = "pkg_mgmt"))]
                 {
                     let _ = &env;
@@ -423,7 +577,9 @@ fn interpret_environment_metta<P: AsRef<Path>>(env_metta_path: P, env: &mut Envi
             },
             Some(atom_0) if *atom_0 == sym!("#gitCatalog") => {
                 #[cfg(feature = "pkg_mgmt")]
-                env.catalogs.push(git_catalog_from_cfg_atom(&expr, env)?);
+                if env.sandbox.is_module_source_allowed(ModuleSource::Git) {
+                    env.catalogs.push(git_catalog_from_cfg_atom(&expr, env)?);
+                }
                 #[cfg(not(feature = "pkg_mgmt"))]
                 log::warn!("#gitCatalog in environment.metta not supported without pkg_mgmt feature");
             },
@@ -0,0 +1,121 @@
+//! Input source provides the text read by MeTTa operations like `read-line`
+//! and `read-all`. It allows host to feed the input of the MeTTa code from
+//! the terminal, a script or any other place.
+
+use std::collections::VecDeque;
+use std::io::{BufRead, Read, Write};
+use std::sync::Mutex;
+
+/// Provides the text read by MeTTa operations
+pub trait InputSource: std::fmt::Debug + Send + Sync {
+    /// Reads next line of the input. Returned line doesn't contain trailing
+    /// new line characters. Returns `Ok(None)` when the input is exhausted.
+    fn read_line(&self) -> std::io::Result<Option<String>>;
+
+    /// Reads all remaining input
+    fn read_all(&self) -> std::io::Result<String>;
+}
+
+fn trim_line_end(mut line: String) -> String {
+    if line.ends_with('\n') {
+        line.pop();
+        if line.ends_with('\r') {
+            line.pop();
+        }
+    }
+    line
+}
+
+/// Default source which reads the process stdin. The stdout is flushed before
+/// reading to make sure the prompt printed by the program is visible.
+#[derive(Debug, Default)]
+pub struct StdInputSource;
+
+impl InputSource for StdInputSource {
+    fn read_line(&self) -> std::io::Result<Option<String>> {
+        std::io::stdout().flush()?;
+        let mut line = String::new();
+        match std::io::stdin().lock().read_line(&mut line)? {
+            0 => Ok(None),
+            _ => Ok(Some(trim_line_end(line))),
+        }
+    }
+
+    fn read_all(&self) -> std::io::Result<String> {
+        std::io::stdout().flush()?;
+        let mut text = String::new();
+        std::io::stdin().lock().read_to_string(&mut text)?;
+        Ok(text)
+    }
+}
+
+/// Source which returns the predefined text, it is useful to feed the input
+/// of the program in tests
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+/// use hyperon::metta::runner::{Metta, EnvBuilder};
+/// use hyperon::metta::runner::input::ScriptedInputSource;
+/// use hyperon::metta::text::SExprParser;
+///
+/// let metta = Metta::new(Some(EnvBuilder::test_env()));
+/// metta.set_input_source(Arc::new(ScriptedInputSource::new("Alice\n")));
+/// let result = metta.run(SExprParser::new("!(read-line)")).unwrap();
+///
+/// assert_eq!(result[0][0].to_string(), "\"Alice\"");
+/// ```
+#[derive(Debug, Default)]
+pub struct ScriptedInputSource {
+    text: Mutex<VecDeque<char>>,
+}
+
+impl ScriptedInputSource {
+    pub fn new(text: &str) -> Self {
+        Self{ text: Mutex::new(text.chars().collect()) }
+    }
+
+    /// Constructs source which returns the lines passed one by one
+    pub fn from_lines<I: IntoIterator<Item=S>, S: AsRef<str>>(lines: I) -> Self {
+        let text: String = lines.into_iter()
+            .map(|line| format!("{}\n", line.as_ref()))
+            .collect();
+        Self::new(&text)
+    }
+}
+
+impl InputSource for ScriptedInputSource {
+    fn read_line(&self) -> std::io::Result<Option<String>> {
+        let mut text = self.text.lock().unwrap();
+        if text.is_empty() {
+            return Ok(None);
+        }
+        let len = text.iter().position(|c| *c == '\n').map_or(text.len(), |pos| pos + 1);
+        Ok(Some(trim_line_end(text.drain(..len).collect())))
+    }
+
+    fn read_all(&self) -> std::io::Result<String> {
+        Ok(self.text.lock().unwrap().drain(..).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scripted_input_source() {
+        let input = ScriptedInputSource::new("a\r\n\nb\nc");
+        assert_eq!(input.read_line().unwrap(), Some("a".into()));
+        assert_eq!(input.read_line().unwrap(), Some("".into()));
+        assert_eq!(input.read_all().unwrap(), "b\nc");
+        assert_eq!(input.read_line().unwrap(), None);
+        assert_eq!(input.read_all().unwrap(), "");
+
+        let input = ScriptedInputSource::from_lines(["x", "y"]);
+        assert_eq!(input.read_line().unwrap(), Some("x".into()));
+        assert_eq!(input.read_line().unwrap(), Some("y".into()));
+        assert_eq!(input.read_line().unwrap(), None);
+    }
+}
@@ -78,6 +78,7 @@ use pkg_mgmt::*;
 pub(crate) type ModuleDescriptor = ();
 
 use std::rc::Rc;
+use std::cell::RefCell;
 use std::path::PathBuf;
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex, OnceLock};
@@ -85,6 +86,20 @@ use std::sync::{Arc, Mutex, OnceLock};
 mod environment;
 pub use environment::{Environment, EnvBuilder};
 
+pub mod sandbox;
+pub use sandbox::{SandboxProfile, ModuleSource};
+
+pub mod output;
+use output::{OutputSink, OutputStream};
+
+pub mod input;
+
+pub mod assertion;
+
+pub mod coverage;
+use coverage::CoverageCollector;
+use assertion::{AssertionListener, AssertionOutcome};
+
 use super::interpreter::{interpret, interpret_init, interpret_step, InterpreterState};
 
 #[macro_use]
@@ -97,6 +112,7 @@ use builtin_mods::*;
 pub mod bool;
 pub mod number;
 pub mod str;
+pub mod collection;
 
 const EXEC_SYMBOL : Atom = sym!("!");
 
@@ -116,8 +132,8 @@ impl PartialEq for Metta {
 
 #[derive(Debug)]
 pub(crate) struct MettaContents {
-    /// All the runner's loaded modules
-    modules: Mutex<Vec<Rc<MettaMod>>>,
+    /// All the runner's loaded modules, `None` in place of unloaded modules
+    modules: Mutex<Vec<Option<Rc<MettaMod>>>>,
     /// A tree to locate loaded mods by name
     module_names: Mutex<ModNameNode>,
     #[cfg(feature = "pkg_mgmt")]
@@ -137,6 +153,14 @@ pub(crate) struct MettaContents {
     settings: Shared<HashMap<String, Atom>>,
     /// The runner's Environment
     environment: Arc<Environment>,
+    /// The sink which receives text printed by MeTTa operations
+    output_sink: Mutex<Arc<dyn OutputSink>>,
+    /// The source which provides text read by MeTTa operations
+    input_source: Mutex<Arc<dyn input::InputSource>>,
+    /// The listener which receives outcomes of the assertions made by MeTTa code
+    assertion_listener: Mutex<Option<Arc<dyn AssertionListener>>>,
+    /// The collector which records definitions matched during evaluation
+    coverage_collector: Mutex<Option<Rc<CoverageCollector>>>,
     //TODO-HACK: This is a terrible horrible ugly hack that should not be merged.  Delete this field
     // The real context is an interface to the state in a run, and should not live across runs
     // This hack will fail badly if we end up running code from two different modules in parallel
@@ -216,6 +240,8 @@ impl Metta {
         };
         let top_mod_resource_dir = environment.working_dir().map(|path| path.into());
         let top_mod_tokenizer = Shared::new(Tokenizer::new());
+        let output_sink = Mutex::new(environment.output_sink().clone());
+        let input_source = Mutex::new(environment.input_source().clone());
         let contents = MettaContents{
             modules: Mutex::new(vec![]),
             module_names: Mutex::new(ModNameNode::top()),
@@ -226,6 +252,10 @@ impl Metta {
             stdlib_mod: OnceLock::new(),
             settings,
             environment,
+            output_sink,
+            input_source,
+            assertion_listener: Mutex::new(None),
+            coverage_collector: Mutex::new(None),
             context: std::sync::Arc::new(std::sync::Mutex::new(vec![])),
         };
         let metta = Self(Rc::new(contents));
@@ -268,7 +298,7 @@ impl Metta {
     /// Internal method to look up a module from a ModId
     pub(crate) fn get_mod_ptr(&self, mod_id: ModId) -> Rc<MettaMod> {
         let mod_ref = self.0.modules.lock().unwrap();
-        mod_ref.get(mod_id.0).unwrap().clone()
+        mod_ref.get(mod_id.0).and_then(Option::as_ref).expect("Attempt to access module which is not loaded").clone()
     }
 
     /// Locates and retrieves a loaded module based on its name, relative to the top of the runner
@@ -277,7 +307,8 @@ impl Metta {
     /// [RunContext::get_module_by_name] if you require that
     fn get_module_by_name(&self, mod_name: &str) -> Result<ModId, String> {
         let module_names = self.0.module_names.lock().unwrap();
-        module_names.resolve(mod_name).ok_or_else(|| format!("Unable to locate module: {mod_name}"))
+        module_names.resolve(mod_name).filter(|mod_id| *mod_id != ModId::INVALID)
+            .ok_or_else(|| format!("Unable to locate module: {mod_name}"))
     }
 
     /// Adds a ModId to the named module tree with the specified name, relative to the top of the runer
@@ -302,8 +333,13 @@ impl Metta {
     /// Writes a textual description of the loaded modules to stdout
     pub fn display_loaded_modules(&self) {
         let module_names = self.0.module_names.lock().unwrap();
-        let wrapper = ModNameNodeDisplayWrapper::new(TOP_MOD_NAME, &*module_names, |mod_id: ModId, f: &mut std::fmt::Formatter| write!(f, "{}", mod_id.0));
-        println!("{wrapper}");
+        let wrapper = ModNameNodeDisplayWrapper::new(TOP_MOD_NAME, &*module_names, |mod_id: ModId, f: &mut std::fmt::Formatter| {
+            match mod_id {
+                ModId::INVALID => write!(f, "unloaded"),
+                _ => write!(f, "{}", mod_id.0),
+            }
+        });
+        self.output_sink().write(OutputStream::Stdout, &format!("{wrapper}\n"));
     }
 
     #[cfg(feature = "pkg_mgmt")]
@@ -380,15 +416,153 @@ impl Metta {
     fn add_module(&self, module: MettaMod) -> Result<ModId, String> {
         let mut vec_ref = self.0.modules.lock().unwrap();
         let new_id = ModId(vec_ref.len());
-        vec_ref.push(Rc::new(module));
+        vec_ref.push(Some(Rc::new(module)));
         Ok(new_id)
     }
 
+    /// Unloads a module from the runner.  The module's space and Tokenizer entries are removed from
+    /// all modules which imported it, and the module's name no longer resolves to a module.  The
+    /// `mod_id` must not be used after the module is unloaded.
+    ///
+    /// NOTE: The top module and the stdlib cannot be unloaded.  Sub-modules loaded by the module are
+    /// not unloaded with it.
+    pub fn unload_module(&self, mod_id: ModId) -> Result<(), String> {
+        self.check_module_can_be_unloaded(mod_id)?;
+        self.detach_module(mod_id, ModId::INVALID);
+        Ok(())
+    }
+
+    /// Reloads a module using the loader the module was loaded with, for example to pick up the
+    /// changes in the module's file.  The new instance of the module replaces the old one under the
+    /// same name, and it is imported again into all modules which imported the old instance.
+    /// Returns the [ModId] of the new instance; the old `mod_id` must not be used after the reload.
+    ///
+    /// If the loader fails then the old instance of the module is kept loaded.
+    pub fn reload_module(&self, mod_id: ModId) -> Result<ModId, String> {
+        self.check_module_can_be_unloaded(mod_id)?;
+        let old_mod = self.get_mod_ptr(mod_id);
+        let loader = old_mod.loader().ok_or_else(|| format!("Module {} has no loader to reload it", old_mod.path()))?;
+
+        let mut state = RunnerState::new_with_module(self, ModId::TOP);
+        let new_mod_id = state.run_in_context(|context| {
+            context.init_module_with_prepared_loader(old_mod.path(), loader)
+        })?;
+
+        let new_mod = self.get_mod_ptr(new_mod_id);
+        for (dependent, imports) in self.detach_module(mod_id, new_mod_id) {
+            dependent.repeat_imports(new_mod_id, new_mod.clone(), &imports)?;
+        }
+        Ok(new_mod_id)
+    }
+
+    /// Returns the paths of the files the loaded modules were loaded from, see
+    /// [ModuleLoader::source_paths]
+    pub fn module_source_paths(&self) -> Vec<PathBuf> {
+        let modules: Vec<Rc<MettaMod>> = self.0.modules.lock().unwrap().iter().flatten().cloned().collect();
+        let mut paths: Vec<PathBuf> = vec![];
+        for path in modules.iter().filter_map(|module| module.loader()).flat_map(|loader| loader.source_paths()) {
+            if !paths.contains(&path) {
+                paths.push(path);
+            }
+        }
+        paths
+    }
+
+    /// Internal method to check that a module is loaded and can be unloaded
+    fn check_module_can_be_unloaded(&self, mod_id: ModId) -> Result<(), String> {
+        let loaded = self.0.modules.lock().unwrap().get(mod_id.0).is_some_and(Option::is_some);
+        if !loaded {
+            return Err(format!("Module {} is not loaded", mod_id.0));
+        }
+        if mod_id == ModId::TOP || self.0.stdlib_mod.get() == Some(&mod_id) {
+            return Err(format!("Module {} cannot be unloaded", self.get_mod_ptr(mod_id).path()));
+        }
+        Ok(())
+    }
+
+    /// Internal method to remove a module from the runner, and from all the modules which imported
+    /// it.  References to the module in the module name tree are replaced by `replacement`.
+    /// Returns the modules which imported the removed module along with their imports
+    fn detach_module(&self, mod_id: ModId, replacement: ModId) -> Vec<(Rc<MettaMod>, Vec<modules::ModImport>)> {
+        let remaining: Vec<Rc<MettaMod>> = {
+            let mut modules = self.0.modules.lock().unwrap();
+            modules[mod_id.0] = None;
+            modules.iter().flatten().cloned().collect()
+        };
+
+        self.0.module_names.lock().unwrap().visit_mut("", |_name, node: &mut ModNameNode| {
+            if node.mod_id == mod_id {
+                node.mod_id = replacement;
+            }
+        });
+
+        #[cfg(feature = "pkg_mgmt")]
+        {
+            let mut descriptors = self.0.module_descriptors.lock().unwrap();
+            if replacement == ModId::INVALID {
+                descriptors.retain(|_descriptor, id| *id != mod_id);
+            } else {
+                descriptors.values_mut().filter(|id| **id == mod_id).for_each(|id| *id = replacement);
+            }
+        }
+
+        remaining.into_iter()
+            .filter_map(|module| module.remove_dependency(mod_id).map(|imports| (module, imports)))
+            .collect()
+    }
+
     /// Returns a reference to the Environment used by the runner
     pub fn environment(&self) -> &Environment {
         &self.0.environment
     }
 
+    /// Returns the sink which receives text printed by MeTTa operations such as `println!`
+    pub fn output_sink(&self) -> Arc<dyn OutputSink> {
+        self.0.output_sink.lock().unwrap().clone()
+    }
+
+    /// Replaces the sink which receives text printed by MeTTa operations.  By default the
+    /// runner uses the sink of its [Environment], see [EnvBuilder::set_output_sink]
+    pub fn set_output_sink(&self, sink: Arc<dyn OutputSink>) {
+        *self.0.output_sink.lock().unwrap() = sink;
+    }
+
+    /// Returns the source which provides text read by MeTTa operations such as `read-line`
+    pub fn input_source(&self) -> Arc<dyn input::InputSource> {
+        self.0.input_source.lock().unwrap().clone()
+    }
+
+    /// Replaces the source which provides text read by MeTTa operations.  By default the
+    /// runner uses the source of its [Environment], see [EnvBuilder::set_input_source]
+    pub fn set_input_source(&self, source: Arc<dyn input::InputSource>) {
+        *self.0.input_source.lock().unwrap() = source;
+    }
+
+    /// Sets the listener which receives outcomes of the assertions made by MeTTa operations such
+    /// as `assertEqual`.  Outcomes are not reported when listener is `None`, which is the default
+    pub fn set_assertion_listener(&self, listener: Option<Arc<dyn AssertionListener>>) {
+        *self.0.assertion_listener.lock().unwrap() = listener;
+    }
+
+    /// Sets the collector which records `=` definitions matched by the interpreter.  Coverage is
+    /// not recorded when collector is `None`, which is the default
+    pub fn set_coverage_collector(&self, collector: Option<Rc<CoverageCollector>>) {
+        *self.0.coverage_collector.lock().unwrap() = collector;
+    }
+
+    /// Returns the collector which records `=` definitions matched by the interpreter if any
+    pub(crate) fn coverage_collector(&self) -> Option<Rc<CoverageCollector>> {
+        self.0.coverage_collector.lock().unwrap().clone()
+    }
+
+    /// Reports the outcome of the assertion to the listener if any
+    pub(crate) fn report_assertion(&self, outcome: AssertionOutcome) {
+        let listener = self.0.assertion_listener.lock().unwrap().clone();
+        if let Some(listener) = listener {
+            listener.on_assertion(outcome);
+        }
+    }
+
     /// Returns a reference to the Space associated with the runner's top module
     pub fn space(&self) -> &DynSpace {
         &self.0.top_mod_space
@@ -396,14 +570,12 @@ impl Metta {
 
     /// Returns the [DynSpace] handle associated with any loaded module's Space
     pub fn module_space(&self, mod_id: ModId) -> DynSpace {
-        let modules = self.0.modules.lock().unwrap();
-        modules.get(mod_id.0).unwrap().space().clone()
+        self.get_mod_ptr(mod_id).space().clone()
     }
 
     /// Returns a buffer containing the specified resource, if it is available from a loaded module
     pub fn get_module_resource(&self, mod_id: ModId, res_key: ResourceKey) -> Result<Resource, String> {
-        let modules = self.0.modules.lock().unwrap();
-        modules.get(mod_id.0).unwrap().get_resource(res_key)
+        self.get_mod_ptr(mod_id).get_resource(res_key)
     }
 
     /// Returns a reference to the Tokenizer associated with the runner's top module
@@ -556,6 +728,12 @@ impl<'m, 'input> RunnerState<'m, 'input> {
         state
     }
 
+    /// Makes the RunnerState continue the execution after a result which contains an error,
+    ///    instead of terminating.  By default execution is terminated by the first error
+    pub fn set_continue_on_error(&mut self, continue_on_error: bool) {
+        self.i_wrapper.continue_on_error = continue_on_error;
+    }
+
     /// Repeatedly steps a RunnerState until it is complete, and then returns the results
     pub fn run_to_completion(mut self) -> Result<Vec<Vec<Atom>>, String> {
         while !self.is_complete() {
@@ -580,6 +758,12 @@ impl<'m, 'input> RunnerState<'m, 'input> {
         &self.i_wrapper.results
     }
 
+    /// Returns the atoms which produced the current results, the atom at each index corresponds to
+    /// the results at the same index of [RunnerState::current_results]
+    pub fn current_statements(&self) -> &Vec<Atom> {
+        &self.i_wrapper.statements
+    }
+
     /// Consumes the RunnerState and returns the final results
     pub fn into_results(self) -> Vec<Vec<Atom>> {
         self.i_wrapper.results
@@ -948,7 +1132,19 @@ impl<'input> RunContext<'_, 'input> {
     /// The init function will then call `context.init_self_module()` along with any other initialization code
     fn init_module(&mut self, mod_name: &str, loader: Box<dyn ModuleLoader>) -> Result<ModId, String> {
         let new_mod_id = self.init_state.init_module(&self.metta, mod_name, loader)?;
+        self.merge_initialized_module(new_mod_id)
+    }
+
+    /// Internal method, same as [Self::init_module] but for the loader which is already prepared,
+    /// used to reload a module
+    fn init_module_with_prepared_loader(&mut self, mod_name: &str, loader: Arc<dyn ModuleLoader>) -> Result<ModId, String> {
+        let new_mod_id = self.init_state.init_module_with_prepared_loader(self.metta, mod_name, loader)?;
+        self.merge_initialized_module(new_mod_id)
+    }
 
+    /// Internal method to merge the initialized module into the runner, if the context is the root
+    /// of the module initialization
+    fn merge_initialized_module(&mut self, new_mod_id: ModId) -> Result<ModId, String> {
         if self.init_state.is_root() {
             let mut init_state = ModuleInitState::empty();
             core::mem::swap(&mut init_state, self.init_state);
@@ -961,7 +1157,7 @@ impl<'input> RunContext<'_, 'input> {
     /// Adds a loaded module as a dependency of the `&self` [MettaMod], and adds a [Tokenizer] entry to access
     /// the dependent module's Space.
     pub fn import_dependency_as(&self, mod_id: ModId, name: Option<String>) -> Result<(), String> {
-        self.module().import_dependency_as(self.get_mod_ptr(mod_id)?, name)
+        self.module().import_dependency_as(mod_id, self.get_mod_ptr(mod_id)?, name)
     }
 
     /// Adds a specific atom and/or Tokenizer entry from a dependency module to the &self module
@@ -985,7 +1181,7 @@ impl<'input> RunContext<'_, 'input> {
     // numbers?
     // More discussion on the topic of tokenizer entry names is here https://github.com/trueagi-io/hyperon-experimental/issues/510
     pub fn import_item_from_dependency_as(&self, from_name: &str, mod_id: ModId, name: Option<&str>) -> Result<(), String> {
-        self.module().import_item_from_dependency_as(from_name, self.get_mod_ptr(mod_id)?, name)
+        self.module().import_item_from_dependency_as(from_name, mod_id, self.get_mod_ptr(mod_id)?, name)
     }
 
     /// Effectively adds all atoms in a dependency module to the &self module, by adding the dependency
@@ -1005,15 +1201,30 @@ impl<'input> RunContext<'_, 'input> {
 
             if interpreter_state.has_next() {
 
-                //Take a step with the interpreter, and put it back for next time
-                self.i_wrapper.interpreter_state = Some(interpret_step(interpreter_state))
+                //Take a step with the interpreter, and put it back for next time.  Steps are
+                // charged to the resource meter, and the evaluation is finished with an error
+                // when the sandbox limits are exceeded
+                let meter = self.i_wrapper.meter.clone();
+                let _guard = meter.map(sandbox::enter_meter);
+                let _coverage_guard = self.metta.coverage_collector()
+                    .map(|collector| coverage::enter_collector(collector, self.module().space().clone()));
+                self.i_wrapper.interpreter_state = match sandbox::charge_step() {
+                    Ok(()) => Some(interpret_step(interpreter_state)),
+                    Err(limit) => {
+                        let atom = self.i_wrapper.evaluated_atom.clone().unwrap_or(EMPTY_SYMBOL);
+                        let error = Atom::expr([ERROR_SYMBOL, atom, limit.as_atom()]);
+                        Some(InterpreterState::new_finished(self.module().space().clone(), vec![error]))
+                    },
+                }
             } else {
 
                 //This interpreter is finished, process the results
                 let result = interpreter_state.into_result().unwrap();
                 let error = result.iter().any(|atom| atom_is_error(atom));
+                let statement = self.i_wrapper.evaluated_atom.take().unwrap_or(EMPTY_SYMBOL);
+                self.i_wrapper.statements.push(statement);
                 self.i_wrapper.results.push(result);
-                if error {
+                if error && !self.i_wrapper.continue_on_error {
                     self.i_wrapper.mode = MettaRunnerMode::TERMINATE;
                     return Ok(());
                 }
@@ -1047,18 +1258,32 @@ impl<'input> RunContext<'_, 'input> {
                     }
                     match self.i_wrapper.mode {
                         MettaRunnerMode::ADD => {
-                            if let Err(atom) = self.module().add_atom(atom, self.metta.type_check_is_enabled()) {
-                                self.i_wrapper.results.push(vec![atom]);
-                                self.i_wrapper.mode = MettaRunnerMode::TERMINATE;
+                            if let Err(err) = self.module().add_atom(atom, self.metta.type_check_is_enabled()) {
+                                //The error is `(Error <atom> <reason>)`, so the atom is taken from it
+                                // instead of cloning each added atom
+                                let statement = match &err {
+                                    Atom::Expression(expr) => expr.children().get(1).cloned(),
+                                    _ => None,
+                                };
+                                self.i_wrapper.statements.push(statement.unwrap_or(EMPTY_SYMBOL));
+                                self.i_wrapper.results.push(vec![err]);
+                                if !self.i_wrapper.continue_on_error {
+                                    self.i_wrapper.mode = MettaRunnerMode::TERMINATE;
+                                }
                                 return Ok(());
                             }
                         },
                         MettaRunnerMode::INTERPRET => {
 
                             if self.metta.type_check_is_enabled() && !validate_atom(self.module().space().borrow().as_space(), &atom) {
+                                self.i_wrapper.evaluated_atom = Some(atom.clone());
                                 let type_err_exp = Atom::expr([ERROR_SYMBOL, atom, BAD_TYPE_SYMBOL]);
                                 self.i_wrapper.interpreter_state = Some(InterpreterState::new_finished(self.module().space().clone(), vec![type_err_exp]));
                             } else {
+                                let sandbox = self.metta.environment().sandbox();
+                                self.i_wrapper.meter = (sandbox.max_steps().is_some() || sandbox.max_duration().is_some())
+                                    .then(|| Rc::new(RefCell::new(sandbox::ResourceMeter::new(sandbox))));
+                                self.i_wrapper.evaluated_atom = Some(atom.clone());
                                 let atom = if is_bare_minimal_interpreter(self.metta) {
                                     atom
                                 } else {
@@ -1100,6 +1325,15 @@ struct InterpreterWrapper<'i> {
     input_src: InputStream<'i>,
     interpreter_state: Option<InterpreterState<DynSpace>>,
     results: Vec<Vec<Atom>>,
+    /// Atoms which produced the results, one per each item of `results`
+    statements: Vec<Atom>,
+    /// Atom being evaluated, it is used to report exceeded resource limits and recorded in
+    /// `statements` when the evaluation is finished
+    evaluated_atom: Option<Atom>,
+    /// Resources spent on the evaluation of the current atom
+    meter: Option<Rc<RefCell<sandbox::ResourceMeter>>>,
+    /// Don't terminate the execution after a result which contains an error
+    continue_on_error: bool,
 }
 
 #[derive(Debug, Default, PartialEq, Eq)]
@@ -1273,6 +1507,51 @@ mod tests {
         assert_eq!(result, Ok(vec![vec![expr!("Error" ({ErrorOp{}}) "TestError")]]));
     }
 
+    #[test]
+    fn metta_continue_run_after_error() {
+        let program = "
+            (= (foo) ok)
+            !(error)
+            !(foo)
+        ";
+
+        let metta = Metta::new(Some(EnvBuilder::test_env()));
+        metta.tokenizer().borrow_mut().register_token_with_regex_str("error",
+            |_| Atom::gnd(ErrorOp{}));
+        let mut runner_state = RunnerState::new_with_parser(&metta, Box::new(SExprParser::new(program)));
+        runner_state.set_continue_on_error(true);
+        let result = runner_state.run_to_completion();
+
+        assert_eq!(result, Ok(vec![vec![expr!("Error" ({ErrorOp{}}) "TestError")], vec![expr!("ok")]]));
+    }
+
+    #[test]
+    fn metta_statements_of_results() {
+        let program = "
+            (: foo (-> A B))
+            (: b B)
+            (= (bar) ok)
+            (foo b)
+            !(bar)
+            !(foo b)
+        ";
+
+        let metta = Metta::new(Some(EnvBuilder::test_env()));
+        metta.set_setting("type-check".into(), sym!("auto"));
+        let mut runner_state = RunnerState::new_with_parser(&metta, Box::new(SExprParser::new(program)));
+        runner_state.set_continue_on_error(true);
+        while !runner_state.is_complete() {
+            runner_state.run_step().unwrap();
+        }
+
+        assert_eq!(runner_state.current_statements(), &vec![expr!("foo" "b"), expr!(("bar")), expr!("foo" "b")]);
+        assert_eq!(runner_state.into_results(), vec![
+            vec![expr!("Error" ("foo" "b") "BadType")],
+            vec![expr!("ok")],
+            vec![expr!("Error" ("foo" "b") "BadType")],
+        ]);
+    }
+
     #[test]
     fn metta_stop_after_type_check_fails_on_add() {
         let program = "
@@ -5,6 +5,8 @@ use std::cell::RefCell;
 use crate::metta::*;
 use crate::metta::runner::*;
 use crate::space::module::ModuleSpace;
+use crate::metta::types::find_declared_subtype_cycle;
+use crate::metta::text::TokenKey;
 
 use regex::Regex;
 
@@ -52,6 +54,33 @@ impl ModId {
     }
 }
 
+/// Describes how a dependency was imported into a module, so the import can be undone when the
+/// dependency is unloaded, and repeated when the dependency is reloaded
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum ModImport {
+    /// See [MettaMod::import_all_from_dependency]
+    All,
+    /// The dependency's space was imported as a transitive dependency of another module
+    Transitive,
+    /// See [MettaMod::import_dependency_as]
+    As(Option<String>),
+    /// See [MettaMod::import_item_from_dependency_as]
+    Item(String, Option<String>),
+}
+
+/// Imports made from a single dependency and the Tokenizer entries added by them
+#[derive(Debug)]
+struct ImportRecord {
+    imports: Vec<ModImport>,
+    tokens: Vec<TokenKey>,
+}
+
+impl ImportRecord {
+    fn new() -> Self {
+        Self{ imports: vec![], tokens: vec![] }
+    }
+}
+
 /// Contains state associated with a loaded MeTTa module
 #[derive(Debug)]
 pub struct MettaMod {
@@ -60,7 +89,8 @@ pub struct MettaMod {
     space: Rc<RefCell<ModuleSpace>>,
     tokenizer: Shared<Tokenizer>,
     imported_deps: Mutex<HashMap<ModId, DynSpace>>,
-    loader: Option<Box<dyn ModuleLoader>>,
+    imports: Mutex<HashMap<ModId, ImportRecord>>,
+    loader: Option<Arc<dyn ModuleLoader>>,
 }
 
 impl MettaMod {
@@ -83,6 +113,7 @@ impl MettaMod {
             space,
             tokenizer,
             imported_deps: Mutex::new(HashMap::new()),
+            imports: Mutex::new(HashMap::new()),
             resource_dir,
             loader: None,
         };
@@ -102,16 +133,36 @@ impl MettaMod {
     }
 
     /// Internal method to store the loader with its module, for resource access later on
-    pub(crate) fn set_loader(&mut self, loader: Box<dyn ModuleLoader>) {
+    pub(crate) fn set_loader(&mut self, loader: Arc<dyn ModuleLoader>) {
         self.loader = Some(loader);
     }
 
+    /// Returns the loader which was used to load the module, if the module was loaded by a loader
+    pub(crate) fn loader(&self) -> Option<Arc<dyn ModuleLoader>> {
+        self.loader.clone()
+    }
+
+    /// Private method to record an import from the `mod_id` dependency, along with the Tokenizer
+    /// entries with the `token_regexes` added by it
+    fn record_import(&self, mod_id: ModId, import: ModImport, token_regexes: &[&str]) {
+        let mut imports = self.imports.lock().unwrap();
+        let record = imports.entry(mod_id).or_insert_with(ImportRecord::new);
+        record.imports.push(import);
+        let tokenizer = self.tokenizer.borrow();
+        for regex in token_regexes {
+            if let Some(constr) = tokenizer.find_exact(regex) {
+                record.tokens.push(TokenKey::new(regex.to_string(), constr));
+            }
+        }
+    }
+
     /// Adds a loaded module as a dependency of the `&self` [MettaMod], and adds a [Tokenizer] entry to access
     /// the dependent module's Space.
-    pub(crate) fn import_dependency_as(&self, mod_ptr: Rc<MettaMod>, name: Option<String>) -> Result<(), String> {
+    pub(crate) fn import_dependency_as(&self, mod_id: ModId, mod_ptr: Rc<MettaMod>, name: Option<String>) -> Result<(), String> {
 
         // Get the space and name associated with the dependent module
         let dep_space = mod_ptr.space().clone();
+        let import = ModImport::As(name.clone());
         let name = match name {
             Some(name) => name,
             None => mod_ptr.name().to_string()
@@ -127,12 +178,13 @@ impl MettaMod {
         // Add a new atom to the &self space, so we can access the dependent module
         let dep_space_atom = Atom::gnd(dep_space);
         self.tokenizer.borrow_mut().register_token_with_regex_str(&new_space_token, move |_| { dep_space_atom.clone() });
+        self.record_import(mod_id, import, &[&new_space_token]);
 
         Ok(())
     }
 
     /// Adds a specific atom and/or Tokenizer entry from a dependency module to the &self module
-    pub(crate) fn import_item_from_dependency_as(&self, from_name: &str, mod_ptr: Rc<MettaMod>, name: Option<&str>) -> Result<(), String> {
+    pub(crate) fn import_item_from_dependency_as(&self, from_name: &str, mod_id: ModId, mod_ptr: Rc<MettaMod>, name: Option<&str>) -> Result<(), String> {
 
         // Get the space and tokenizer associated with the dependent module
         let dep_space = mod_ptr.space().clone();
@@ -144,6 +196,7 @@ impl MettaMod {
 
             // If so, this method just transplants the Tokenizer entry
             self.tokenizer.borrow_mut().register_token_with_func_ptr(Regex::new(from_name).unwrap(), found_constructor);
+            self.record_import(mod_id, ModImport::Item(from_name.to_string(), name.map(str::to_string)), &[from_name]);
         } else {
             //Otherwise we will try and transplant an atom
 
@@ -163,6 +216,7 @@ impl MettaMod {
             self.add_atom(src_atom.clone(), false).map_err(|a| a.to_string())?;
 
             // Finally, Add a Tokenizer entry to access this atom, if one is needed
+            let import = ModImport::Item(from_name.to_string(), name.map(str::to_string));
             let name = match name {
                 Some(name) => name,
                 None => from_name
@@ -174,6 +228,9 @@ impl MettaMod {
             };
             if should_add_tok {
                 self.tokenizer.borrow_mut().register_token_with_regex_str(&name, move |_| { src_atom.clone() });
+                self.record_import(mod_id, import, &[name]);
+            } else {
+                self.record_import(mod_id, import, &[]);
             }
         }
 
@@ -204,22 +261,67 @@ impl MettaMod {
         }
 
         // Finally, Import the tokens from the dependency
-        self.import_all_tokens_from_dependency(mod_ptr)
+        self.import_all_tokens_from_dependency(mod_id, mod_ptr)
     }
 
     /// Merges all Tokenizer entries in a dependency module into &self
-    pub(crate) fn import_all_tokens_from_dependency(&self, mod_ptr: Rc<MettaMod>) -> Result<(), String> {
+    pub(crate) fn import_all_tokens_from_dependency(&self, mod_id: ModId, mod_ptr: Rc<MettaMod>) -> Result<(), String> {
 
         // Get the tokenizer associated with the dependent module
         let dep_tokenizer = mod_ptr.tokenizer().clone();
 
-        //Import all the Tokenizer entries from the dependency
+        //Import all the Tokenizer entries from the dependency, and remember them in order to
+        // remove them if the dependency is unloaded
         let mut dep_tok_clone = dep_tokenizer.borrow().clone();
+        let mut imports = self.imports.lock().unwrap();
+        let record = imports.entry(mod_id).or_insert_with(ImportRecord::new);
+        record.imports.push(ModImport::All);
+        record.tokens.extend(dep_tok_clone.token_keys());
         self.tokenizer.borrow_mut().move_front(&mut dep_tok_clone);
 
         Ok(())
     }
 
+    /// Removes everything imported from the `mod_id` dependency: the dependency's space and the
+    /// Tokenizer entries.  Returns the imports which were made from the dependency, or `None` if
+    /// nothing was imported.
+    ///
+    /// NOTE: atoms copied by [Self::import_item_from_dependency_as] are not removed
+    pub(crate) fn remove_dependency(&self, mod_id: ModId) -> Option<Vec<ModImport>> {
+        let dep_space = self.imported_deps.lock().unwrap().remove(&mod_id);
+        if let Some(dep_space) = &dep_space {
+            self.space.borrow_mut().remove_dep(dep_space);
+        }
+        let record = self.imports.lock().unwrap().remove(&mod_id);
+        if dep_space.is_none() && record.is_none() {
+            return None;
+        }
+        let mut imports = match record {
+            Some(record) => {
+                self.tokenizer.borrow_mut().remove_imported(&record.tokens);
+                record.imports
+            },
+            None => vec![],
+        };
+        if dep_space.is_some() && !imports.contains(&ModImport::All) {
+            imports.push(ModImport::Transitive);
+        }
+        Some(imports)
+    }
+
+    /// Repeats the imports returned by [Self::remove_dependency] from the reloaded dependency
+    pub(crate) fn repeat_imports(&self, mod_id: ModId, mod_ptr: Rc<MettaMod>, imports: &[ModImport]) -> Result<(), String> {
+        for import in imports {
+            match import {
+                ModImport::All => self.import_all_from_dependency(mod_id, mod_ptr.clone())?,
+                ModImport::Transitive => self.insert_dep(mod_id, mod_ptr.space())?,
+                ModImport::As(name) => self.import_dependency_as(mod_id, mod_ptr.clone(), name.clone())?,
+                ModImport::Item(from_name, name) => self.import_item_from_dependency_as(from_name, mod_id, mod_ptr.clone(), name.as_deref())?,
+            }
+        }
+        Ok(())
+    }
+
     /// Returns `true` if the `self` module has imported the `mod_id` module as a sub-dependency
     pub fn contains_imported_dep(&self, mod_id: &ModId) -> bool {
         let deps_table = self.imported_deps.lock().unwrap();
@@ -249,6 +351,14 @@ impl MettaMod {
             };
             deps.insert(new_mod_id, space);
         }
+        drop(deps);
+
+        let mut imports = self.imports.lock().unwrap();
+        let temp = mem::take(&mut *imports);
+        for (dep_mod_id, record) in temp.into_iter() {
+            let new_mod_id = mapping.get(&dep_mod_id).copied().unwrap_or(dep_mod_id);
+            imports.insert(new_mod_id, record);
+        }
     }
 
     /// Private function that returns a deep copy of a module's space, with the module's dependency
@@ -299,6 +409,9 @@ impl MettaMod {
         if type_check && !validate_atom(self.space.borrow().as_space(), &atom) {
             return Err(Atom::expr([ERROR_SYMBOL, atom, BAD_TYPE_SYMBOL]));
         }
+        if find_declared_subtype_cycle(self.space.borrow().as_space(), &atom).is_some() {
+            return Err(Atom::expr([ERROR_SYMBOL, atom, SUBTYPE_CYCLE_SYMBOL]));
+        }
         self.space.borrow_mut().add(atom);
         Ok(())
     }
@@ -454,6 +567,13 @@ impl ModuleInitState {
             None => loader
         };
 
+        self.init_module_with_prepared_loader(runner, mod_name, Arc::from(loader))
+    }
+
+    /// Same as [Self::init_module], but the loader is already prepared and it may be shared with
+    /// another instance of the module, for example when the module is reloaded
+    pub fn init_module_with_prepared_loader(&mut self, runner: &Metta, mod_name: &str, loader: Arc<dyn ModuleLoader>) -> Result<ModId, String> {
+
         //Create a new RunnerState in order to initialize the new module, and push the init function
         // to run within the new RunnerState.  The init function will then call `context.init_self_module()`
         let mut runner_state = RunnerState::new_for_loading(runner, mod_name, self);
@@ -471,6 +591,11 @@ impl ModuleInitState {
         //Set the loader on the module, so its resource can be accessed later
         self.in_frame(mod_id, |frame| Rc::get_mut(frame.the_mod.as_mut().unwrap()).unwrap().set_loader(loader));
 
+        //Track the definitions of the module's source files if the coverage is collected
+        if let Some(collector) = runner.coverage_collector() {
+            self.in_frame(mod_id, |frame| collector.track_module(frame.the_mod.as_ref().unwrap()));
+        }
+
         Ok(mod_id)
     }
 
@@ -604,6 +729,12 @@ pub trait ModuleLoader: std::fmt::Debug + Send + Sync {
     fn get_resource(&self, _res_key: ResourceKey) -> Result<Resource, String> {
         Err("resource not found".to_string())
     }
+
+    /// Returns the paths of the files the module is loaded from, if the module is loaded from the
+    /// file system.  It allows host to find out which files should be watched for changes
+    fn source_paths(&self) -> Vec<PathBuf> {
+        vec![]
+    }
 }
 
 /// Resource for loading
@@ -662,6 +793,7 @@ pub enum ResourceKey<'a> {
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::metta::runner::number::Number;
 
     #[derive(Debug)]
     struct OuterLoader;
@@ -769,6 +901,71 @@ mod test {
 
     }
 
+    /// Loader of a module which code can be changed between the reloads
+    #[derive(Debug)]
+    struct EditableLoader(Arc<Mutex<String>>);
+
+    impl ModuleLoader for EditableLoader {
+        fn load(&self, context: &mut RunContext) -> Result<(), String> {
+            let space = DynSpace::new(GroundingSpace::new());
+            context.init_self_module(space, None);
+
+            let program = self.0.lock().unwrap().clone();
+            context.push_parser(Box::new(SExprParser::new(program)));
+
+            Ok(())
+        }
+    }
+
+    fn find_token(runner: &Metta, token: &str) -> Option<Atom> {
+        runner.tokenizer().borrow().find_token(token).map(|constr| constr(token).unwrap())
+    }
+
+    #[test]
+    fn reload_module_test() {
+        let runner = Metta::new(Some(EnvBuilder::test_env()));
+        let code = Arc::new(Mutex::new("(= (version) 1) !(bind! version-token v1)".to_string()));
+        let mod_id = runner.load_module_direct(Box::new(EditableLoader(code.clone())), "editable").unwrap();
+        runner.run(SExprParser::new("!(import! &self editable)")).unwrap();
+        assert_eq!(runner.run(SExprParser::new("!(version)")), Ok(vec![vec![expr!({Number::Integer(1)})]]));
+        assert_eq!(find_token(&runner, "version-token"), Some(sym!("v1")));
+
+        *code.lock().unwrap() = "(= (version) 2) !(bind! version-token v2)".to_string();
+        let new_mod_id = runner.reload_module(mod_id).unwrap();
+        assert_ne!(new_mod_id, mod_id);
+        assert_eq!(runner.get_module_by_name("editable"), Ok(new_mod_id));
+        assert_eq!(runner.run(SExprParser::new("!(version)")), Ok(vec![vec![expr!({Number::Integer(2)})]]));
+        assert_eq!(find_token(&runner, "version-token"), Some(sym!("v2")));
+        assert!(runner.reload_module(mod_id).is_err());
+
+        *code.lock().unwrap() = "(= (version) 3)".to_string();
+        runner.run(SExprParser::new("!(reload-module! editable)")).unwrap();
+        assert_eq!(runner.run(SExprParser::new("!(version)")), Ok(vec![vec![expr!({Number::Integer(3)})]]));
+        assert_eq!(find_token(&runner, "version-token"), None);
+    }
+
+    #[test]
+    fn unload_module_test() {
+        let runner = Metta::new(Some(EnvBuilder::test_env()));
+        let code = Arc::new(Mutex::new("(= (version) 1) !(bind! version-token v1)".to_string()));
+        let mod_id = runner.load_module_direct(Box::new(EditableLoader(code)), "editable").unwrap();
+        runner.run(SExprParser::new("!(import! &self editable) !(import! editable-space editable)")).unwrap();
+        assert!(find_token(&runner, "&editable-space").is_some());
+
+        runner.unload_module(mod_id).unwrap();
+        assert!(runner.get_module_by_name("editable").is_err());
+        assert_eq!(runner.run(SExprParser::new("!(version)")), Ok(vec![vec![expr!(("version"))]]));
+        assert_eq!(find_token(&runner, "version-token"), None);
+        assert_eq!(find_token(&runner, "&editable-space"), None);
+        assert!(runner.unload_module(mod_id).is_err());
+
+        //Top module and stdlib are required by the runner
+        assert!(runner.unload_module(ModId::TOP).is_err());
+        assert!(runner.run(SExprParser::new("!(unload-module! stdlib)")).unwrap()[0][0].to_string().starts_with("(Error"));
+        //Tokens imported from stdlib are kept
+        assert_eq!(runner.run(SExprParser::new("!(+ 1 1)")), Ok(vec![vec![expr!({Number::Integer(2)})]]));
+    }
+
     //LP-TODO-NEXT,  Make a test for an inner-loader that throws an error, blocking the outer-loader from loading sucessfully,
     // and make sure neither module is loaded into the named index
     //
@@ -1,14 +1,28 @@
 use crate::*;
+use crate::metta::{ARITHMETIC_OVERFLOW_SYMBOL, DIVISION_BY_ZERO_SYMBOL};
 use crate::atom::serial;
 use crate::atom::serial::ConvertingSerializer;
 
 use std::fmt::Display;
+use std::ops::{Add, Sub, Mul};
+use std::cmp::Ordering;
+use num_bigint::BigInt;
+use num_rational::BigRational;
+use num_traits::{FromPrimitive, ToPrimitive, Signed, Zero, Pow};
 
 pub const ATOM_TYPE_NUMBER : Atom = sym!("Number");
 
+/// Number value. Integer values which don't fit into `i64` are represented
+/// by [Number::BigInt], exact fractions are represented by
+/// [Number::Rational]. Results of the arithmetic operations are normalized:
+/// [Number::BigInt] which fits into `i64` is converted into
+/// [Number::Integer] and [Number::Rational] with denominator equal to one is
+/// converted into an integer.
 #[derive(Clone, Debug)]
 pub enum Number {
     Integer(i64),
+    BigInt(BigInt),
+    Rational(BigRational),
     Float(f64),
 }
 
@@ -17,12 +31,27 @@ impl PartialEq<Self> for Number {
         let (a, b) = Number::promote(self.clone(), other.clone());
         match (a, b) {
             (Number::Integer(a), Number::Integer(b)) => a == b,
+            (Number::BigInt(a), Number::BigInt(b)) => a == b,
+            (Number::Rational(a), Number::Rational(b)) => a == b,
             (Number::Float(a), Number::Float(b)) => a == b,
             _ => panic!("Unexpected state!"),
         }
     }
 }
 
+impl PartialOrd<Self> for Number {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        let (a, b) = Number::promote(self.clone(), other.clone());
+        match (a, b) {
+            (Number::Integer(a), Number::Integer(b)) => a.partial_cmp(&b),
+            (Number::BigInt(a), Number::BigInt(b)) => a.partial_cmp(&b),
+            (Number::Rational(a), Number::Rational(b)) => a.partial_cmp(&b),
+            (Number::Float(a), Number::Float(b)) => a.partial_cmp(&b),
+            _ => panic!("Unexpected state!"),
+        }
+    }
+}
+
 impl Into<Number> for i64 {
     fn into(self) -> Number {
         Number::Integer(self)
@@ -35,10 +64,26 @@ impl Into<Number> for f64 {
     }
 }
 
+impl From<BigInt> for Number {
+    fn from(n: BigInt) -> Self {
+        Number::BigInt(n).normalize()
+    }
+}
+
+impl From<BigRational> for Number {
+    fn from(n: BigRational) -> Self {
+        Number::Rational(n).normalize()
+    }
+}
+
 impl Into<i64> for Number {
+    /// Converts number into `i64`. Fractional part is truncated and values
+    /// which don't fit into `i64` are saturated.
     fn into(self) -> i64 {
         match self {
             Number::Integer(n) => n,
+            Number::BigInt(n) => saturate_big_int(&n),
+            Number::Rational(n) => saturate_big_int(&n.to_integer()),
             Number::Float(n) => n as i64,
         }
     }
@@ -48,15 +93,26 @@ impl Into<f64> for Number {
     fn into(self) -> f64 {
         match self {
             Number::Integer(n) => n as f64,
+            Number::BigInt(n) => n.to_f64().unwrap_or(f64::NAN),
+            Number::Rational(n) => n.to_f64().unwrap_or(f64::NAN),
             Number::Float(n) => n,
         }
     }
 }
 
+fn saturate_big_int(n: &BigInt) -> i64 {
+    n.to_i64().unwrap_or(if n.is_negative() { i64::MIN } else { i64::MAX })
+}
+
 impl Number {
     pub fn from_int_str(num: &str) -> Result<Self, String> {
-        let n = num.parse::<i64>().map_err(|e| format!("Could not parse integer: '{num}', {e}"))?;
-        Ok(Self::Integer(n))
+        match num.parse::<i64>() {
+            Ok(n) => Ok(Self::Integer(n)),
+            Err(_) => {
+                let n = num.parse::<BigInt>().map_err(|e| format!("Could not parse integer: '{num}', {e}"))?;
+                Ok(Self::from(n))
+            }
+        }
     }
 
     pub fn from_float_str(num: &str) -> Result<Self, String> {
@@ -64,8 +120,14 @@ impl Number {
         Ok(Self::Float(n))
     }
 
+    /// Parses rational number in the `<numerator>/<denominator>` format.
+    pub fn from_rational_str(num: &str) -> Result<Self, String> {
+        let n = num.parse::<BigRational>().map_err(|e| format!("Could not parse rational: '{num}', {e}"))?;
+        Ok(Self::from(n))
+    }
+
     pub fn promote(a: Number, b: Number) -> (Number, Number) {
-        let res_type = &NumberType::widest_type(a.get_type(), b.get_type());
+        let res_type = &std::cmp::max(a.get_type(), b.get_type());
         (a.cast(res_type), b.cast(res_type))
     }
 
@@ -73,9 +135,49 @@ impl Number {
         NumberSerializer::convert(atom)
     }
 
+    /// Returns true if number is an integer value of any size.
+    pub fn is_integer(&self) -> bool {
+        matches!(self, Number::Integer(_) | Number::BigInt(_))
+    }
+
+    /// Converts integer number into [BigInt]. Returns [None] if number is
+    /// not an integer.
+    pub fn into_integer(self) -> Option<BigInt> {
+        match self {
+            Number::Integer(n) => Some(BigInt::from(n)),
+            Number::BigInt(n) => Some(n),
+            Number::Rational(_) | Number::Float(_) => None,
+        }
+    }
+
+    /// Returns true if number is equal to zero.
+    pub fn is_zero(&self) -> bool {
+        match self {
+            Number::Integer(n) => *n == 0,
+            Number::BigInt(n) => n.is_zero(),
+            Number::Rational(n) => n.is_zero(),
+            Number::Float(n) => *n == 0.0,
+        }
+    }
+
+    /// Converts big integer which fits into `i64` into [Number::Integer] and
+    /// rational with denominator equal to one into an integer.
+    pub fn normalize(self) -> Number {
+        match self {
+            Number::BigInt(n) => match n.to_i64() {
+                Some(n) => Number::Integer(n),
+                None => Number::BigInt(n),
+            },
+            Number::Rational(n) if n.is_integer() => Number::BigInt(n.to_integer()).normalize(),
+            n => n,
+        }
+    }
+
     fn get_type(&self) -> NumberType {
         match self {
             Number::Integer(_) => NumberType::Integer,
+            Number::BigInt(_) => NumberType::BigInt,
+            Number::Rational(_) => NumberType::Rational,
             Number::Float(_) => NumberType::Float,
         }
     }
@@ -83,32 +185,175 @@ impl Number {
     fn cast(self, t: &NumberType) -> Number {
         match t {
             NumberType::Integer => Number::Integer(self.into()),
+            NumberType::BigInt => Number::BigInt(self.into_big_int()),
+            NumberType::Rational => Number::Rational(self.into_rational()),
             NumberType::Float => Number::Float(self.into()),
         }
     }
+
+    fn into_big_int(self) -> BigInt {
+        match self {
+            Number::Integer(n) => BigInt::from(n),
+            Number::BigInt(n) => n,
+            Number::Rational(n) => n.to_integer(),
+            Number::Float(n) => BigInt::from_f64(n).unwrap_or_default(),
+        }
+    }
+
+    fn into_rational(self) -> BigRational {
+        match self {
+            Number::Integer(n) => BigRational::from_integer(BigInt::from(n)),
+            Number::BigInt(n) => BigRational::from_integer(n),
+            Number::Rational(n) => n,
+            Number::Float(n) => BigRational::from_float(n).unwrap_or_default(),
+        }
+    }
 }
 
-#[derive(PartialEq)]
-enum NumberType {
-    Integer,
-    Float,
+/// Maximal size of the integer part of the number in bits. Operation which
+/// returns a bigger value fails with [ArithmeticError::Overflow].
+pub const MAX_NUMBER_BITS: u64 = 1 << 20;
+
+/// Error of the arithmetic operation on integer or rational numbers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArithmeticError {
+    /// Result doesn't fit into [MAX_NUMBER_BITS].
+    Overflow,
+    /// Integer or rational number is divided by zero.
+    DivisionByZero,
+}
+
+impl ArithmeticError {
+    /// Returns symbol which is used as an error code in the error atom.
+    pub fn as_atom(&self) -> Atom {
+        match self {
+            Self::Overflow => ARITHMETIC_OVERFLOW_SYMBOL,
+            Self::DivisionByZero => DIVISION_BY_ZERO_SYMBOL,
+        }
+    }
+}
+
+impl Display for ArithmeticError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_atom())
+    }
+}
+
+impl From<ArithmeticError> for ExecError {
+    fn from(err: ArithmeticError) -> Self {
+        ExecError::Runtime(err.to_string())
+    }
 }
 
-impl NumberType {
-    fn widest_type(a: NumberType, b: NumberType) -> NumberType {
-        // wanted using std::cmp::max but looks like this approach is much much simpler
-        if a == NumberType::Float || b == NumberType::Float {
-            NumberType::Float
+macro_rules! def_checked_number_op {
+    ($name:ident, $method:ident, $doc:literal) => {
+        #[doc = $doc]
+        pub fn $name(self, other: Number) -> Result<Number, ArithmeticError> {
+            let res = match Number::promote(self, other) {
+                (Number::Integer(a), Number::Integer(b)) => match a.$name(b) {
+                    Some(n) => Number::Integer(n),
+                    None => Number::from(BigInt::from(a).$method(BigInt::from(b))),
+                },
+                (Number::BigInt(a), Number::BigInt(b)) => Number::from(a.$method(b)),
+                (Number::Rational(a), Number::Rational(b)) => Number::from(a.$method(b)),
+                (Number::Float(a), Number::Float(b)) => Number::Float(a.$method(b)),
+                _ => panic!("Unexpected state!"),
+            };
+            res.check_size()
+        }
+    }
+}
+
+impl Number {
+    def_checked_number_op!(checked_add, add, "Sums numbers, returns an error if result is too big.");
+    def_checked_number_op!(checked_sub, sub, "Subtracts numbers, returns an error if result is too big.");
+    def_checked_number_op!(checked_mul, mul, "Multiplies numbers, returns an error if result is too big.");
+
+    /// Divides numbers. Division of integers is exact, it returns rational
+    /// number when dividend is not divisible by divisor. Returns an error
+    /// when integer or rational number is divided by zero.
+    pub fn checked_div(self, other: Number) -> Result<Number, ArithmeticError> {
+        let res = match Number::promote(self, other) {
+            (Number::Float(a), Number::Float(b)) => Number::Float(a / b),
+            (_, b) if b.is_zero() => return Err(ArithmeticError::DivisionByZero),
+            (Number::Integer(a), Number::Integer(b)) if a.checked_rem(b) == Some(0) =>
+                Number::Integer(a / b),
+            (Number::Integer(a), Number::Integer(b)) =>
+                Number::from(BigRational::new(BigInt::from(a), BigInt::from(b))),
+            (Number::BigInt(a), Number::BigInt(b)) => Number::from(BigRational::new(a, b)),
+            (Number::Rational(a), Number::Rational(b)) => Number::from(a / b),
+            _ => panic!("Unexpected state!"),
+        };
+        res.check_size()
+    }
+
+    /// Returns remainder of the division. Returns an error when integer or
+    /// rational number is divided by zero.
+    pub fn checked_rem(self, other: Number) -> Result<Number, ArithmeticError> {
+        let res = match Number::promote(self, other) {
+            (Number::Float(a), Number::Float(b)) => Number::Float(a % b),
+            (_, b) if b.is_zero() => return Err(ArithmeticError::DivisionByZero),
+            (Number::Integer(a), Number::Integer(b)) => match a.checked_rem(b) {
+                Some(n) => Number::Integer(n),
+                None => Number::from(BigInt::from(a) % BigInt::from(b)),
+            },
+            (Number::BigInt(a), Number::BigInt(b)) => Number::from(a % b),
+            (Number::Rational(a), Number::Rational(b)) => Number::from(a % b),
+            _ => panic!("Unexpected state!"),
+        };
+        res.check_size()
+    }
+
+    /// Raises number to an integer power. Result is exact for integer and
+    /// rational numbers. Returns an error when zero is raised to a negative
+    /// power or result is too big.
+    pub fn checked_powi(self, n: i32) -> Result<Number, ArithmeticError> {
+        match self {
+            Number::Float(f) => Ok(Number::Float(f.powi(n))),
+            base if n < 0 && base.is_zero() => Err(ArithmeticError::DivisionByZero),
+            base => {
+                let base = base.into_rational();
+                // result has at least (bits - 1) * n + 1 bits, checking it
+                // before calculation to not allocate huge numbers
+                let bits = std::cmp::max(base.numer().bits(), base.denom().bits());
+                if (bits - 1).saturating_mul(n.unsigned_abs() as u64) >= MAX_NUMBER_BITS {
+                    return Err(ArithmeticError::Overflow)
+                }
+                Number::from(Pow::pow(base, n)).check_size()
+            },
+        }
+    }
+
+    /// Returns [ArithmeticError::Overflow] if number doesn't fit into
+    /// [MAX_NUMBER_BITS].
+    pub fn check_size(self) -> Result<Number, ArithmeticError> {
+        let bits = match &self {
+            Number::BigInt(n) => n.bits(),
+            Number::Rational(n) => std::cmp::max(n.numer().bits(), n.denom().bits()),
+            Number::Integer(_) | Number::Float(_) => 0,
+        };
+        if bits > MAX_NUMBER_BITS {
+            Err(ArithmeticError::Overflow)
         } else {
-            NumberType::Integer
+            Ok(self)
         }
     }
 }
 
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
+enum NumberType {
+    Integer,
+    BigInt,
+    Rational,
+    Float,
+}
+
 impl Display for Number {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Self::Integer(n) => write!(f, "{}", n),
+            Self::BigInt(n) => write!(f, "{}", n),
+            Self::Rational(n) => write!(f, "{}", n),
             Self::Float(n) => write!(f, "{:?}", n),
         }
     }
@@ -123,6 +368,14 @@ impl Grounded for Number {
         match self {
             &Self::Integer(n) => serializer.serialize_i64(n),
             &Self::Float(n) => serializer.serialize_f64(n),
+            Self::BigInt(n) => match n.to_i64() {
+                Some(n) => serializer.serialize_i64(n),
+                None => serializer.serialize_number_str(&n.to_string()),
+            },
+            Self::Rational(n) => match n.is_integer().then(|| n.to_integer().to_i64()).flatten() {
+                Some(n) => serializer.serialize_i64(n),
+                None => serializer.serialize_number_str(&n.to_string()),
+            },
         }
     }
 }
@@ -141,6 +394,10 @@ impl serial::Serializer for NumberSerializer {
         self.value = Some(Number::Float(v));
         Ok(())
     }
+    fn serialize_number_str(&mut self, v: &str) -> serial::Result {
+        self.value = Some(Number::from_rational_str(v).map_err(|_| serial::Error::NotSupported)?);
+        Ok(())
+    }
 }
 
 impl serial::ConvertingSerializer<Number> for NumberSerializer {
@@ -162,4 +419,43 @@ mod tests {
         assert_eq!(format!("{}", Number::Integer(12345i64)), "12345");
         assert_eq!(format!("{}", Number::Float(123.45f64)), "123.45");
     }
+
+    #[test]
+    fn big_numbers() {
+        assert_eq!(format!("{}", Number::from_int_str("123456789012345678901234567890").unwrap()), "123456789012345678901234567890");
+        assert!(matches!(Number::from_int_str("-9223372036854775808").unwrap(), Number::Integer(i64::MIN)));
+        assert!(matches!(Number::from_int_str("9223372036854775808").unwrap(), Number::BigInt(_)));
+        assert!(matches!(Number::from_rational_str("4/2").unwrap(), Number::Integer(2)));
+        assert_eq!(format!("{}", Number::from_rational_str("-6/4").unwrap()), "-3/2");
+        assert!(Number::from_rational_str("1/0").is_err());
+    }
+
+    #[test]
+    fn big_numbers_promotion() {
+        assert_eq!(Number::from_rational_str("1/2").unwrap(), Number::Float(0.5));
+        assert_eq!(Number::from_int_str("9223372036854775808").unwrap(), Number::Float(9223372036854775808.0));
+        assert!(Number::from_rational_str("1/3").unwrap() < Number::from_rational_str("1/2").unwrap());
+        assert_eq!(Number::Integer(2).checked_powi(-2), Ok(Number::from_rational_str("1/4").unwrap()));
+        assert_eq!(format!("{}", Number::Integer(2).checked_powi(64).unwrap()), "18446744073709551616");
+    }
+
+    #[test]
+    fn big_numbers_serialization() {
+        let big = Number::from_int_str("123456789012345678901234567890").unwrap();
+        let third = Number::from_rational_str("1/3").unwrap();
+
+        let mut text = String::new();
+        big.serialize(&mut text).unwrap();
+        assert_eq!(text, "123456789012345678901234567890");
+        let mut text = String::new();
+        third.serialize(&mut text).unwrap();
+        assert_eq!(text, "1/3");
+
+        let mut serializer = NumberSerializer::default();
+        big.serialize(&mut serializer).unwrap();
+        assert!(matches!(serializer.into_type(), Some(Number::BigInt(n)) if n.to_string() == "123456789012345678901234567890"));
+        let mut serializer = NumberSerializer::default();
+        third.serialize(&mut serializer).unwrap();
+        assert!(matches!(serializer.into_type(), Some(Number::Rational(n)) if n.to_string() == "1/3"));
+    }
 }
@@ -0,0 +1,105 @@
+//! Output sink receives the text printed by MeTTa operations like `println!`
+//! and `trace!`. It allows host to capture, tag or redirect the output of the
+//! MeTTa code.
+
+use std::io::Write;
+use std::sync::Mutex;
+
+/// Stream which text is printed to
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OutputStream {
+    /// Regular output, printed by `println!` for example
+    Stdout,
+    /// Diagnostic output, printed by `trace!` for example
+    Stderr,
+}
+
+/// Receives the text printed by MeTTa operations. Text passed contains
+/// trailing new line characters if any.
+pub trait OutputSink: std::fmt::Debug + Send + Sync {
+    fn write(&self, stream: OutputStream, text: &str);
+}
+
+/// Default sink which writes text into the process stdout and stderr
+#[derive(Debug, Default)]
+pub struct StdOutputSink;
+
+impl OutputSink for StdOutputSink {
+    fn write(&self, stream: OutputStream, text: &str) {
+        match stream {
+            OutputStream::Stdout => {
+                let mut out = std::io::stdout().lock();
+                let _ = out.write_all(text.as_bytes()).and_then(|()| out.flush());
+            },
+            OutputStream::Stderr => {
+                let _ = std::io::stderr().lock().write_all(text.as_bytes());
+            },
+        }
+    }
+}
+
+/// Sink which collects the printed text in memory
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+/// use hyperon::metta::runner::{Metta, EnvBuilder};
+/// use hyperon::metta::runner::output::{BufferOutputSink, OutputStream};
+/// use hyperon::metta::text::SExprParser;
+///
+/// let output = Arc::new(BufferOutputSink::new());
+/// let metta = Metta::new(Some(EnvBuilder::test_env()));
+/// metta.set_output_sink(output.clone());
+/// metta.run(SExprParser::new("!(println! \"Hello\")")).unwrap();
+///
+/// assert_eq!(output.text(OutputStream::Stdout), "Hello\n");
+/// ```
+#[derive(Debug, Default)]
+pub struct BufferOutputSink {
+    chunks: Mutex<Vec<(OutputStream, String)>>,
+}
+
+impl BufferOutputSink {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Returns text printed into the stream so far
+    pub fn text(&self, stream: OutputStream) -> String {
+        self.chunks.lock().unwrap().iter()
+            .filter(|(s, _)| *s == stream)
+            .map(|(_, text)| text.as_str())
+            .collect()
+    }
+
+    /// Returns all printed chunks in order and clears the buffer
+    pub fn take(&self) -> Vec<(OutputStream, String)> {
+        std::mem::take(&mut *self.chunks.lock().unwrap())
+    }
+}
+
+impl OutputSink for BufferOutputSink {
+    fn write(&self, stream: OutputStream, text: &str) {
+        self.chunks.lock().unwrap().push((stream, text.into()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn buffer_output_sink() {
+        let sink = BufferOutputSink::new();
+        sink.write(OutputStream::Stdout, "a\n");
+        sink.write(OutputStream::Stderr, "b\n");
+        sink.write(OutputStream::Stdout, "c\n");
+
+        assert_eq!(sink.text(OutputStream::Stdout), "a\nc\n");
+        assert_eq!(sink.text(OutputStream::Stderr), "b\n");
+        assert_eq!(sink.take(), vec![(OutputStream::Stdout, "a\n".into()),
+            (OutputStream::Stderr, "b\n".into()), (OutputStream::Stdout, "c\n".into())]);
+        assert_eq!(sink.take(), vec![]);
+    }
+}
@@ -441,6 +441,9 @@ impl ModuleLoader for SingleFileModule {
             _ => Err("unsupported resource key".to_string())
         }
     }
+    fn source_paths(&self) -> Vec<PathBuf> {
+        vec![self.path.clone()]
+    }
 }
 
 /// A loader for a MeTTa module implemented as a directory
@@ -492,6 +495,9 @@ impl ModuleLoader for DirModule {
             _ => Err("unsupported resource key".to_string())
         }
     }
+    fn source_paths(&self) -> Vec<PathBuf> {
+        vec![self.path.join("module.metta")]
+    }
 }
 
 /// Implemented on a type to test if a given file-system path points to a MeTTa module, and to construct
@@ -833,6 +839,26 @@ mod tests {
         assert!(runner.get_module_by_name("a:B").is_err());
     }
 
+    /// This tests the source paths reported by the loaders of the modules loaded from the file system
+    #[test]
+    fn module_source_paths_test() {
+        let dir = std::env::temp_dir().join(format!("hyperon-source-paths-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join("dir_mod")).unwrap();
+        std::fs::write(dir.join("file_mod.metta"), "(file-mod-atom)").unwrap();
+        std::fs::write(dir.join("dir_mod").join("module.metta"), "(dir-mod-atom)").unwrap();
+
+        let runner = Metta::new(Some(EnvBuilder::test_env().set_working_dir(Some(&dir))));
+        let result = runner.run(SExprParser::new("!(import! &self file_mod) !(import! &self dir_mod)"));
+        assert_eq!(result, Ok(vec![vec![expr!()], vec![expr!()]]));
+
+        let paths: Vec<PathBuf> = runner.module_source_paths().iter().map(|path| path.canonicalize().unwrap()).collect();
+        let _ = std::fs::remove_dir_all(&dir);
+        assert_eq!(paths.len(), 2);
+        assert!(paths.iter().any(|path| path.ends_with("file_mod.metta")));
+        assert!(paths.iter().any(|path| path.ends_with("dir_mod/module.metta")));
+    }
+
     //
     //LP-TODO-NEXT, Next make sure the catalogs are able to do the recursive loading from the file system,
     // using their working dirs.  Maybe make this second test a C API test to get better coverage
@@ -0,0 +1,310 @@
+//! Sandbox profile restricts capabilities available to MeTTa code executed by
+//! the runner. It is used to run code which is not trusted by the host.
+
+use std::cell::RefCell;
+use std::collections::BTreeSet;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+/// Source which a module can be loaded from
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ModuleSource {
+    /// Modules loaded from the file system: working directory, include paths
+    /// and `register-module!` operation
+    FileSystem,
+    /// Modules fetched from git repositories: git catalogs and `git-module!`
+    /// operation
+    Git,
+}
+
+/// Declares which grounded operations and module sources are available to
+/// MeTTa code, and which resource limits are applied to its evaluation.
+///
+/// Default profile doesn't restrict anything. Denied operations are replaced
+/// in the tokenizer by stubs which return an `Error` atom when called.
+///
+/// # Examples
+///
+/// ```
+/// use hyperon::metta::runner::{Metta, EnvBuilder, SandboxProfile};
+/// use hyperon::metta::text::SExprParser;
+///
+/// let profile = SandboxProfile::untrusted().set_max_steps(Some(100_000));
+/// let metta = Metta::new(Some(EnvBuilder::test_env().set_sandbox(profile)));
+/// let result = metta.run(SExprParser::new("!(println! \"hello\")")).unwrap();
+///
+/// assert_eq!(result[0][0].to_string(), "(Error (println! \"hello\") println! is denied by the sandbox profile)");
+/// ```
+#[derive(Clone, Debug)]
+pub struct SandboxProfile {
+    denied_ops: BTreeSet<String>,
+    module_sources: BTreeSet<ModuleSource>,
+    max_steps: Option<usize>,
+    max_duration: Option<Duration>,
+}
+
+/// Operations which are denied by the [SandboxProfile::untrusted] profile
+const UNTRUSTED_DENIED_OPS: &[&str] = &["import!", "include", "register-module!",
+    "git-module!", "println!", "trace!", "print-alternatives!", "pragma!", "print-mods!", "unload-module!", "reload-module!",
+    "read-file", "write-file", "append-file", "list-dir", "file-exists", "read-line", "read-all",
+    "get-env"];
+
+impl Default for SandboxProfile {
+    fn default() -> Self {
+        Self {
+            denied_ops: BTreeSet::new(),
+            module_sources: [ModuleSource::FileSystem, ModuleSource::Git].into(),
+            max_steps: None,
+            max_duration: None,
+        }
+    }
+}
+
+impl SandboxProfile {
+    /// Returns profile which doesn't restrict anything
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Returns profile suitable for the code received from untrusted party.
+    /// It denies module loading, input and output, changing runner settings, file
+    /// system access and reading environment variables. Resource limits are not set and should be configured
+    /// by the caller.
+    pub fn untrusted() -> Self {
+        let mut profile = Self::new()
+            .deny_module_source(ModuleSource::FileSystem)
+            .deny_module_source(ModuleSource::Git);
+        profile.denied_ops.extend(UNTRUSTED_DENIED_OPS.iter().map(|op| op.to_string()));
+        profile
+    }
+
+    /// Denies the grounded operation with the given token
+    pub fn deny_op(mut self, token: &str) -> Self {
+        self.denied_ops.insert(token.into());
+        self
+    }
+
+    /// Allows the grounded operation with the given token
+    pub fn allow_op(mut self, token: &str) -> Self {
+        self.denied_ops.remove(token);
+        self
+    }
+
+    /// Denies loading modules from the source
+    pub fn deny_module_source(mut self, source: ModuleSource) -> Self {
+        self.module_sources.remove(&source);
+        self
+    }
+
+    /// Allows loading modules from the source
+    pub fn allow_module_source(mut self, source: ModuleSource) -> Self {
+        self.module_sources.insert(source);
+        self
+    }
+
+    /// Sets maximal number of interpreter steps which can be spent to
+    /// evaluate a single top-level expression. Steps of the nested
+    /// interpreters started by grounded operations are counted as well.
+    pub fn set_max_steps(mut self, max_steps: Option<usize>) -> Self {
+        self.max_steps = max_steps;
+        self
+    }
+
+    /// Sets maximal time which can be spent to evaluate a single top-level
+    /// expression
+    pub fn set_max_duration(mut self, max_duration: Option<Duration>) -> Self {
+        self.max_duration = max_duration;
+        self
+    }
+
+    /// Returns true if operation with the given token is allowed
+    pub fn is_op_allowed(&self, token: &str) -> bool {
+        !self.denied_ops().any(|op| op == token)
+    }
+
+    /// Returns tokens of the denied operations including operations which
+    /// load modules from the denied sources
+    pub fn denied_ops(&self) -> impl Iterator<Item=&str> + '_ {
+        let source_ops = [(ModuleSource::FileSystem, "register-module!"), (ModuleSource::Git, "git-module!")];
+        self.denied_ops.iter().map(String::as_str)
+            .chain(source_ops.into_iter()
+                .filter(|(source, op)| !self.is_module_source_allowed(*source) && !self.denied_ops.contains(*op))
+                .map(|(_, op)| op))
+    }
+
+    /// Returns true if modules can be loaded from the source
+    pub fn is_module_source_allowed(&self, source: ModuleSource) -> bool {
+        self.module_sources.contains(&source)
+    }
+
+    pub fn max_steps(&self) -> Option<usize> {
+        self.max_steps
+    }
+
+    pub fn max_duration(&self) -> Option<Duration> {
+        self.max_duration
+    }
+}
+
+/// Resource limit which was exceeded during evaluation
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LimitExceeded {
+    Steps,
+    Duration,
+}
+
+impl LimitExceeded {
+    pub fn as_atom(&self) -> crate::Atom {
+        match self {
+            Self::Steps => crate::metta::STEP_LIMIT_EXCEEDED_SYMBOL,
+            Self::Duration => crate::metta::TIME_LIMIT_EXCEEDED_SYMBOL,
+        }
+    }
+}
+
+impl std::fmt::Display for LimitExceeded {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_atom())
+    }
+}
+
+/// Counts resources spent on evaluation of a single top-level expression
+#[derive(Debug)]
+pub(crate) struct ResourceMeter {
+    steps_left: Option<usize>,
+    deadline: Option<Instant>,
+}
+
+impl ResourceMeter {
+    pub(crate) fn new(profile: &SandboxProfile) -> Self {
+        Self {
+            steps_left: profile.max_steps,
+            deadline: profile.max_duration.map(|duration| Instant::now() + duration),
+        }
+    }
+
+    fn charge_step(&mut self) -> Result<(), LimitExceeded> {
+        match &mut self.steps_left {
+            Some(0) => return Err(LimitExceeded::Steps),
+            Some(steps) => *steps -= 1,
+            None => {},
+        }
+        match self.deadline {
+            Some(deadline) if Instant::now() > deadline => Err(LimitExceeded::Duration),
+            _ => Ok(()),
+        }
+    }
+}
+
+thread_local! {
+    static CURRENT_METER: RefCell<Option<Rc<RefCell<ResourceMeter>>>> = const { RefCell::new(None) };
+}
+
+/// Makes the meter current for the interpreter steps performed until the
+/// returned guard is dropped. Nested interpreters started by grounded
+/// operations don't have access to the runner, thus the meter is passed to
+/// them via thread local variable.
+pub(crate) fn enter_meter(meter: Rc<RefCell<ResourceMeter>>) -> MeterGuard {
+    let prev = CURRENT_METER.with(|current| current.replace(Some(meter)));
+    MeterGuard(prev)
+}
+
+pub(crate) struct MeterGuard(Option<Rc<RefCell<ResourceMeter>>>);
+
+impl Drop for MeterGuard {
+    fn drop(&mut self) {
+        let prev = self.0.take();
+        CURRENT_METER.with(|current| *current.borrow_mut() = prev);
+    }
+}
+
+/// Charges one interpreter step to the current meter if any
+pub(crate) fn charge_step() -> Result<(), LimitExceeded> {
+    CURRENT_METER.with(|current| match &*current.borrow() {
+        Some(meter) => meter.borrow_mut().charge_step(),
+        None => Ok(()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::*;
+    use crate::metta::*;
+    use crate::metta::runner::{Metta, EnvBuilder};
+    use crate::metta::text::SExprParser;
+
+    fn run_sandboxed(profile: SandboxProfile, program: &str) -> Result<Vec<Vec<Atom>>, String> {
+        let metta = Metta::new(Some(EnvBuilder::test_env().set_sandbox(profile)));
+        metta.run(SExprParser::new(program))
+    }
+
+    fn error_message(results: &[Vec<Atom>]) -> String {
+        match <&[Atom]>::try_from(&results[0][0]) {
+            Ok([error, _, message]) if *error == ERROR_SYMBOL => message.to_string(),
+            _ => panic!("Error is expected, found: {:?}", results),
+        }
+    }
+
+    #[test]
+    fn denied_ops_include_module_source_ops() {
+        let profile = SandboxProfile::new()
+            .deny_op("println!")
+            .deny_module_source(ModuleSource::Git);
+        assert_eq!(profile.denied_ops().collect::<Vec<_>>(), vec!["println!", "git-module!"]);
+        assert!(profile.is_op_allowed("register-module!"));
+        assert!(!profile.is_op_allowed("git-module!"));
+
+        let profile = SandboxProfile::untrusted().allow_op("println!");
+        assert!(profile.is_op_allowed("println!"));
+        assert!(!profile.is_op_allowed("print-alternatives!"));
+        assert!(!profile.is_op_allowed("import!"));
+        assert!(!profile.is_module_source_allowed(ModuleSource::FileSystem));
+    }
+
+    #[test]
+    fn meter_counts_steps() {
+        let meter = Rc::new(RefCell::new(ResourceMeter::new(&SandboxProfile::new().set_max_steps(Some(2)))));
+        assert_eq!(charge_step(), Ok(()));
+        {
+            let _guard = enter_meter(meter.clone());
+            assert_eq!(charge_step(), Ok(()));
+            assert_eq!(charge_step(), Ok(()));
+            assert_eq!(charge_step(), Err(LimitExceeded::Steps));
+        }
+        assert_eq!(charge_step(), Ok(()));
+    }
+
+    #[test]
+    fn denied_ops_are_replaced_by_stubs() {
+        let result = run_sandboxed(SandboxProfile::untrusted(), "!(import! &self some-module)").unwrap();
+        assert_eq!(error_message(&result), "import! is denied by the sandbox profile");
+        let result = run_sandboxed(SandboxProfile::untrusted(), "!(pragma! type-check auto)").unwrap();
+        assert_eq!(error_message(&result), "pragma! is denied by the sandbox profile");
+
+        let result = run_sandboxed(SandboxProfile::untrusted().allow_op("pragma!"), "!(pragma! type-check auto)");
+        assert_eq!(result, Ok(vec![vec![UNIT_ATOM]]));
+        let result = run_sandboxed(SandboxProfile::untrusted(), "!(+ 1 2)").unwrap();
+        assert_eq!(result[0][0].to_string(), "3");
+    }
+
+    #[test]
+    fn step_limit_is_enforced() {
+        let profile = SandboxProfile::new().set_max_steps(Some(1000));
+        let result = run_sandboxed(profile.clone(), "(= (loop) (loop)) !(loop) !(A)");
+        assert_eq!(result, Ok(vec![vec![expr!("Error" ("loop") "StepLimitExceeded")]]));
+
+        let result = run_sandboxed(profile.clone(), "(= (loop) (loop)) !(collapse (loop))").unwrap();
+        assert_eq!(result[0][0].to_string(), "(Error (collapse (loop)) StepLimitExceeded)");
+
+        let result = run_sandboxed(profile, "(= (loop) (loop)) !(A) !(B)");
+        assert_eq!(result, Ok(vec![vec![expr!(("A"))], vec![expr!(("B"))]]));
+    }
+
+    #[test]
+    fn time_limit_is_enforced() {
+        let profile = SandboxProfile::new().set_max_duration(Some(Duration::from_millis(10)));
+        let result = run_sandboxed(profile, "(= (loop) (loop)) !(loop)");
+        assert_eq!(result, Ok(vec![vec![expr!("Error" ("loop") "TimeLimitExceeded")]]));
+    }
+}
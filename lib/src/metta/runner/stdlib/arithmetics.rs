@@ -8,7 +8,7 @@ use crate::metta::runner::bool::*;
 use std::fmt::Display;
 
 macro_rules! def_binary_number_op {
-    ($name:ident, $op:tt, $r:ident, $ret_type:ident) => {
+    ($name:ident, $op:tt, $method:ident) => {
         #[derive(Clone, PartialEq, Debug)]
         pub struct $name{}
 
@@ -20,7 +20,7 @@ macro_rules! def_binary_number_op {
 
         impl Grounded for $name {
             fn type_(&self) -> Atom {
-                Atom::expr([ARROW_SYMBOL, ATOM_TYPE_NUMBER, ATOM_TYPE_NUMBER, $r])
+                Atom::expr([ARROW_SYMBOL, ATOM_TYPE_NUMBER, ATOM_TYPE_NUMBER, ATOM_TYPE_NUMBER])
             }
 
             fn as_execute(&self) -> Option<&dyn CustomExecute> {
@@ -34,28 +34,55 @@ macro_rules! def_binary_number_op {
                 let a = args.get(0).and_then(Number::from_atom).ok_or_else(arg_error)?;
                 let b = args.get(1).and_then(Number::from_atom).ok_or_else(arg_error)?;
 
-                let (a, b) = Number::promote(a, b);
-                let res: $ret_type = match (a, b) {
-                    (Number::Integer(a), Number::Integer(b)) => (a $op b).into(),
-                    (Number::Float(a), Number::Float(b)) => (a $op b).into(),
-                    _ => panic!("Unexpected state"),
-                };
+                Ok(vec![Atom::gnd(a.$method(b)?)])
+            }
+        }
+    }
+}
+
+def_binary_number_op!(SumOp, +, checked_add);
+def_binary_number_op!(SubOp, -, checked_sub);
+def_binary_number_op!(MulOp, *, checked_mul);
+def_binary_number_op!(DivOp, /, checked_div);
+def_binary_number_op!(ModOp, %, checked_rem);
+
+macro_rules! def_number_compare_op {
+    ($name:ident, $op:tt) => {
+        #[derive(Clone, PartialEq, Debug)]
+        pub struct $name{}
 
-                Ok(vec![Atom::gnd(res)])
+        impl Display for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(f, stringify!($op))
+            }
+        }
+
+        impl Grounded for $name {
+            fn type_(&self) -> Atom {
+                Atom::expr([ARROW_SYMBOL, ATOM_TYPE_NUMBER, ATOM_TYPE_NUMBER, ATOM_TYPE_BOOL])
+            }
+
+            fn as_execute(&self) -> Option<&dyn CustomExecute> {
+                Some(self)
+            }
+        }
+
+        impl CustomExecute for $name {
+            fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+                let arg_error = || ExecError::IncorrectArgument;
+                let a = args.get(0).and_then(Number::from_atom).ok_or_else(arg_error)?;
+                let b = args.get(1).and_then(Number::from_atom).ok_or_else(arg_error)?;
+
+                Ok(vec![Atom::gnd(Bool(a $op b))])
             }
         }
     }
 }
 
-def_binary_number_op!(SumOp, +, ATOM_TYPE_NUMBER, Number);
-def_binary_number_op!(SubOp, -, ATOM_TYPE_NUMBER, Number);
-def_binary_number_op!(MulOp, *, ATOM_TYPE_NUMBER, Number);
-def_binary_number_op!(DivOp, /, ATOM_TYPE_NUMBER, Number);
-def_binary_number_op!(ModOp, %, ATOM_TYPE_NUMBER, Number);
-def_binary_number_op!(LessOp, <, ATOM_TYPE_BOOL, Bool);
-def_binary_number_op!(GreaterOp, >, ATOM_TYPE_BOOL, Bool);
-def_binary_number_op!(LessEqOp, <=, ATOM_TYPE_BOOL, Bool);
-def_binary_number_op!(GreaterEqOp, >=, ATOM_TYPE_BOOL, Bool);
+def_number_compare_op!(LessOp, <);
+def_number_compare_op!(GreaterOp, >);
+def_number_compare_op!(LessEqOp, <=);
+def_number_compare_op!(GreaterEqOp, >=);
 
 macro_rules! def_binary_bool_op {
     ($name:ident, $disp:ident, $op:tt) => {
@@ -128,6 +155,8 @@ impl CustomExecute for NotOp {
 pub fn register_rust_stdlib_tokens(tref: &mut Tokenizer) {
     tref.register_fallible_token(regex(r"[\-\+]?\d+"),
         |token| { Ok(Atom::gnd(Number::from_int_str(token)?)) });
+    tref.register_fallible_token(regex(r"[\-\+]?\d+/\d+"),
+        |token| { Ok(Atom::gnd(Number::from_rational_str(token)?)) });
     tref.register_fallible_token(regex(r"[\-\+]?\d+\.\d+"),
         |token| { Ok(Atom::gnd(Number::from_float_str(token)?)) });
     tref.register_fallible_token(regex(r"[\-\+]?\d+(\.\d+)?[eE][\-\+]?\d+"),
@@ -167,6 +196,7 @@ pub fn register_rust_stdlib_tokens(tref: &mut Tokenizer) {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::metta::runner::stdlib::tests::run_program;
 
     macro_rules! assert_binary_op {
         ($name:ident, $a: expr, $b: expr, $r: expr) => {
@@ -241,4 +271,48 @@ mod tests {
         assert_binary_op!(ModOp, Number::Float(85.5), Number::Integer(43), Number::Float(42.5));
         assert_binary_op!(ModOp, Number::Float(85.5), Number::Float(43.5), Number::Float(42.0));
     }
+
+    #[test]
+    fn integer_overflow_promotes_to_big_int() {
+        let res = SumOp{}.execute(&mut vec![Atom::gnd(Number::Integer(i64::MAX)), Atom::gnd(Number::Integer(1))]).unwrap();
+        assert_eq!(res[0].to_string(), "9223372036854775808");
+        let res = SubOp{}.execute(&mut vec![res[0].clone(), Atom::gnd(Number::Integer(1))]).unwrap();
+        assert!(matches!(res[0].as_gnd::<Number>(), Some(Number::Integer(i64::MAX))));
+        let res = MulOp{}.execute(&mut vec![Atom::gnd(Number::Integer(i64::MIN)), Atom::gnd(Number::Integer(-1))]).unwrap();
+        assert_eq!(res[0].to_string(), "9223372036854775808");
+    }
+
+    #[test]
+    fn integer_division_is_exact() {
+        let res = DivOp{}.execute(&mut vec![Atom::gnd(Number::Integer(7)), Atom::gnd(Number::Integer(2))]).unwrap();
+        assert_eq!(res[0].to_string(), "7/2");
+        let res = SumOp{}.execute(&mut vec![res[0].clone(), Atom::gnd(Number::from_rational_str("1/2").unwrap())]).unwrap();
+        assert!(matches!(res[0].as_gnd::<Number>(), Some(Number::Integer(4))));
+        assert_binary_op!(LessOp, Number::from_rational_str("1/3").unwrap(), Number::Float(0.34), Bool(true));
+        assert_binary_op!(GreaterOp, Number::from_int_str("100000000000000000000").unwrap(), Number::Integer(i64::MAX), Bool(true));
+    }
+
+    #[test]
+    fn division_by_zero() {
+        let division_by_zero = Err(ExecError::Runtime("DivisionByZero".into()));
+        assert_eq!(DivOp{}.execute(&mut vec![Atom::gnd(Number::Integer(1)), Atom::gnd(Number::Integer(0))]), division_by_zero);
+        assert_eq!(ModOp{}.execute(&mut vec![Atom::gnd(Number::Integer(1)), Atom::gnd(Number::Integer(0))]), division_by_zero);
+        assert_eq!(DivOp{}.execute(&mut vec![Atom::gnd(Number::from_rational_str("1/2").unwrap()), Atom::gnd(Number::Integer(0))]), division_by_zero);
+        assert_binary_op!(DivOp, Number::Float(1.0), Number::Integer(0), Number::Float(f64::INFINITY));
+        assert_binary_op!(ModOp, Number::Integer(i64::MIN), Number::Integer(-1), Number::Integer(0));
+    }
+
+    #[test]
+    fn arithmetic_overflow() {
+        let big = Number::Integer(2).checked_powi(MAX_NUMBER_BITS as i32 - 1).unwrap();
+        assert_eq!(MulOp{}.execute(&mut vec![Atom::gnd(big), Atom::gnd(Number::Integer(4))]),
+            Err(ExecError::Runtime("ArithmeticOverflow".into())));
+        assert_eq!(Number::Integer(2).checked_powi(MAX_NUMBER_BITS as i32 + 1), Err(ArithmeticError::Overflow));
+    }
+
+    #[test]
+    fn metta_arithmetic_errors() {
+        assert_eq!(run_program("!(/ 1 0)"), Ok(vec![vec![expr!("Error" ({DivOp{}} {Number::Integer(1)} {Number::Integer(0)}) "DivisionByZero")]]));
+        assert_eq!(run_program("!(chain (eval (% 5 0)) $r (eval (if-error $r caught not-caught)))"), Ok(vec![vec![expr!("caught")]]));
+    }
 }
@@ -2,13 +2,14 @@ use crate::*;
 use crate::space::*;
 use crate::metta::*;
 use crate::metta::text::Tokenizer;
-use crate::metta::types::{get_atom_types, get_meta_type};
+use crate::metta::types::{get_atom_types, get_meta_type, infer_types, InferredTypesCache};
 use crate::common::multitrie::{MultiTrie, TrieKey, TrieToken};
 use crate::common::collections::ImmutableString;
-use super::{grounded_op, regex};
+use super::{grounded_op, unit_result, regex};
 use crate::metta::runner::number::*;
 
 use std::convert::TryInto;
+use std::rc::Rc;
 use std::hash::{DefaultHasher, Hasher};
 
 #[derive(Clone, Debug)]
@@ -97,6 +98,7 @@ fn atom_to_trie_key(atom: &Atom) -> TrieKey<SymbolAtom> {
                 // TODO: Adding Hash on grounded atoms matched by equality is
                 // required in order to make TrieToken::Exact be generated for
                 // them.
+                // Keys are dropped with the trie, thus they are not interned
                 let mut h = DefaultHasher::new();
                 match (*g).serialize(&mut h) {
                     Ok(()) => { tokens.push(TrieToken::Exact(SymbolAtom::new(ImmutableString::Allocated(h.finish().to_string())))) }
@@ -352,13 +354,14 @@ impl CustomExecute for SubtractionAtomOp {
 #[derive(Clone, Debug)]
 pub struct GetTypeOp {
     space: DynSpace,
+    inferred_types: Rc<InferredTypesCache>,
 }
 
 grounded_op!(GetTypeOp, "get-type");
 
 impl GetTypeOp {
     pub fn new(space: DynSpace) -> Self {
-        Self{ space }
+        Self{ space, inferred_types: Rc::new(InferredTypesCache::new()) }
     }
 }
 
@@ -382,6 +385,11 @@ impl CustomExecute for GetTypeOp {
             None => Ok(&self.space),
         }?;
         let types = get_atom_types(space, atom);
+        let types = match (atom, types.as_slice()) {
+            (Atom::Symbol(_) | Atom::Expression(_), [typ]) if *typ == ATOM_TYPE_UNDEFINED =>
+                self.inferred_types.infer_atom_type(space, atom).map_or(types, |typ| vec![typ]),
+            _ => types,
+        };
         if types.is_empty() {
             Ok(vec![EMPTY_SYMBOL])
         } else {
@@ -441,6 +449,35 @@ impl CustomExecute for GetTypeSpaceOp {
     }
 }
 
+#[derive(Clone, Debug)]
+pub struct InferTypesOp {}
+
+grounded_op!(InferTypesOp, "infer-types!");
+
+impl Grounded for InferTypesOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, rust_type_atom::<DynSpace>(), UNIT_TYPE])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for InferTypesOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("infer-types! expects single space as an argument");
+        let space = args.first().ok_or_else(arg_error)?;
+        let space = Atom::as_gnd::<DynSpace>(space).ok_or("infer-types! expects a space as an argument")?;
+        let types = infer_types(space);
+        log::debug!("InferTypesOp::execute: space: {}, inferred types: {:?}", space, types);
+        for typ in types {
+            space.borrow_mut().add(typ);
+        }
+        unit_result()
+    }
+}
+
 pub fn register_common_tokens(tref: &mut Tokenizer, space: &DynSpace) {
     let get_type_space_op = Atom::gnd(GetTypeSpaceOp{});
     tref.register_token(regex(r"get-type-space"), move |_| { get_type_space_op.clone() });
@@ -448,6 +485,8 @@ pub fn register_common_tokens(tref: &mut Tokenizer, space: &DynSpace) {
     tref.register_token(regex(r"get-metatype"), move |_| { get_meta_type_op.clone() });
     let get_type_op = Atom::gnd(GetTypeOp::new(space.clone()));
     tref.register_token(regex(r"get-type"), move |_| { get_type_op.clone() });
+    let infer_types_op = Atom::gnd(InferTypesOp{});
+    tref.register_token(regex(r"infer-types!"), move |_| { infer_types_op.clone() });
 
 
     let min_atom_op = Atom::gnd(MinAtomOp{});
@@ -745,4 +784,26 @@ mod tests {
         assert_eq_no_order!(get_type_op.execute(&mut vec![expr!("f" "\"test\""), expr!({space.clone()})]).unwrap(),
             vec![EMPTY_SYMBOL]);
     }
+
+    #[test]
+    fn get_type_op_inferred_type() {
+        let program = "
+            (: Z Nat)
+            (: S (-> Nat Nat))
+            (= (inc $n) (S $n))
+            !(get-type inc)
+            !(get-type (inc Z))
+            !(infer-types! &self)
+            !(get-type (inc Z))
+            !(get-type (inc 1))
+        ";
+
+        assert_eq_metta_results!(run_program(program), Ok(vec![
+            vec![expr!("->" "Nat" "Nat")],
+            vec![expr!("Nat")],
+            vec![UNIT_ATOM],
+            vec![expr!("Nat")],
+            vec![],
+        ]));
+    }
 }
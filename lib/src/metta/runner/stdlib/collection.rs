@@ -0,0 +1,530 @@
+use crate::*;
+use crate::metta::*;
+use crate::metta::text::Tokenizer;
+use super::{grounded_op, regex};
+use crate::metta::runner::collection::*;
+use crate::metta::runner::number::*;
+use crate::metta::runner::bool::*;
+
+use std::convert::TryInto;
+
+#[derive(Clone, Debug)]
+pub struct NewMapOp {}
+
+grounded_op!(NewMapOp, "new-map");
+
+impl Grounded for NewMapOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_EXPRESSION, ATOM_TYPE_MAP])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for NewMapOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("new-map expects one argument: expression of key-value pairs");
+        let pairs = TryInto::<&ExpressionAtom>::try_into(args.first().ok_or_else(arg_error)?)?;
+        let map = pairs.children().iter()
+            .map(|pair| match <&[Atom]>::try_from(pair) {
+                Ok([key, value]) => Ok((key.clone(), value.clone())),
+                _ => Err(arg_error()),
+            }).collect::<Result<Map, ExecError>>()?;
+        Ok(vec![Atom::gnd(map)])
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct MapInsertOp {}
+
+grounded_op!(MapInsertOp, "map-insert");
+
+impl Grounded for MapInsertOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_MAP, ATOM_TYPE_UNDEFINED, ATOM_TYPE_UNDEFINED, ATOM_TYPE_MAP])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for MapInsertOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("map-insert expects three arguments: map, key and value");
+        let map = args.first().and_then(Map::from_atom).ok_or_else(arg_error)?;
+        let key = args.get(1).ok_or_else(arg_error)?;
+        let value = args.get(2).ok_or_else(arg_error)?;
+        Ok(vec![Atom::gnd(map.insert(key.clone(), value.clone()))])
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct MapRemoveOp {}
+
+grounded_op!(MapRemoveOp, "map-remove");
+
+impl Grounded for MapRemoveOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_MAP, ATOM_TYPE_UNDEFINED, ATOM_TYPE_MAP])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for MapRemoveOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("map-remove expects two arguments: map and key");
+        let map = args.first().and_then(Map::from_atom).ok_or_else(arg_error)?;
+        let key = args.get(1).ok_or_else(arg_error)?;
+        Ok(vec![Atom::gnd(map.remove(key))])
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct MapGetOp {}
+
+grounded_op!(MapGetOp, "map-get");
+
+impl Grounded for MapGetOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_MAP, ATOM_TYPE_UNDEFINED, ATOM_TYPE_UNDEFINED])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for MapGetOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("map-get expects two arguments: map and key");
+        let map = args.first().and_then(Map::from_atom).ok_or_else(arg_error)?;
+        let key = args.get(1).ok_or_else(arg_error)?;
+        Ok(map.get(key).cloned().into_iter().collect())
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct MapContainsOp {}
+
+grounded_op!(MapContainsOp, "map-contains");
+
+impl Grounded for MapContainsOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_MAP, ATOM_TYPE_UNDEFINED, ATOM_TYPE_BOOL])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for MapContainsOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("map-contains expects two arguments: map and key");
+        let map = args.first().and_then(Map::from_atom).ok_or_else(arg_error)?;
+        let key = args.get(1).ok_or_else(arg_error)?;
+        Ok(vec![Atom::gnd(Bool(map.get(key).is_some()))])
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct MapKeysOp {}
+
+grounded_op!(MapKeysOp, "map-keys");
+
+impl Grounded for MapKeysOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_MAP, ATOM_TYPE_EXPRESSION])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for MapKeysOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("map-keys expects one argument: map");
+        let map = args.first().and_then(Map::from_atom).ok_or_else(arg_error)?;
+        let keys = map.entries().into_iter().map(|(k, _)| k.clone()).collect::<Vec<_>>();
+        Ok(vec![Atom::expr(keys)])
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct MapValuesOp {}
+
+grounded_op!(MapValuesOp, "map-values");
+
+impl Grounded for MapValuesOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_MAP, ATOM_TYPE_EXPRESSION])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for MapValuesOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("map-values expects one argument: map");
+        let map = args.first().and_then(Map::from_atom).ok_or_else(arg_error)?;
+        let values = map.entries().into_iter().map(|(_, v)| v.clone()).collect::<Vec<_>>();
+        Ok(vec![Atom::expr(values)])
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct MapSizeOp {}
+
+grounded_op!(MapSizeOp, "map-size");
+
+impl Grounded for MapSizeOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_MAP, ATOM_TYPE_NUMBER])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for MapSizeOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("map-size expects one argument: map");
+        let map = args.first().and_then(Map::from_atom).ok_or_else(arg_error)?;
+        Ok(vec![Atom::gnd(Number::Integer(map.len() as i64))])
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct NewSetOp {}
+
+grounded_op!(NewSetOp, "new-set");
+
+impl Grounded for NewSetOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_EXPRESSION, ATOM_TYPE_SET])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for NewSetOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("new-set expects one argument: expression of items");
+        let items = TryInto::<&ExpressionAtom>::try_into(args.first().ok_or_else(arg_error)?)?;
+        let set: Set = items.children().iter().cloned().collect();
+        Ok(vec![Atom::gnd(set)])
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct SetInsertOp {}
+
+grounded_op!(SetInsertOp, "set-insert");
+
+impl Grounded for SetInsertOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_SET, ATOM_TYPE_UNDEFINED, ATOM_TYPE_SET])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for SetInsertOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("set-insert expects two arguments: set and item");
+        let set = args.first().and_then(Set::from_atom).ok_or_else(arg_error)?;
+        let item = args.get(1).ok_or_else(arg_error)?;
+        Ok(vec![Atom::gnd(set.insert(item.clone()))])
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct SetRemoveOp {}
+
+grounded_op!(SetRemoveOp, "set-remove");
+
+impl Grounded for SetRemoveOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_SET, ATOM_TYPE_UNDEFINED, ATOM_TYPE_SET])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for SetRemoveOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("set-remove expects two arguments: set and item");
+        let set = args.first().and_then(Set::from_atom).ok_or_else(arg_error)?;
+        let item = args.get(1).ok_or_else(arg_error)?;
+        Ok(vec![Atom::gnd(set.remove(item))])
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct SetContainsOp {}
+
+grounded_op!(SetContainsOp, "set-contains");
+
+impl Grounded for SetContainsOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_SET, ATOM_TYPE_UNDEFINED, ATOM_TYPE_BOOL])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for SetContainsOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("set-contains expects two arguments: set and item");
+        let set = args.first().and_then(Set::from_atom).ok_or_else(arg_error)?;
+        let item = args.get(1).ok_or_else(arg_error)?;
+        Ok(vec![Atom::gnd(Bool(set.contains(item)))])
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct SetItemsOp {}
+
+grounded_op!(SetItemsOp, "set-items");
+
+impl Grounded for SetItemsOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_SET, ATOM_TYPE_EXPRESSION])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for SetItemsOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("set-items expects one argument: set");
+        let set = args.first().and_then(Set::from_atom).ok_or_else(arg_error)?;
+        let items = set.items().into_iter().cloned().collect::<Vec<_>>();
+        Ok(vec![Atom::expr(items)])
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct SetSizeOp {}
+
+grounded_op!(SetSizeOp, "set-size");
+
+impl Grounded for SetSizeOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_SET, ATOM_TYPE_NUMBER])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for SetSizeOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("set-size expects one argument: set");
+        let set = args.first().and_then(Set::from_atom).ok_or_else(arg_error)?;
+        Ok(vec![Atom::gnd(Number::Integer(set.len() as i64))])
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct SetUnionOp {}
+
+grounded_op!(SetUnionOp, "set-union");
+
+impl Grounded for SetUnionOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_SET, ATOM_TYPE_SET, ATOM_TYPE_SET])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for SetUnionOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("set-union expects two arguments: set and set");
+        let a = args.first().and_then(Set::from_atom).ok_or_else(arg_error)?;
+        let b = args.get(1).and_then(Set::from_atom).ok_or_else(arg_error)?;
+        Ok(vec![Atom::gnd(a.union(&b))])
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct SetIntersectionOp {}
+
+grounded_op!(SetIntersectionOp, "set-intersection");
+
+impl Grounded for SetIntersectionOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_SET, ATOM_TYPE_SET, ATOM_TYPE_SET])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for SetIntersectionOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("set-intersection expects two arguments: set and set");
+        let a = args.first().and_then(Set::from_atom).ok_or_else(arg_error)?;
+        let b = args.get(1).and_then(Set::from_atom).ok_or_else(arg_error)?;
+        Ok(vec![Atom::gnd(a.intersection(&b))])
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct SetDifferenceOp {}
+
+grounded_op!(SetDifferenceOp, "set-difference");
+
+impl Grounded for SetDifferenceOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_SET, ATOM_TYPE_SET, ATOM_TYPE_SET])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for SetDifferenceOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("set-difference expects two arguments: set and set");
+        let a = args.first().and_then(Set::from_atom).ok_or_else(arg_error)?;
+        let b = args.get(1).and_then(Set::from_atom).ok_or_else(arg_error)?;
+        Ok(vec![Atom::gnd(a.difference(&b))])
+    }
+}
+
+pub fn register_common_tokens(tref: &mut Tokenizer) {
+    let new_map_op = Atom::gnd(NewMapOp{});
+    tref.register_token(regex(r"new-map"), move |_| { new_map_op.clone() });
+    let map_insert_op = Atom::gnd(MapInsertOp{});
+    tref.register_token(regex(r"map-insert"), move |_| { map_insert_op.clone() });
+    let map_remove_op = Atom::gnd(MapRemoveOp{});
+    tref.register_token(regex(r"map-remove"), move |_| { map_remove_op.clone() });
+    let map_get_op = Atom::gnd(MapGetOp{});
+    tref.register_token(regex(r"map-get"), move |_| { map_get_op.clone() });
+    let map_contains_op = Atom::gnd(MapContainsOp{});
+    tref.register_token(regex(r"map-contains"), move |_| { map_contains_op.clone() });
+    let map_keys_op = Atom::gnd(MapKeysOp{});
+    tref.register_token(regex(r"map-keys"), move |_| { map_keys_op.clone() });
+    let map_values_op = Atom::gnd(MapValuesOp{});
+    tref.register_token(regex(r"map-values"), move |_| { map_values_op.clone() });
+    let map_size_op = Atom::gnd(MapSizeOp{});
+    tref.register_token(regex(r"map-size"), move |_| { map_size_op.clone() });
+    let new_set_op = Atom::gnd(NewSetOp{});
+    tref.register_token(regex(r"new-set"), move |_| { new_set_op.clone() });
+    let set_insert_op = Atom::gnd(SetInsertOp{});
+    tref.register_token(regex(r"set-insert"), move |_| { set_insert_op.clone() });
+    let set_remove_op = Atom::gnd(SetRemoveOp{});
+    tref.register_token(regex(r"set-remove"), move |_| { set_remove_op.clone() });
+    let set_contains_op = Atom::gnd(SetContainsOp{});
+    tref.register_token(regex(r"set-contains"), move |_| { set_contains_op.clone() });
+    let set_items_op = Atom::gnd(SetItemsOp{});
+    tref.register_token(regex(r"set-items"), move |_| { set_items_op.clone() });
+    let set_size_op = Atom::gnd(SetSizeOp{});
+    tref.register_token(regex(r"set-size"), move |_| { set_size_op.clone() });
+    let set_union_op = Atom::gnd(SetUnionOp{});
+    tref.register_token(regex(r"set-union"), move |_| { set_union_op.clone() });
+    let set_intersection_op = Atom::gnd(SetIntersectionOp{});
+    tref.register_token(regex(r"set-intersection"), move |_| { set_intersection_op.clone() });
+    let set_difference_op = Atom::gnd(SetDifferenceOp{});
+    tref.register_token(regex(r"set-difference"), move |_| { set_difference_op.clone() });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metta::runner::stdlib::tests::run_program;
+
+    #[test]
+    fn metta_map_ops() {
+        let program = "
+            !(let $m (new-map ((a 1) (b 2))) (map-get (map-insert $m c 3) c))
+            !(let $m (new-map ((a 1) (b 2))) (map-get (map-remove $m a) a))
+            !(let $m (new-map ((a 1) (b 2))) (map-contains $m b))
+            !(let $m (new-map ((b 2) (a 1))) (map-keys $m))
+            !(let $m (new-map ((b 2) (a 1))) (map-values $m))
+            !(map-size (new-map ((a 1) (a 2))))
+            !(map-get (new-map ((a 1))) (+ 1 2))
+        ";
+        assert_eq!(run_program(program), Ok(vec![
+            vec![expr!({Number::Integer(3)})],
+            vec![],
+            vec![expr!({Bool(true)})],
+            vec![expr!("a" "b")],
+            vec![expr!({Number::Integer(1)} {Number::Integer(2)})],
+            vec![expr!({Number::Integer(1)})],
+            vec![],
+        ]));
+    }
+
+    #[test]
+    fn metta_map_is_persistent() {
+        let program = "
+            !(let* (($m (new-map ((a 1))))
+                    ($m2 (map-insert $m a 2)))
+                ((map-get $m a) (map-get $m2 a)))
+        ";
+        assert_eq!(run_program(program), Ok(vec![vec![expr!({Number::Integer(1)} {Number::Integer(2)})]]));
+    }
+
+    #[test]
+    fn metta_set_ops() {
+        let program = "
+            !(set-items (set-insert (new-set (b a)) c))
+            !(set-items (set-remove (new-set (b a)) b))
+            !(set-contains (new-set (a (b c))) (b c))
+            !(set-size (new-set (a b a)))
+            !(set-items (set-union (new-set (a b)) (new-set (b c))))
+            !(set-items (set-intersection (new-set (a b)) (new-set (b c))))
+            !(set-items (set-difference (new-set (a b)) (new-set (b c))))
+        ";
+        assert_eq!(run_program(program), Ok(vec![
+            vec![expr!("a" "b" "c")],
+            vec![expr!(("a"))],
+            vec![expr!({Bool(true)})],
+            vec![expr!({Number::Integer(2)})],
+            vec![expr!("a" "b" "c")],
+            vec![expr!(("b"))],
+            vec![expr!(("a"))],
+        ]));
+    }
+
+    #[test]
+    fn metta_collections_match_by_equality() {
+        let program = "
+            (stored (new-set (a b)))
+            !(match &self (stored $s) (if (== $s (new-set (b a))) ok not-ok))
+            !(== (new-map ((a 1))) (map-insert (new-map ()) a 1))
+        ";
+        assert_eq!(run_program(program), Ok(vec![
+            vec![expr!("ok")],
+            vec![expr!({Bool(true)})],
+        ]));
+    }
+}
@@ -5,21 +5,142 @@ use crate::space::*;
 use crate::common::collections::{VecDisplay, Equality, DefaultEquality};
 use crate::common::assert::compare_vec_no_order;
 use crate::atom::matcher::atoms_are_equivalent;
-use crate::metta::runner::stdlib::{grounded_op, regex, interpret_no_error, unit_result};
+use crate::metta::runner::stdlib::{grounded_op, regex, interpret, unit_result};
 use crate::metta::runner::bool::*;
+use crate::metta::runner::Metta;
+use crate::metta::runner::output::OutputStream;
+use crate::metta::runner::assertion::AssertionOutcome;
 
 use crate::metta::runner::str::atom_to_string;
 
 use std::convert::TryInto;
+use std::collections::HashMap;
+use std::fmt::Display;
 
 fn assert_results_equal(actual: &Vec<Atom>, expected: &Vec<Atom>) -> Result<Vec<Atom>, ExecError> {
     let report = format!("\nExpected: {}\nGot: {}", VecDisplay(expected), VecDisplay(actual));
-    match compare_vec_no_order(actual.iter(), expected.iter(), DefaultEquality{}).as_display() {
+    let diff = compare_vec_no_order(actual.iter(), expected.iter(), DefaultEquality{});
+    match diff.as_display() {
         None => unit_result(),
-        Some(diff) => Err(ExecError::Runtime(format!("{}\n{}", report, diff)))
+        Some(text) => Err(ExecError::Runtime(format!("{}\n{}{}", report, text,
+            structural_diff(diff.missed(), diff.excessive(), false))))
     }
 }
 
+/// Point where the actual atom differs from the expected one
+#[derive(Debug, PartialEq)]
+struct AtomDifference<'a> {
+    /// Indexes of the children leading from the root to the differing subexpression
+    path: Vec<usize>,
+    /// Expected root expression, it is `None` when the roots differ
+    root: Option<&'a Atom>,
+    expected: &'a Atom,
+    actual: &'a Atom,
+    /// Explanation of the difference when it is not obvious from the atoms
+    reason: Option<String>,
+}
+
+impl Display for AtomDifference<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Difference")?;
+        if let Some(root) = self.root {
+            write!(f, " at {:?} in {}", self.path, root)?;
+        }
+        write!(f, ": expected {}, got {}", self.expected, self.actual)?;
+        match &self.reason {
+            Some(reason) => write!(f, "; {}", reason),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Variables renaming which makes atoms alpha-equivalent
+type VarRenaming<'a> = (HashMap<&'a VariableAtom, &'a VariableAtom>, HashMap<&'a VariableAtom, &'a VariableAtom>);
+
+/// Finds the first subexpression of the actual atom which differs from the
+/// expected one. When `alpha` is true variables are compared modulo renaming
+/// the same way [atoms_are_equivalent] does.
+fn first_difference<'a>(expected: &'a Atom, actual: &'a Atom, alpha: bool) -> Option<AtomDifference<'a>> {
+    fn can_be_renamed<'a>(map: &mut HashMap<&'a VariableAtom, &'a VariableAtom>,
+        var: &'a VariableAtom, other: &'a VariableAtom) -> bool {
+        *map.entry(var).or_insert(other) == other
+    }
+
+    fn find<'a>(root: &'a Atom, expected: &'a Atom, actual: &'a Atom, alpha: bool,
+        path: &mut Vec<usize>, vars: &mut VarRenaming<'a>) -> Option<AtomDifference<'a>> {
+        let difference = |path: &Vec<usize>, reason: Option<String>| Some(AtomDifference{
+            path: path.clone(), root: (!path.is_empty()).then_some(root), expected, actual, reason });
+        match (expected, actual) {
+            (Atom::Expression(exp), Atom::Expression(act)) => {
+                if exp.children().len() != act.children().len() {
+                    return difference(path, Some(format!("expected {} elements, got {}",
+                        exp.children().len(), act.children().len())));
+                }
+                for (i, (exp_child, act_child)) in exp.children().iter().zip(act.children().iter()).enumerate() {
+                    path.push(i);
+                    let diff = find(root, exp_child, act_child, alpha, path, vars);
+                    if diff.is_some() {
+                        return diff;
+                    }
+                    path.pop();
+                }
+                None
+            },
+            (Atom::Variable(exp), Atom::Variable(act)) if alpha => {
+                if can_be_renamed(&mut vars.0, exp, act) && can_be_renamed(&mut vars.1, act, exp) {
+                    None
+                } else {
+                    difference(path, Some("variables cannot be renamed consistently".into()))
+                }
+            },
+            (Atom::Grounded(exp), Atom::Grounded(act)) if exp != act => {
+                let (exp_type, act_type) = (exp.type_(), act.type_());
+                if exp_type != act_type {
+                    difference(path, Some(format!("expected value of type {}, got {}", exp_type, act_type)))
+                } else {
+                    difference(path, None)
+                }
+            },
+            _ if expected == actual => None,
+            _ => difference(path, None),
+        }
+    }
+
+    find(expected, expected, actual, alpha, &mut vec![], &mut (HashMap::new(), HashMap::new()))
+}
+
+/// Returns true if both atoms are expressions with the same head and number
+/// of elements.
+fn have_same_shape(a: &Atom, b: &Atom) -> bool {
+    match (a, b) {
+        (Atom::Expression(a), Atom::Expression(b)) =>
+            a.children().len() == b.children().len() && a.children().first() == b.children().first(),
+        _ => false,
+    }
+}
+
+/// Describes the differences between missed and excessive results. Single
+/// missed and excessive results are compared with each other, otherwise each
+/// missed result is compared with the first excessive result of the same
+/// shape, see [have_same_shape]. Differences of the plain atoms are obvious
+/// from the report and skipped.
+fn structural_diff<'a>(missed: impl Iterator<Item=&'a &'a Atom>, excessive: impl Iterator<Item=&'a &'a Atom>, alpha: bool) -> String {
+    let missed: Vec<&Atom> = missed.copied().collect();
+    let mut excessive: Vec<&Atom> = excessive.copied().collect();
+    let pairs: Vec<(&Atom, &Atom)> = match (missed.as_slice(), excessive.as_slice()) {
+        ([expected], [actual]) => vec![(expected, actual)],
+        _ => missed.into_iter().filter_map(|expected| {
+            let i = excessive.iter().position(|actual| have_same_shape(expected, actual))?;
+            Some((expected, excessive.remove(i)))
+        }).collect(),
+    };
+    pairs.into_iter()
+        .filter_map(|(expected, actual)| first_difference(expected, actual, alpha))
+        .filter(|diff| diff.root.is_some() || diff.reason.is_some())
+        .map(|diff| format!("\n{}", diff))
+        .collect()
+}
+
 /// Implement trace! built-in.
 ///
 /// It is equivalent to Idris or Haskell Trace, that is, it prints a
@@ -53,10 +174,18 @@ fn assert_results_equal(actual: &Vec<Atom>, expected: &Vec<Atom>) -> Result<Vec<
 /// ```
 
 #[derive(Clone, Debug)]
-pub struct TraceOp {}
+pub struct TraceOp {
+    metta: Metta,
+}
 
 grounded_op!(TraceOp, "trace!");
 
+impl TraceOp {
+    pub fn new(metta: Metta) -> Self {
+        Self{ metta }
+    }
+}
+
 impl Grounded for TraceOp {
     fn type_(&self) -> Atom {
         Atom::expr([ARROW_SYMBOL, ATOM_TYPE_UNDEFINED, ATOM_TYPE_ATOM, ATOM_TYPE_ATOM])
@@ -72,16 +201,24 @@ impl CustomExecute for TraceOp {
         let arg_error = || ExecError::from("trace! expects two atoms as arguments");
         let val = args.get(1).ok_or_else(arg_error)?;
         let msg = args.get(0).ok_or_else(arg_error)?;
-        eprintln!("{}", msg);
+        self.metta.output_sink().write(OutputStream::Stderr, &format!("{}\n", msg));
         Ok(vec![val.clone()])
     }
 }
 
 #[derive(Clone, Debug)]
-pub struct PrintAlternativesOp {}
+pub struct PrintAlternativesOp {
+    metta: Metta,
+}
 
 grounded_op!(PrintAlternativesOp, "print-alternatives!");
 
+impl PrintAlternativesOp {
+    pub fn new(metta: Metta) -> Self {
+        Self{ metta }
+    }
+}
+
 impl Grounded for PrintAlternativesOp {
     fn type_(&self) -> Atom {
         Atom::expr([ARROW_SYMBOL, ATOM_TYPE_ATOM, ATOM_TYPE_EXPRESSION, UNIT_TYPE])
@@ -100,12 +237,27 @@ impl CustomExecute for PrintAlternativesOp {
         let args: Vec<String> = args.children().iter()
             .map(|atom| atom_to_string(atom))
             .collect();
-        println!("{} {}:", args.len(), atom);
-        args.iter().for_each(|arg| println!("    {}", arg));
+        let mut text = format!("{} {}:\n", args.len(), atom);
+        args.iter().for_each(|arg| text.push_str(&format!("    {}\n", arg)));
+        self.metta.output_sink().write(OutputStream::Stdout, &text);
         Ok(vec![UNIT_ATOM])
     }
 }
 
+/// Reports the outcome of the assertion to the listener of the runner, see
+/// [Metta::set_assertion_listener], and returns the result unchanged. Errors
+/// of the arguments evaluation are reported as failures too.
+pub(super) fn report_assertion(metta: &Metta, name: &str, args: &[Atom], result: Result<Vec<Atom>, ExecError>) -> Result<Vec<Atom>, ExecError> {
+    let assertion = Atom::expr(std::iter::once(Atom::sym(name)).chain(args.iter().cloned()).collect::<Vec<_>>());
+    let failure = match &result {
+        Ok(_) => None,
+        Err(ExecError::Runtime(msg)) => Some(msg.clone()),
+        Err(err) => Some(format!("{:?}", err)),
+    };
+    metta.report_assertion(AssertionOutcome{ assertion: assertion.to_string(), failure });
+    result
+}
+
 struct AlphaEquality{}
 
 impl Equality<&Atom> for AlphaEquality {
@@ -116,23 +268,25 @@ impl Equality<&Atom> for AlphaEquality {
 
 fn assert_alpha_equal(actual: &Vec<Atom>, expected: &Vec<Atom>) -> Result<Vec<Atom>, ExecError> {
     let report = format!("\nExpected: {}\nGot: {}", VecDisplay(expected), VecDisplay(actual));
-    let res = compare_vec_no_order(actual.iter(), expected.iter(), AlphaEquality{});
-    match res.as_display() {
+    let diff = compare_vec_no_order(actual.iter(), expected.iter(), AlphaEquality{});
+    match diff.as_display() {
         None => unit_result(),
-        Some(diff) => Err(ExecError::Runtime(format!("{}\n{}", report, diff)))
+        Some(text) => Err(ExecError::Runtime(format!("{}\n{}{}", report, text,
+            structural_diff(diff.missed(), diff.excessive(), true))))
     }
 }
 
 #[derive(Clone, Debug)]
 pub struct AssertEqualOp {
     space: DynSpace,
+    metta: Metta,
 }
 
 grounded_op!(AssertEqualOp, "assertEqual");
 
 impl AssertEqualOp {
-    pub fn new(space: DynSpace) -> Self {
-        Self{ space }
+    pub fn new(space: DynSpace, metta: Metta) -> Self {
+        Self{ space, metta }
     }
 }
 
@@ -153,23 +307,26 @@ impl CustomExecute for AssertEqualOp {
         let actual_atom = args.get(0).ok_or_else(arg_error)?;
         let expected_atom = args.get(1).ok_or_else(arg_error)?;
 
-        let actual = interpret_no_error(self.space.clone(), actual_atom)?;
-        let expected = interpret_no_error(self.space.clone(), expected_atom)?;
+        let result = interpret(self.space.clone(), actual_atom)
+            .and_then(|actual| Ok((actual, interpret(self.space.clone(), expected_atom)?)))
+            .map_err(ExecError::from)
+            .and_then(|(actual, expected)| assert_results_equal(&actual, &expected));
 
-        assert_results_equal(&actual, &expected)
+        report_assertion(&self.metta, "assertEqual", args, result)
     }
 }
 
 #[derive(Clone, Debug)]
 pub struct AssertAlphaEqualOp {
     space: DynSpace,
+    metta: Metta,
 }
 
 grounded_op!(AssertAlphaEqualOp, "assertAlphaEqual");
 
 impl AssertAlphaEqualOp {
-    pub fn new(space: DynSpace) -> Self {
-        Self{ space }
+    pub fn new(space: DynSpace, metta: Metta) -> Self {
+        Self{ space, metta }
     }
 }
 
@@ -190,10 +347,12 @@ impl CustomExecute for AssertAlphaEqualOp {
         let actual_atom = args.get(0).ok_or_else(arg_error)?;
         let expected_atom = args.get(1).ok_or_else(arg_error)?;
 
-        let actual = interpret_no_error(self.space.clone(), actual_atom)?;
-        let expected = interpret_no_error(self.space.clone(), expected_atom)?;
+        let result = interpret(self.space.clone(), actual_atom)
+            .and_then(|actual| Ok((actual, interpret(self.space.clone(), expected_atom)?)))
+            .map_err(ExecError::from)
+            .and_then(|(actual, expected)| assert_alpha_equal(&actual, &expected));
 
-        assert_alpha_equal(&actual, &expected)
+        report_assertion(&self.metta, "assertAlphaEqual", args, result)
     }
 }
 
@@ -226,13 +385,14 @@ impl CustomExecute for AlphaEqOp {
 #[derive(Clone, Debug)]
 pub struct AssertEqualToResultOp {
     space: DynSpace,
+    metta: Metta,
 }
 
 grounded_op!(AssertEqualToResultOp, "assertEqualToResult");
 
 impl AssertEqualToResultOp {
-    pub fn new(space: DynSpace) -> Self {
-        Self{ space }
+    pub fn new(space: DynSpace, metta: Metta) -> Self {
+        Self{ space, metta }
     }
 }
 
@@ -255,22 +415,25 @@ impl CustomExecute for AssertEqualToResultOp {
             .map_err(|_| arg_error())?
             .children();
 
-        let actual = interpret_no_error(self.space.clone(), actual_atom)?;
+        let result = interpret(self.space.clone(), actual_atom)
+            .map_err(ExecError::from)
+            .and_then(|actual| assert_results_equal(&actual, &expected.into()));
 
-        assert_results_equal(&actual, &expected.into())
+        report_assertion(&self.metta, "assertEqualToResult", args, result)
     }
 }
 
 #[derive(Clone, Debug)]
 pub struct AssertAlphaEqualToResultOp {
     space: DynSpace,
+    metta: Metta,
 }
 
 grounded_op!(AssertAlphaEqualToResultOp, "assertAlphaEqualToResult");
 
 impl AssertAlphaEqualToResultOp {
-    pub fn new(space: DynSpace) -> Self {
-        Self{ space }
+    pub fn new(space: DynSpace, metta: Metta) -> Self {
+        Self{ space, metta }
     }
 }
 
@@ -293,31 +456,33 @@ impl CustomExecute for AssertAlphaEqualToResultOp {
             .map_err(|_| arg_error())?
             .children();
 
-        let actual = interpret_no_error(self.space.clone(), actual_atom)?;
+        let result = interpret(self.space.clone(), actual_atom)
+            .map_err(ExecError::from)
+            .and_then(|actual| assert_alpha_equal(&actual, &expected.into()));
 
-        assert_alpha_equal(&actual, &expected.into())
+        report_assertion(&self.metta, "assertAlphaEqualToResult", args, result)
     }
 }
 
 
-pub fn register_common_tokens(tref: &mut Tokenizer) {
-    let trace_op = Atom::gnd(TraceOp{});
+pub fn register_common_tokens(tref: &mut Tokenizer, metta: &Metta) {
+    let trace_op = Atom::gnd(TraceOp::new(metta.clone()));
     tref.register_token(regex(r"trace!"), move |_| { trace_op.clone() });
-    let print_alternatives_op = Atom::gnd(PrintAlternativesOp{});
+    let print_alternatives_op = Atom::gnd(PrintAlternativesOp::new(metta.clone()));
     tref.register_token(regex(r"print-alternatives!"), move |_| { print_alternatives_op.clone() });
 }
 
-pub fn register_runner_tokens(tref: &mut Tokenizer, space: &DynSpace) {
-    let assert_alpha_equal_to_result_op = Atom::gnd(AssertAlphaEqualToResultOp::new(space.clone()));
+pub fn register_runner_tokens(tref: &mut Tokenizer, space: &DynSpace, metta: &Metta) {
+    let assert_alpha_equal_to_result_op = Atom::gnd(AssertAlphaEqualToResultOp::new(space.clone(), metta.clone()));
     tref.register_token(regex(r"assertAlphaEqualToResult"), move |_| { assert_alpha_equal_to_result_op.clone() });
 
-    let assert_equal_to_result_op = Atom::gnd(AssertEqualToResultOp::new(space.clone()));
+    let assert_equal_to_result_op = Atom::gnd(AssertEqualToResultOp::new(space.clone(), metta.clone()));
     tref.register_token(regex(r"assertEqualToResult"), move |_| { assert_equal_to_result_op.clone() });
 
-    let assert_alpha_equal_op = Atom::gnd(AssertAlphaEqualOp::new(space.clone()));
+    let assert_alpha_equal_op = Atom::gnd(AssertAlphaEqualOp::new(space.clone(), metta.clone()));
     tref.register_token(regex(r"assertAlphaEqual"), move |_| { assert_alpha_equal_op.clone() });
 
-    let assert_equal_op = Atom::gnd(AssertEqualOp::new(space.clone()));
+    let assert_equal_op = Atom::gnd(AssertEqualOp::new(space.clone(), metta.clone()));
     tref.register_token(regex(r"assertEqual"), move |_| { assert_equal_op.clone() });
 
     let alpha_eq_op = Atom::gnd(AlphaEqOp{});
@@ -328,7 +493,13 @@ pub fn register_runner_tokens(tref: &mut Tokenizer, space: &DynSpace) {
 mod tests {
     use super::*;
     use crate::metta::runner::{Metta, EnvBuilder, SExprParser};
+    use crate::metta::runner::output::BufferOutputSink;
+    use crate::metta::runner::assertion::AssertionCollector;
+    use crate::metta::runner::SandboxProfile;
+    use crate::metta::runner::number::Number;
+    use crate::metta::runner::str::Str;
     use crate::common::test_utils::metta_space;
+    use std::sync::Arc;
     use crate::metta::runner::stdlib::tests::run_program;
 
     use regex::Regex;
@@ -344,7 +515,7 @@ mod tests {
     #[test]
     fn metta_assert_equal_op() {
         let metta = Metta::new(Some(EnvBuilder::test_env()));
-        let assert = AssertEqualOp::new(metta.space().clone());
+        let assert = AssertEqualOp::new(metta.space().clone(), metta.clone());
         let program = "
             (= (foo $x) $x)
             (= (bar $x) $x)
@@ -364,7 +535,7 @@ mod tests {
     #[test]
     fn metta_assert_alpha_equal_op() {
         let metta = Metta::new(Some(EnvBuilder::test_env()));
-        let assert = AssertAlphaEqualOp::new(metta.space().clone());
+        let assert = AssertAlphaEqualOp::new(metta.space().clone(), metta.clone());
         let program = "
             (= (foo $x) $x)
             (= (bar $x) $x)
@@ -390,7 +561,7 @@ mod tests {
     #[test]
     fn metta_assert_equal_to_result_op() {
         let metta = Metta::new(Some(EnvBuilder::test_env()));
-        let assert = AssertEqualToResultOp::new(metta.space().clone());
+        let assert = AssertEqualToResultOp::new(metta.space().clone(), metta.clone());
         let program = "
             (= (foo) A)
             (= (foo) B)
@@ -414,7 +585,7 @@ mod tests {
     #[test]
     fn metta_assert_alpha_equal_to_result_op() {
         let metta = Metta::new(Some(EnvBuilder::test_env()));
-        let assert = AssertAlphaEqualToResultOp::new(metta.space().clone());
+        let assert = AssertAlphaEqualToResultOp::new(metta.space().clone(), metta.clone());
         let program = "
             (= (foo) $x)
             (= (bar) C)
@@ -453,7 +624,7 @@ mod tests {
             (= (err) (A B))
         "));
 
-        let assert_equal_op = AssertEqualOp::new(space);
+        let assert_equal_op = AssertEqualOp::new(space, Metta::new(Some(EnvBuilder::test_env())));
 
         assert_eq!(assert_equal_op.execute(&mut vec![expr!(("foo")), expr!(("bar"))]), unit_result());
 
@@ -472,16 +643,127 @@ mod tests {
             (= (foo) (A B))
             (= (foo) (B C))
         "));
-        let assert_equal_to_result_op = AssertEqualToResultOp::new(space);
+        let assert_equal_to_result_op = AssertEqualToResultOp::new(space, Metta::new(Some(EnvBuilder::test_env())));
 
         assert_eq!(assert_equal_to_result_op.execute(&mut vec![
             expr!(("foo")), expr!(("B" "C") ("A" "B"))]),
                    unit_result());
     }
 
+    #[test]
+    fn first_difference_path() {
+        let expected = expr!("foo" ("bar" "B") "C");
+        let actual = expr!("foo" ("bar" "X") "C");
+        let diff = first_difference(&expected, &actual, false).unwrap();
+        assert_eq!(diff.to_string(), "Difference at [1, 1] in (foo (bar B) C): expected B, got X");
+        assert_eq!(first_difference(&expected, &expected.clone(), false), None);
+
+        let (expected, actual) = (expr!("a" ("b" "c")), expr!("a" ("b")));
+        let diff = first_difference(&expected, &actual, false).unwrap();
+        assert_eq!(diff.to_string(), "Difference at [1] in (a (b c)): expected (b c), got (b); expected 2 elements, got 1");
+        let (expected, actual) = (expr!({Number::Integer(1)}), expr!({Number::Float(1.5)}));
+        let diff = first_difference(&expected, &actual, false).unwrap();
+        assert_eq!(diff.to_string(), "Difference: expected 1, got 1.5");
+    }
+
+    #[test]
+    fn first_difference_grounded_types() {
+        let expected = expr!("x" {Number::Integer(1)});
+        let actual = expr!("x" {Str::from_str("1")});
+        let diff = first_difference(&expected, &actual, false).unwrap();
+        assert_eq!(diff.to_string(), "Difference at [1] in (x 1): expected 1, got \"1\"; expected value of type Number, got String");
+    }
+
+    #[test]
+    fn first_difference_alpha() {
+        let (expected, actual) = (expr!("a" x x), expr!("a" y y));
+        assert_eq!(first_difference(&expected, &actual, true), None);
+        assert!(first_difference(&expected, &actual, false).is_some());
+        let (expected, actual) = (expr!("a" x ("b" x)), expr!("a" y ("b" z)));
+        let diff = first_difference(&expected, &actual, true).unwrap();
+        assert_eq!(diff.to_string(), "Difference at [2, 1] in (a $x (b $x)): expected $x, got $z; variables cannot be renamed consistently");
+    }
+
+    #[test]
+    fn structural_diff_pairs_results_of_same_shape() {
+        let missed = [expr!("f" "A" "B"), expr!("g" "B")];
+        let excessive = [expr!("g" "C"), expr!("h"), expr!("f" "A" "C")];
+        assert_eq!(structural_diff(missed.iter().collect::<Vec<_>>().iter(), excessive.iter().collect::<Vec<_>>().iter(), false),
+            "\nDifference at [2] in (f A B): expected B, got C\nDifference at [1] in (g B): expected B, got C");
+
+        let missed = [expr!("f" "A")];
+        let excessive = [expr!("g" "A")];
+        assert_eq!(structural_diff(missed.iter().collect::<Vec<_>>().iter(), excessive.iter().collect::<Vec<_>>().iter(), false),
+            "\nDifference at [0] in (f A): expected f, got g");
+    }
+
+    #[test]
+    fn assert_equal_reports_structural_diff() {
+        let metta = Metta::new(Some(EnvBuilder::test_env()));
+        let assert = AssertEqualOp::new(metta.space().clone(), metta.clone());
+        assert_eq!(metta.run(SExprParser::new("!(assertEqual (f (g A) B) (f (g C) B))")), Ok(vec![
+            vec![expr!("Error" ({assert} ("f" ("g" "A") "B") ("f" ("g" "C") "B"))
+                "\nExpected: [(f (g C) B)]\nGot: [(f (g A) B)]\nMissed results: (f (g C) B)\nExcessive results: (f (g A) B)\nDifference at [1, 1] in (f (g C) B): expected C, got A")],
+        ]));
+    }
+
+    #[test]
+    fn assertions_are_reported() {
+        let metta = Metta::new(Some(EnvBuilder::test_env()));
+        let assertions = Arc::new(AssertionCollector::new());
+        metta.set_assertion_listener(Some(assertions.clone()));
+        metta.run(SExprParser::new("
+            (= (foo) A)
+            !(assertEqual (foo) A)
+            !(assertAlphaEqual (foo) A)
+            !(assertEqualToResult (foo) (B))
+        ")).unwrap();
+
+        let outcomes = assertions.take();
+        assert_eq!(outcomes.iter().map(|o| (o.assertion.as_str(), o.is_passed())).collect::<Vec<_>>(), vec![
+            ("(assertEqual (foo) A)", true),
+            ("(assertAlphaEqual (foo) A)", true),
+            ("(assertEqualToResult (foo) (B))", false),
+        ]);
+        assert_eq!(outcomes[2].failure.as_deref(), Some("\nExpected: [B]\nGot: [A]\nMissed results: B\nExcessive results: A"));
+        assert_eq!(assertions.passed() + assertions.failed(), 0);
+    }
+
+    #[test]
+    fn assertion_with_evaluation_error_is_reported() {
+        let env = EnvBuilder::test_env().set_sandbox(SandboxProfile::new().set_max_steps(Some(1000)));
+        let metta = Metta::new(Some(env));
+        let assertions = Arc::new(AssertionCollector::new());
+        metta.set_assertion_listener(Some(assertions.clone()));
+        let result = metta.run(SExprParser::new("
+            (= (loop) (loop))
+            !(assertEqual (loop) A)
+        ")).unwrap();
+
+        let outcomes = assertions.take();
+        assert_eq!(outcomes.len(), 1);
+        assert_eq!(outcomes[0].assertion, "(assertEqual (loop) A)");
+        assert_eq!(outcomes[0].failure.as_deref(), Some("StepLimitExceeded"));
+        assert_eq!(result[0][0].to_string(), "(Error (assertEqual (loop) A) StepLimitExceeded)");
+    }
+
     #[test]
     fn trace_op() {
-        assert_eq!(TraceOp{}.execute(&mut vec![sym!("\"Here?\""), sym!("42")]),
+        let metta = Metta::new(Some(EnvBuilder::test_env()));
+        let output = Arc::new(BufferOutputSink::new());
+        metta.set_output_sink(output.clone());
+        assert_eq!(TraceOp::new(metta).execute(&mut vec![sym!("\"Here?\""), sym!("42")]),
                    Ok(vec![sym!("42")]));
+        assert_eq!(output.take(), vec![(OutputStream::Stderr, "\"Here?\"\n".into())]);
+    }
+
+    #[test]
+    fn print_alternatives_op() {
+        let metta = Metta::new(Some(EnvBuilder::test_env()));
+        let output = Arc::new(BufferOutputSink::new());
+        metta.set_output_sink(output.clone());
+        assert_eq!(PrintAlternativesOp::new(metta).execute(&mut vec![sym!("results"), expr!("A" "B")]),
+                   unit_result());
+        assert_eq!(output.text(OutputStream::Stdout), "2 results:\n    A\n    B\n");
     }
 }
@@ -0,0 +1,317 @@
+use crate::*;
+use crate::metta::*;
+use crate::metta::text::Tokenizer;
+use crate::metta::runner::Metta;
+use crate::metta::runner::bool::*;
+use crate::metta::runner::str::*;
+use super::{grounded_op, regex, unit_result};
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+fn path_arg(arg: Option<&Atom>) -> Option<PathBuf> {
+    arg.and_then(Str::from_atom).map(|path| PathBuf::from(path.as_str()))
+}
+
+fn content_arg(arg: Option<&Atom>) -> Option<Str> {
+    arg.and_then(Str::from_atom)
+}
+
+fn io_error(path: &Path, err: std::io::Error) -> ExecError {
+    ExecError::from(format!("Cannot access {}: {}", path.display(), err))
+}
+
+/// Checks the path against the file access allow-list of the runner's
+/// environment. See [crate::metta::runner::EnvBuilder::push_fs_allowed_dir].
+fn allowed_path(metta: &Metta, path: &Path) -> Result<PathBuf, ExecError> {
+    metta.environment().check_fs_access(path).map_err(ExecError::from)
+}
+
+#[derive(Clone, Debug)]
+pub struct ReadFileOp {
+    metta: Metta,
+}
+
+grounded_op!(ReadFileOp, "read-file");
+
+impl ReadFileOp {
+    pub fn new(metta: Metta) -> Self {
+        Self{ metta }
+    }
+}
+
+impl Grounded for ReadFileOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_STRING, ATOM_TYPE_STRING])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for ReadFileOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("read-file expects one argument: string (path)");
+        let path = path_arg(args.first()).ok_or_else(arg_error)?;
+        let resolved = allowed_path(&self.metta, &path)?;
+        let content = std::fs::read_to_string(resolved).map_err(|e| io_error(&path, e))?;
+        Ok(vec![Atom::gnd(Str::from_string(content))])
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct WriteFileOp {
+    metta: Metta,
+}
+
+grounded_op!(WriteFileOp, "write-file");
+
+impl WriteFileOp {
+    pub fn new(metta: Metta) -> Self {
+        Self{ metta }
+    }
+}
+
+impl Grounded for WriteFileOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_STRING, ATOM_TYPE_STRING, UNIT_TYPE])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for WriteFileOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("write-file expects two arguments: string (path) and string (content)");
+        let path = path_arg(args.first()).ok_or_else(arg_error)?;
+        let content = content_arg(args.get(1)).ok_or_else(arg_error)?;
+        let resolved = allowed_path(&self.metta, &path)?;
+        std::fs::write(resolved, content.as_str()).map_err(|e| io_error(&path, e))?;
+        unit_result()
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct AppendFileOp {
+    metta: Metta,
+}
+
+grounded_op!(AppendFileOp, "append-file");
+
+impl AppendFileOp {
+    pub fn new(metta: Metta) -> Self {
+        Self{ metta }
+    }
+}
+
+impl Grounded for AppendFileOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_STRING, ATOM_TYPE_STRING, UNIT_TYPE])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for AppendFileOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("append-file expects two arguments: string (path) and string (content)");
+        let path = path_arg(args.first()).ok_or_else(arg_error)?;
+        let content = content_arg(args.get(1)).ok_or_else(arg_error)?;
+        let resolved = allowed_path(&self.metta, &path)?;
+        std::fs::OpenOptions::new().create(true).append(true).open(resolved)
+            .and_then(|mut file| file.write_all(content.as_str().as_bytes()))
+            .map_err(|e| io_error(&path, e))?;
+        unit_result()
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ListDirOp {
+    metta: Metta,
+}
+
+grounded_op!(ListDirOp, "list-dir");
+
+impl ListDirOp {
+    pub fn new(metta: Metta) -> Self {
+        Self{ metta }
+    }
+}
+
+impl Grounded for ListDirOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_STRING, ATOM_TYPE_EXPRESSION])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for ListDirOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("list-dir expects one argument: string (path)");
+        let path = path_arg(args.first()).ok_or_else(arg_error)?;
+        let resolved = allowed_path(&self.metta, &path)?;
+        let mut names = std::fs::read_dir(resolved)
+            .and_then(|entries| entries
+                .map(|entry| entry.map(|entry| entry.file_name().to_string_lossy().into_owned()))
+                .collect::<Result<Vec<String>, _>>())
+            .map_err(|e| io_error(&path, e))?;
+        names.sort();
+        Ok(vec![Atom::expr(names.into_iter().map(|name| Atom::gnd(Str::from_string(name))).collect::<Vec<_>>())])
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct FileExistsOp {
+    metta: Metta,
+}
+
+grounded_op!(FileExistsOp, "file-exists");
+
+impl FileExistsOp {
+    pub fn new(metta: Metta) -> Self {
+        Self{ metta }
+    }
+}
+
+impl Grounded for FileExistsOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_STRING, ATOM_TYPE_BOOL])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for FileExistsOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("file-exists expects one argument: string (path)");
+        let path = path_arg(args.first()).ok_or_else(arg_error)?;
+        let resolved = allowed_path(&self.metta, &path)?;
+        Ok(vec![Atom::gnd(Bool(resolved.exists()))])
+    }
+}
+
+pub fn register_common_tokens(tref: &mut Tokenizer, metta: &Metta) {
+    let read_file_op = Atom::gnd(ReadFileOp::new(metta.clone()));
+    tref.register_token(regex(r"read-file"), move |_| { read_file_op.clone() });
+    let write_file_op = Atom::gnd(WriteFileOp::new(metta.clone()));
+    tref.register_token(regex(r"write-file"), move |_| { write_file_op.clone() });
+    let append_file_op = Atom::gnd(AppendFileOp::new(metta.clone()));
+    tref.register_token(regex(r"append-file"), move |_| { append_file_op.clone() });
+    let list_dir_op = Atom::gnd(ListDirOp::new(metta.clone()));
+    tref.register_token(regex(r"list-dir"), move |_| { list_dir_op.clone() });
+    let file_exists_op = Atom::gnd(FileExistsOp::new(metta.clone()));
+    tref.register_token(regex(r"file-exists"), move |_| { file_exists_op.clone() });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metta::runner::EnvBuilder;
+    use crate::metta::text::SExprParser;
+
+    struct TestDir(PathBuf);
+
+    impl TestDir {
+        fn new(name: &str) -> Self {
+            let path = std::env::temp_dir().join(format!("hyperon-{}-{}", name, std::process::id()));
+            let _ = std::fs::remove_dir_all(&path);
+            std::fs::create_dir_all(path.join("data")).unwrap();
+            Self(path)
+        }
+    }
+
+    impl Drop for TestDir {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn run_in_dir(dir: &TestDir, program: &str) -> Result<Vec<Vec<Atom>>, String> {
+        let env = EnvBuilder::test_env()
+            .set_working_dir(Some(&dir.0))
+            .push_fs_allowed_dir("data");
+        let metta = Metta::new(Some(env));
+        metta.run(SExprParser::new(program))
+    }
+
+    fn str_atom(s: &str) -> Atom {
+        Atom::gnd(Str::from_string(s.into()))
+    }
+
+    #[test]
+    fn file_ops_within_allowed_dir() {
+        let dir = TestDir::new("file-ops");
+        let result = run_in_dir(&dir, r#"
+            !(file-exists "data/a.txt")
+            !(write-file "data/a.txt" "hello")
+            !(append-file "data/a.txt" " world")
+            !(read-file "data/a.txt")
+            !(write-file "data/b.txt" "")
+            !(list-dir "data")
+            !(file-exists "data/a.txt")
+        "#);
+        assert_eq!(result, Ok(vec![
+            vec![expr!({Bool(false)})],
+            vec![UNIT_ATOM],
+            vec![UNIT_ATOM],
+            vec![str_atom("hello world")],
+            vec![UNIT_ATOM],
+            vec![Atom::expr([str_atom("a.txt"), str_atom("b.txt")])],
+            vec![expr!({Bool(true)})],
+        ]));
+    }
+
+    #[test]
+    fn file_ops_outside_allowed_dir_are_denied() {
+        let dir = TestDir::new("file-ops-denied");
+        std::fs::write(dir.0.join("secret.txt"), "secret").unwrap();
+        let metta = Metta::new(Some(EnvBuilder::test_env()
+            .set_working_dir(Some(&dir.0)).push_fs_allowed_dir("data")));
+
+        let denied = |path: &str| Err(ExecError::from(format!("Access to {} is denied", path)));
+        assert_eq!(ReadFileOp::new(metta.clone()).execute(&[str_atom("secret.txt")]), denied("secret.txt"));
+        assert_eq!(ReadFileOp::new(metta.clone()).execute(&[str_atom("data/../secret.txt")]), denied("data/../secret.txt"));
+        assert_eq!(WriteFileOp::new(metta.clone()).execute(&[str_atom("new.txt"), str_atom("")]), denied("new.txt"));
+        assert_eq!(ListDirOp::new(metta.clone()).execute(&[str_atom(".")]), denied("."));
+        assert_eq!(FileExistsOp::new(metta.clone()).execute(&[str_atom("/")]), denied("/"));
+        assert!(!dir.0.join("new.txt").exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn file_ops_do_not_follow_dangling_symlinks_outside_allowed_dir() {
+        let dir = TestDir::new("file-ops-symlink");
+        std::os::unix::fs::symlink("../outside.txt", dir.0.join("data/outside")).unwrap();
+        std::os::unix::fs::symlink("inside.txt", dir.0.join("data/inside")).unwrap();
+        let metta = Metta::new(Some(EnvBuilder::test_env()
+            .set_working_dir(Some(&dir.0)).push_fs_allowed_dir("data")));
+
+        assert_eq!(WriteFileOp::new(metta.clone()).execute(&[str_atom("data/outside"), str_atom("")]),
+            Err(ExecError::from("Access to data/outside is denied")));
+        assert_eq!(AppendFileOp::new(metta.clone()).execute(&[str_atom("data/outside"), str_atom("")]),
+            Err(ExecError::from("Access to data/outside is denied")));
+        assert!(!dir.0.join("outside.txt").exists());
+
+        assert_eq!(WriteFileOp::new(metta).execute(&[str_atom("data/inside"), str_atom("")]), unit_result());
+        assert!(dir.0.join("data/inside.txt").exists());
+    }
+
+    #[test]
+    fn file_ops_are_denied_by_default() {
+        let dir = TestDir::new("file-ops-default");
+        let metta = Metta::new(Some(EnvBuilder::test_env().set_working_dir(Some(&dir.0))));
+        assert_eq!(AppendFileOp::new(metta).execute(&[str_atom("data/a.txt"), str_atom("")]),
+            Err(ExecError::from("Access to data/a.txt is denied")));
+        assert!(!dir.0.join("data/a.txt").exists());
+    }
+}
@@ -0,0 +1,177 @@
+use crate::*;
+use crate::metta::*;
+use crate::metta::text::Tokenizer;
+use super::{grounded_op, regex};
+use crate::metta::runner::collection::*;
+use crate::metta::runner::number::*;
+use crate::metta::runner::bool::*;
+use crate::metta::runner::str::*;
+
+use serde_json::Value;
+
+/// Atom which represents JSON `null` value
+pub const JSON_NULL_SYMBOL : Atom = sym!("null");
+
+/// Converts JSON value into atom. Objects are converted into [Map] with [Str]
+/// keys, arrays into expressions, numbers into [Number], strings into [Str],
+/// booleans into [Bool] and `null` into [JSON_NULL_SYMBOL].
+pub fn json_to_atom(value: &Value) -> Atom {
+    match value {
+        Value::Null => JSON_NULL_SYMBOL,
+        Value::Bool(b) => Atom::gnd(Bool(*b)),
+        Value::Number(n) => {
+            let n = match (n.as_i64(), n.as_u64()) {
+                (Some(n), _) => Number::Integer(n),
+                (None, Some(n)) => Number::from(num_bigint::BigInt::from(n)),
+                (None, None) => Number::Float(n.as_f64().unwrap_or(f64::NAN)),
+            };
+            Atom::gnd(n)
+        },
+        Value::String(s) => Atom::gnd(Str::from_string(s.clone())),
+        Value::Array(items) => Atom::expr(items.iter().map(json_to_atom).collect::<Vec<_>>()),
+        Value::Object(fields) => Atom::gnd(fields.iter()
+            .map(|(k, v)| (Atom::gnd(Str::from_string(k.clone())), json_to_atom(v)))
+            .collect::<Map>()),
+    }
+}
+
+/// Converts atom into JSON value. It is an inverse of the [json_to_atom].
+/// Keys of the [Map] can be [Str] or symbols. Returns error if atom cannot
+/// be represented in JSON, for example if it is a variable or a rational
+/// number.
+pub fn atom_to_json(atom: &Atom) -> Result<Value, String> {
+    let convert_error = || format!("Cannot convert {} to JSON", atom);
+    match atom {
+        Atom::Symbol(_) if *atom == JSON_NULL_SYMBOL => Ok(Value::Null),
+        Atom::Expression(expr) => expr.children().iter()
+            .map(atom_to_json)
+            .collect::<Result<Vec<_>, _>>()
+            .map(Value::Array),
+        Atom::Grounded(_) => {
+            if let Some(Bool(b)) = Bool::from_atom(atom) {
+                Ok(Value::Bool(b))
+            } else if let Some(s) = Str::from_atom(atom) {
+                Ok(Value::String(s.as_str().into()))
+            } else if let Some(n) = Number::from_atom(atom) {
+                match n {
+                    Number::Integer(n) => Ok(Value::from(n)),
+                    Number::Float(f) => serde_json::Number::from_f64(f)
+                        .map(Value::Number).ok_or_else(convert_error),
+                    Number::BigInt(n) => n.to_string().parse::<u64>()
+                        .map(Value::from).map_err(|_| convert_error()),
+                    Number::Rational(_) => Err(convert_error()),
+                }
+            } else if let Some(map) = Map::from_atom(atom) {
+                map.entries().into_iter()
+                    .map(|(k, v)| {
+                        let key = match k {
+                            Atom::Symbol(sym) => sym.name().to_string(),
+                            _ => Str::from_atom(k).ok_or_else(convert_error)?.as_str().to_string(),
+                        };
+                        Ok((key, atom_to_json(v)?))
+                    })
+                    .collect::<Result<serde_json::Map<_, _>, _>>()
+                    .map(Value::Object)
+            } else {
+                Err(convert_error())
+            }
+        },
+        _ => Err(convert_error()),
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ParseJsonOp {}
+
+grounded_op!(ParseJsonOp, "parse-json");
+
+impl Grounded for ParseJsonOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_STRING, ATOM_TYPE_UNDEFINED])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for ParseJsonOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("parse-json expects one argument: string");
+        let text = args.first().and_then(Str::from_atom).ok_or_else(arg_error)?;
+        let value: Value = serde_json::from_str(text.as_str())
+            .map_err(|e| ExecError::from(format!("Invalid JSON: {}", e)))?;
+        Ok(vec![json_to_atom(&value)])
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ToJsonOp {}
+
+grounded_op!(ToJsonOp, "to-json");
+
+impl Grounded for ToJsonOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_UNDEFINED, ATOM_TYPE_STRING])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for ToJsonOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("to-json expects one argument: atom");
+        let atom = args.first().ok_or_else(arg_error)?;
+        let value = atom_to_json(atom)?;
+        Ok(vec![Atom::gnd(Str::from_string(value.to_string()))])
+    }
+}
+
+pub fn register_common_tokens(tref: &mut Tokenizer) {
+    let parse_json_op = Atom::gnd(ParseJsonOp{});
+    tref.register_token(regex(r"parse-json"), move |_| { parse_json_op.clone() });
+    let to_json_op = Atom::gnd(ToJsonOp{});
+    tref.register_token(regex(r"to-json"), move |_| { to_json_op.clone() });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metta::runner::stdlib::tests::run_program;
+
+    #[test]
+    fn json_to_atom_mapping() {
+        let value: Value = serde_json::from_str(r#"[1, 1.5, "a", true, null, {"k": []}, 18446744073709551615]"#).unwrap();
+        assert_eq!(json_to_atom(&value), expr!(
+            {Number::Integer(1)} {Number::Float(1.5)} {Str::from_str("a")} {Bool(true)} "null"
+            {Map::new().insert(expr!({Str::from_str("k")}), expr!())}
+            {Number::from_int_str("18446744073709551615").unwrap()}));
+    }
+
+    #[test]
+    fn atom_to_json_round_trip() {
+        let text = r#"{"a":[1,2.0,"s",false,null],"b":{"c":{}}}"#;
+        let value: Value = serde_json::from_str(text).unwrap();
+        assert_eq!(atom_to_json(&json_to_atom(&value)), Ok(value));
+        assert_eq!(atom_to_json(&expr!(a)), Err("Cannot convert $a to JSON".into()));
+        assert_eq!(atom_to_json(&expr!("x")), Err("Cannot convert x to JSON".into()));
+        assert_eq!(atom_to_json(&Atom::gnd(Map::new().insert(sym!("k"), sym!("null")))),
+            Ok(serde_json::json!({"k": null})));
+    }
+
+    #[test]
+    fn metta_json() {
+        assert_eq!(run_program(r#"!(map-get (parse-json "{\"a\": [1, true]}") "a")"#),
+            Ok(vec![vec![expr!({Number::Integer(1)} {Bool(true)})]]));
+        assert_eq!(run_program(r#"!(to-json (1 2.5 "x" (False null)))"#),
+            Ok(vec![vec![expr!({Str::from_str(r#"[1,2.5,"x",[false,null]]"#)})]]));
+        assert_eq!(run_program(r#"!(to-json (parse-json "{\"b\": 1, \"a\": {}}"))"#),
+            Ok(vec![vec![expr!({Str::from_str(r#"{"a":{},"b":1}"#)})]]));
+        assert_eq!(ParseJsonOp{}.execute(&[expr!({Str::from_str("{")})]),
+            Err(ExecError::from("Invalid JSON: EOF while parsing an object at line 1 column 1")));
+        assert_eq!(ToJsonOp{}.execute(&[expr!({Number::from_rational_str("1/3").unwrap()})]),
+            Err(ExecError::from("Cannot convert 1/3 to JSON")));
+    }
+}
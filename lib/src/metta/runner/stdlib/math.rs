@@ -6,6 +6,9 @@ use crate::metta::runner::number::*;
 use crate::metta::runner::bool::*;
 
 use std::convert::TryInto;
+use num_bigint::BigInt;
+use num_traits::{Signed, ToPrimitive, Zero};
+use num_integer::Integer;
 
 #[derive(Clone, Debug)]
 pub struct PowMathOp {}
@@ -21,18 +24,22 @@ impl Grounded for PowMathOp {
 impl CustomExecute for PowMathOp {
     fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
         let arg_error = || ExecError::from("pow-math expects two arguments: number (base) and number (power)");
-        let base: f64 = args.get(0).and_then(Number::from_atom).ok_or_else(arg_error)?.into();
+        let base = args.get(0).and_then(Number::from_atom).ok_or_else(arg_error)?;
         let pow = args.get(1).and_then(Number::from_atom).ok_or_else(arg_error)?;
         let res = match pow {
             Number::Integer(n) => {
                 match TryInto::<i32>::try_into(n) {
-                    Ok(n) => base.powi(n),
+                    Ok(n) => base.checked_powi(n)?,
                     Err(_) => return Err(ExecError::from("power argument is too big, try using float value")),
                 }
             },
-            Number::Float(f) => base.powf(f),
+            Number::BigInt(_) => return Err(ExecError::from("power argument is too big, try using float value")),
+            pow @ (Number::Rational(_) | Number::Float(_)) => {
+                let base: f64 = base.into();
+                Number::Float(base.powf(pow.into()))
+            },
         };
-        Ok(vec![Atom::gnd(Number::Float(res))])
+        Ok(vec![Atom::gnd(res)])
     }
 }
 
@@ -79,7 +86,9 @@ impl CustomExecute for AbsMathOp {
         let arg_error = || ExecError::from("abs-math expects one argument: number");
         let input = args.get(0).and_then(Number::from_atom).ok_or_else(arg_error)?;
         match input {
-            Number::Integer(n) => Ok(vec![Atom::gnd(Number::Integer(n.abs()))]),
+            Number::Integer(n) => Ok(vec![Atom::gnd(Number::from(BigInt::from(n).abs()))]),
+            Number::BigInt(n) => Ok(vec![Atom::gnd(Number::from(n.abs()))]),
+            Number::Rational(r) => Ok(vec![Atom::gnd(Number::from(r.abs()))]),
             Number::Float(f) => Ok(vec![Atom::gnd(Number::Float(f.abs()))])
         }
     }
@@ -129,7 +138,8 @@ impl CustomExecute for TruncMathOp {
         let arg_error = || ExecError::from("trunc-math expects one argument: input number");
         let input = args.get(0).and_then(Number::from_atom).ok_or_else(arg_error)?;
         match input {
-            Number::Integer(n) => Ok(vec![Atom::gnd(Number::Integer(n))]),
+            n @ (Number::Integer(_) | Number::BigInt(_)) => Ok(vec![Atom::gnd(n)]),
+            Number::Rational(r) => Ok(vec![Atom::gnd(Number::from(r.trunc()))]),
             Number::Float(f) => Ok(vec![Atom::gnd(Number::Float(f.trunc()))])
         }
     }
@@ -155,7 +165,8 @@ impl CustomExecute for CeilMathOp {
         let arg_error = || ExecError::from("ceil-math expects one argument: input number");
         let input = args.get(0).and_then(Number::from_atom).ok_or_else(arg_error)?;
         match input {
-            Number::Integer(n) => Ok(vec![Atom::gnd(Number::Integer(n))]),
+            n @ (Number::Integer(_) | Number::BigInt(_)) => Ok(vec![Atom::gnd(n)]),
+            Number::Rational(r) => Ok(vec![Atom::gnd(Number::from(r.ceil()))]),
             Number::Float(f) => Ok(vec![Atom::gnd(Number::Float(f.ceil()))])
         }
     }
@@ -181,7 +192,8 @@ impl CustomExecute for FloorMathOp {
         let arg_error = || ExecError::from("floor-math expects one argument: input number");
         let input = args.get(0).and_then(Number::from_atom).ok_or_else(arg_error)?;
         match input {
-            Number::Integer(n) => Ok(vec![Atom::gnd(Number::Integer(n))]),
+            n @ (Number::Integer(_) | Number::BigInt(_)) => Ok(vec![Atom::gnd(n)]),
+            Number::Rational(r) => Ok(vec![Atom::gnd(Number::from(r.floor()))]),
             Number::Float(f) => Ok(vec![Atom::gnd(Number::Float(f.floor()))])
         }
     }
@@ -207,7 +219,8 @@ impl CustomExecute for RoundMathOp {
         let arg_error = || ExecError::from("round-math expects one argument: input number");
         let input = args.get(0).and_then(Number::from_atom).ok_or_else(arg_error)?;
         match input {
-            Number::Integer(n) => Ok(vec![Atom::gnd(Number::Integer(n))]),
+            n @ (Number::Integer(_) | Number::BigInt(_)) => Ok(vec![Atom::gnd(n)]),
+            Number::Rational(r) => Ok(vec![Atom::gnd(Number::from(r.round()))]),
             Number::Float(f) => Ok(vec![Atom::gnd(Number::Float(f.round()))])
         }
     }
@@ -371,7 +384,7 @@ impl CustomExecute for IsNanMathOp {
         let arg_error = || ExecError::from("isnan-math expects one argument: input number");
         let input = args.get(0).and_then(Number::from_atom).ok_or_else(arg_error)?;
         let res = match input {
-            Number::Integer(_) => false,
+            Number::Integer(_) | Number::BigInt(_) | Number::Rational(_) => false,
             Number::Float(f) => f.is_nan(),
         };
         Ok(vec![Atom::gnd(Bool(res))])
@@ -398,13 +411,305 @@ impl CustomExecute for IsInfMathOp {
         let arg_error = || ExecError::from("isinf-math expects one argument: input number");
         let input = args.get(0).and_then(Number::from_atom).ok_or_else(arg_error)?;
         let res = match input {
-            Number::Integer(_) => false,
+            Number::Integer(_) | Number::BigInt(_) | Number::Rational(_) => false,
             Number::Float(f) => f.is_infinite(),
         };
         Ok(vec![Atom::gnd(Bool(res))])
     }
 }
 
+fn integer_arg(atom: Option<&Atom>) -> Option<BigInt> {
+    atom.and_then(Number::from_atom).and_then(Number::into_integer)
+}
+
+/// Converts left shift amount into `usize`. Returns an error if shift is
+/// negative or result will not fit into [MAX_NUMBER_BITS].
+fn shift_amount(n: BigInt) -> Result<usize, ExecError> {
+    match n.to_usize() {
+        Some(n) if n as u64 <= MAX_NUMBER_BITS => Ok(n),
+        Some(_) => Err(ArithmeticError::Overflow.into()),
+        None if n.is_negative() => Err(ExecError::from("shift amount should be non-negative")),
+        None => Err(ArithmeticError::Overflow.into()),
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct BitAndOp {}
+
+grounded_op!(BitAndOp, "bit-and");
+
+impl Grounded for BitAndOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_NUMBER, ATOM_TYPE_NUMBER, ATOM_TYPE_NUMBER])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for BitAndOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("bit-and expects two arguments: integer and integer");
+        let a = integer_arg(args.first()).ok_or_else(arg_error)?;
+        let b = integer_arg(args.get(1)).ok_or_else(arg_error)?;
+        Ok(vec![Atom::gnd(Number::from(a & b))])
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct BitOrOp {}
+
+grounded_op!(BitOrOp, "bit-or");
+
+impl Grounded for BitOrOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_NUMBER, ATOM_TYPE_NUMBER, ATOM_TYPE_NUMBER])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for BitOrOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("bit-or expects two arguments: integer and integer");
+        let a = integer_arg(args.first()).ok_or_else(arg_error)?;
+        let b = integer_arg(args.get(1)).ok_or_else(arg_error)?;
+        Ok(vec![Atom::gnd(Number::from(a | b))])
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct BitXorOp {}
+
+grounded_op!(BitXorOp, "bit-xor");
+
+impl Grounded for BitXorOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_NUMBER, ATOM_TYPE_NUMBER, ATOM_TYPE_NUMBER])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for BitXorOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("bit-xor expects two arguments: integer and integer");
+        let a = integer_arg(args.first()).ok_or_else(arg_error)?;
+        let b = integer_arg(args.get(1)).ok_or_else(arg_error)?;
+        Ok(vec![Atom::gnd(Number::from(a ^ b))])
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct BitNotOp {}
+
+grounded_op!(BitNotOp, "bit-not");
+
+impl Grounded for BitNotOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_NUMBER, ATOM_TYPE_NUMBER])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for BitNotOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("bit-not expects one argument: integer");
+        let a = integer_arg(args.first()).ok_or_else(arg_error)?;
+        Ok(vec![Atom::gnd(Number::from(!a))])
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ShlOp {}
+
+grounded_op!(ShlOp, "shl");
+
+impl Grounded for ShlOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_NUMBER, ATOM_TYPE_NUMBER, ATOM_TYPE_NUMBER])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for ShlOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("shl expects two arguments: integer and shift amount (non-negative integer)");
+        let a = integer_arg(args.first()).ok_or_else(arg_error)?;
+        let b = integer_arg(args.get(1)).ok_or_else(arg_error)?;
+        let res = Number::from(a << shift_amount(b)?).check_size()?;
+        Ok(vec![Atom::gnd(res)])
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ShrOp {}
+
+grounded_op!(ShrOp, "shr");
+
+impl Grounded for ShrOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_NUMBER, ATOM_TYPE_NUMBER, ATOM_TYPE_NUMBER])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for ShrOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("shr expects two arguments: integer and shift amount (non-negative integer)");
+        let a = integer_arg(args.first()).ok_or_else(arg_error)?;
+        let b = integer_arg(args.get(1)).ok_or_else(arg_error)?;
+        if b.is_negative() {
+            return Err(ExecError::from("shift amount should be non-negative"))
+        }
+        // shifting right by more than number of bits always gives 0 or -1
+        let shift = b.to_usize().unwrap_or(usize::MAX);
+        Ok(vec![Atom::gnd(Number::from(a >> shift))])
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct GcdOp {}
+
+grounded_op!(GcdOp, "gcd");
+
+impl Grounded for GcdOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_NUMBER, ATOM_TYPE_NUMBER, ATOM_TYPE_NUMBER])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for GcdOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("gcd expects two arguments: integer and integer");
+        let a = integer_arg(args.first()).ok_or_else(arg_error)?;
+        let b = integer_arg(args.get(1)).ok_or_else(arg_error)?;
+        Ok(vec![Atom::gnd(Number::from(a.gcd(&b)))])
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct LcmOp {}
+
+grounded_op!(LcmOp, "lcm");
+
+impl Grounded for LcmOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_NUMBER, ATOM_TYPE_NUMBER, ATOM_TYPE_NUMBER])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for LcmOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("lcm expects two arguments: integer and integer");
+        let a = integer_arg(args.first()).ok_or_else(arg_error)?;
+        let b = integer_arg(args.get(1)).ok_or_else(arg_error)?;
+        Ok(vec![Atom::gnd(Number::from(a.lcm(&b)))])
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct PowOp {}
+
+grounded_op!(PowOp, "pow");
+
+impl Grounded for PowOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_NUMBER, ATOM_TYPE_NUMBER, ATOM_TYPE_NUMBER])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for PowOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("pow expects two arguments: integer base and non-negative integer power");
+        let a = integer_arg(args.first()).ok_or_else(arg_error)?;
+        let b = integer_arg(args.get(1)).ok_or_else(arg_error)?;
+        let res = match b.to_i32() {
+            Some(b) if b >= 0 => Number::from(a).checked_powi(b)?,
+            _ if b.is_negative() => return Err(ExecError::from("pow expects non-negative power")),
+            _ => return Err(ArithmeticError::Overflow.into()),
+        };
+        Ok(vec![Atom::gnd(res)])
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct EuclideanModOp {}
+
+grounded_op!(EuclideanModOp, "mod");
+
+impl Grounded for EuclideanModOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_NUMBER, ATOM_TYPE_NUMBER, ATOM_TYPE_NUMBER])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for EuclideanModOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("mod expects two arguments: integer dividend and integer divisor");
+        let a = integer_arg(args.first()).ok_or_else(arg_error)?;
+        let b = integer_arg(args.get(1)).ok_or_else(arg_error)?;
+        if b.is_zero() {
+            return Err(ArithmeticError::DivisionByZero.into())
+        }
+        // Euclidean remainder is never negative
+        Ok(vec![Atom::gnd(Number::from(a.mod_floor(&b.abs())))])
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct AbsOp {}
+
+grounded_op!(AbsOp, "abs");
+
+impl Grounded for AbsOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_NUMBER, ATOM_TYPE_NUMBER])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for AbsOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("abs expects one argument: integer");
+        let a = integer_arg(args.first()).ok_or_else(arg_error)?;
+        Ok(vec![Atom::gnd(Number::from(a.abs()))])
+    }
+}
+
 //TODO: The additional arguments are a temporary hack on account of the way the operation atoms store references
 // to the runner & module state.  https://github.com/trueagi-io/hyperon-experimental/issues/410
 pub fn register_common_tokens(tref: &mut Tokenizer) {
@@ -441,6 +746,28 @@ pub fn register_common_tokens(tref: &mut Tokenizer) {
     tref.register_token(regex(r"isnan-math"), move |_| { isnan_math_op.clone() });
     let isinf_math_op = Atom::gnd(IsInfMathOp{});
     tref.register_token(regex(r"isinf-math"), move |_| { isinf_math_op.clone() });
+    let bit_and_op = Atom::gnd(BitAndOp{});
+    tref.register_token(regex(r"bit-and"), move |_| { bit_and_op.clone() });
+    let bit_or_op = Atom::gnd(BitOrOp{});
+    tref.register_token(regex(r"bit-or"), move |_| { bit_or_op.clone() });
+    let bit_xor_op = Atom::gnd(BitXorOp{});
+    tref.register_token(regex(r"bit-xor"), move |_| { bit_xor_op.clone() });
+    let bit_not_op = Atom::gnd(BitNotOp{});
+    tref.register_token(regex(r"bit-not"), move |_| { bit_not_op.clone() });
+    let shl_op = Atom::gnd(ShlOp{});
+    tref.register_token(regex(r"shl"), move |_| { shl_op.clone() });
+    let shr_op = Atom::gnd(ShrOp{});
+    tref.register_token(regex(r"shr"), move |_| { shr_op.clone() });
+    let gcd_op = Atom::gnd(GcdOp{});
+    tref.register_token(regex(r"gcd"), move |_| { gcd_op.clone() });
+    let lcm_op = Atom::gnd(LcmOp{});
+    tref.register_token(regex(r"lcm"), move |_| { lcm_op.clone() });
+    let pow_op = Atom::gnd(PowOp{});
+    tref.register_token(regex(r"pow"), move |_| { pow_op.clone() });
+    let mod_op = Atom::gnd(EuclideanModOp{});
+    tref.register_token(regex(r"mod"), move |_| { mod_op.clone() });
+    let abs_op = Atom::gnd(AbsOp{});
+    tref.register_token(regex(r"abs"), move |_| { abs_op.clone() });
     tref.register_token(regex(r"PI"),
                         |_| { Atom::gnd(Number::Float(std::f64::consts::PI)) });
     tref.register_token(regex(r"EXP"),
@@ -740,4 +1067,29 @@ mod tests {
         let res = IsInfMathOp {}.execute(&mut vec![expr!("A")]);
         assert_eq!(res, Err(ExecError::from("isinf-math expects one argument: input number")));
     }
+
+    #[test]
+    fn metta_bitwise_ops() {
+        assert_eq!(run_program("!(bit-and 12 10)"), Ok(vec![vec![expr!({Number::Integer(8)})]]));
+        assert_eq!(run_program("!(bit-or 12 10)"), Ok(vec![vec![expr!({Number::Integer(14)})]]));
+        assert_eq!(run_program("!(bit-xor 12 10)"), Ok(vec![vec![expr!({Number::Integer(6)})]]));
+        assert_eq!(run_program("!(bit-not 5)"), Ok(vec![vec![expr!({Number::Integer(-6)})]]));
+        assert_eq!(run_program("!(shl 1 70)"), Ok(vec![vec![expr!({Number::from_int_str("1180591620717411303424").unwrap()})]]));
+        assert_eq!(run_program("!(shr -9 1)"), Ok(vec![vec![expr!({Number::Integer(-5)})]]));
+        assert_eq!(run_program("!(shr 9 100000000000000000000)"), Ok(vec![vec![expr!({Number::Integer(0)})]]));
+        assert_eq!(run_program("!(shl 1 100000000000)"), Ok(vec![vec![expr!("Error" ({ ShlOp{} } {Number::Integer(1)} {Number::Integer(100000000000)}) "ArithmeticOverflow")]]));
+        assert_eq!(run_program("!(bit-and 1.5 1)"), Ok(vec![vec![expr!("Error" ({ BitAndOp{} } {Number::Float(1.5)} {Number::Integer(1)}) "bit-and expects two arguments: integer and integer")]]));
+    }
+
+    #[test]
+    fn metta_integer_math_ops() {
+        assert_eq!(run_program("!(gcd 12 -18)"), Ok(vec![vec![expr!({Number::Integer(6)})]]));
+        assert_eq!(run_program("!(lcm 4 6)"), Ok(vec![vec![expr!({Number::Integer(12)})]]));
+        assert_eq!(run_program("!(pow 3 40)"), Ok(vec![vec![expr!({Number::from_int_str("12157665459056928801").unwrap()})]]));
+        assert_eq!(run_program("!(pow 2 10000000)"), Ok(vec![vec![expr!("Error" ({ PowOp{} } {Number::Integer(2)} {Number::Integer(10000000)}) "ArithmeticOverflow")]]));
+        assert_eq!(run_program("!(mod -7 3)"), Ok(vec![vec![expr!({Number::Integer(2)})]]));
+        assert_eq!(run_program("!(mod -7 -3)"), Ok(vec![vec![expr!({Number::Integer(2)})]]));
+        assert_eq!(run_program("!(mod 7 0)"), Ok(vec![vec![expr!("Error" ({ EuclideanModOp{} } {Number::Integer(7)} {Number::Integer(0)}) "DivisionByZero")]]));
+        assert_eq!(run_program("!(abs -9223372036854775808)"), Ok(vec![vec![expr!({Number::from_int_str("9223372036854775808").unwrap()})]]));
+    }
 }
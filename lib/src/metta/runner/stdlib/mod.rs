@@ -6,7 +6,13 @@ pub mod module;
 #[cfg(feature = "pkg_mgmt")]
 pub mod package;
 pub mod string;
+pub mod collection;
+#[cfg(feature = "json")]
+pub mod json;
+pub mod file;
+pub mod system;
 pub mod debug;
+pub mod property;
 pub mod space;
 pub mod core;
 pub mod arithmetics;
@@ -17,6 +23,7 @@ use crate::metta::*;
 use crate::metta::text::{Tokenizer, SExprParser};
 use crate::common::shared::Shared;
 use crate::metta::runner::{Metta, RunContext, ModuleLoader};
+use crate::metta::runner::sandbox;
 
 use regex::Regex;
 
@@ -57,10 +64,17 @@ pub fn interpret_no_error(space: DynSpace, expr: &Atom) -> Result<Vec<Atom>, Str
     }
 }
 
+/// Interprets the atom using nested interpreter. Steps of the nested
+/// interpreter are charged to the resource meter of the runner, see
+/// [crate::metta::runner::SandboxProfile::set_max_steps].
 pub fn interpret(space: DynSpace, expr: &Atom) -> Result<Vec<Atom>, String> {
     let expr = Atom::expr([METTA_SYMBOL, expr.clone(), ATOM_TYPE_UNDEFINED, Atom::gnd(space.clone())]);
-    let result = crate::metta::interpreter::interpret(space, &expr);
-    result
+    let mut state = crate::metta::interpreter::interpret_init(space, &expr);
+    while state.has_next() {
+        sandbox::charge_step().map_err(|limit| limit.to_string())?;
+        state = crate::metta::interpreter::interpret_step(state);
+    }
+    state.into_result()
 }
 
 //TODO: The additional arguments are a temporary hack on account of the way the operation atoms store references
@@ -68,14 +82,59 @@ pub fn interpret(space: DynSpace, expr: &Atom) -> Result<Vec<Atom>, String> {
 pub fn register_common_tokens(tref: &mut Tokenizer, _tokenizer: Shared<Tokenizer>, space: &DynSpace, metta: &Metta) {
     core::register_common_tokens(tref);
     math::register_common_tokens(tref);
+    string::register_common_tokens(tref);
+    collection::register_common_tokens(tref);
+    #[cfg(feature = "json")]
+    json::register_common_tokens(tref);
     random::register_common_tokens(tref);
     atom::register_common_tokens(tref, space);
     module::register_common_tokens(tref, metta);
+    file::register_common_tokens(tref, metta);
+    system::register_common_tokens(tref, metta);
     space::register_common_tokens(tref);
-    debug::register_common_tokens(tref);
+    debug::register_common_tokens(tref, metta);
 
     #[cfg(feature = "pkg_mgmt")]
     package::register_pkg_mgmt_tokens(tref, metta);
+
+    // Common tokens are registered after the runner tokens, thus stubs
+    // override both
+    register_denied_op_stubs(tref, metta);
+}
+
+/// Stub which replaces an operation denied by the [crate::metta::runner::SandboxProfile]
+#[derive(Clone, Debug, PartialEq)]
+pub struct DeniedOp {
+    token: String,
+}
+
+impl std::fmt::Display for DeniedOp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.token)
+    }
+}
+
+impl Grounded for DeniedOp {
+    fn type_(&self) -> Atom {
+        ATOM_TYPE_UNDEFINED
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for DeniedOp {
+    fn execute(&self, _args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        Err(format!("{} is denied by the sandbox profile", self.token).into())
+    }
+}
+
+fn register_denied_op_stubs(tref: &mut Tokenizer, metta: &Metta) {
+    for token in metta.environment().sandbox().denied_ops() {
+        let stub = Atom::gnd(DeniedOp{ token: token.into() });
+        tref.register_token(regex(&regex::escape(token)), move |_| { stub.clone() });
+    }
 }
 
 //TODO: The additional arguments are a temporary hack on account of the way the operation atoms store references
@@ -84,8 +143,9 @@ pub fn register_runner_tokens(tref: &mut Tokenizer, tokenizer: Shared<Tokenizer>
 
     core::register_runner_tokens(tref, space, metta);
     module::register_runner_tokens(tref, tokenizer.clone(), metta);
-    string::register_runner_tokens(tref);
-    debug::register_runner_tokens(tref, space);
+    string::register_runner_tokens(tref, metta);
+    debug::register_runner_tokens(tref, space, metta);
+    property::register_runner_tokens(tref, space, metta);
     // &self should be updated
     // TODO: adding &self might be done not by stdlib, but by MeTTa itself.
     // TODO: adding &self introduces self referencing and thus prevents space
@@ -272,6 +332,122 @@ mod tests {
         assert_eq!(result, Ok(vec![vec![expr!("Error" ("a" "b") "BadType")]]));
     }
 
+    #[test]
+    fn metta_interpret_func_subtype() {
+        let header = "
+            (: Animal Type)
+            (: Cat Type)
+            (:< Cat Animal)
+            (: tom Cat)
+            (: rock Stone)
+            (: make-cat (-> Cat))
+            (= (make-cat) tom)
+            (: name (-> Animal Symbol))
+            (= (name $x) named)
+        ";
+        assert_eq!(run_program(&format!("{header} !(name tom)")), Ok(vec![vec![expr!("named")]]));
+        assert_eq!(run_program(&format!("{header} !(name (make-cat))")), Ok(vec![vec![expr!("named")]]));
+        assert_eq!(run_program(&format!("{header} !(name rock)")), Ok(vec![vec![expr!("Error" "rock" "BadType")]]));
+        assert_eq!(run_program(&format!("{header} !(metta (make-cat) Animal &self)")), Ok(vec![vec![expr!("tom")]]));
+
+        let result = run_program("
+            (:< Number Real)
+            (: half (-> Real Real))
+            (= (half $x) (/ $x 2))
+            !(half 4)
+        ");
+        assert_eq!(result, Ok(vec![vec![expr!({Number::Integer(2)})]]));
+    }
+
+    #[test]
+    fn metta_subtype_cycle_is_rejected() {
+        let result = run_program("
+            (:< Cat Mammal)
+            (:< Mammal Animal)
+            (:< Animal Cat)
+        ");
+        assert_eq!(result, Ok(vec![vec![expr!("Error" (":<" "Animal" "Cat") "SubtypeCycle")]]));
+    }
+
+    #[test]
+    fn metta_type_class_method() {
+        let header = "
+            (: Vec Type)
+            (: vec (-> Number Number Vec))
+            (: vec-add (-> Vec Vec Vec))
+            (= (vec-add (vec $a $b) (vec $c $d)) (vec (+ $a $c) (+ $b $d)))
+            (: str-add (-> String String String))
+            (= (str-add $a $b) added)
+
+            (: <> (=> (Semigroup $t) (-> $t $t $t)))
+            (@instance (Semigroup Vec) <> vec-add)
+            (@instance (Semigroup String) <> str-add)
+            (= (twice $x) (<> $x $x))
+        ";
+        assert_eq!(run_program(&format!("{header} !(<> (vec 1 2) (vec 3 4))")),
+            Ok(vec![vec![expr!("vec" {Number::Integer(4)} {Number::Integer(6)})]]));
+        assert_eq!(run_program(&format!("{header} !(<> \"a\" \"b\")")), Ok(vec![vec![expr!("added")]]));
+        assert_eq!(run_program(&format!("{header} !(twice (vec 1 2))")),
+            Ok(vec![vec![expr!("vec" {Number::Integer(2)} {Number::Integer(4)})]]));
+        assert_eq!(run_program(&format!("{header} !(<> 1 2)")),
+            Ok(vec![vec![expr!("Error" ("<>" {Number::Integer(1)} {Number::Integer(2)}) "BadType")]]));
+        assert_eq!(run_program(&format!("{header} !(get-type (<> (vec 1 2) (vec 3 4)))")), Ok(vec![vec![expr!("Vec")]]));
+        assert_eq!(run_program(&format!("{header} !(get-type (<> 1 2))")), Ok(vec![vec![]]));
+    }
+
+    #[test]
+    fn metta_type_class_declaration() {
+        let header = "
+            (: Vec Type)
+            (: vec (-> Number Number Vec))
+            (: vec-add (-> Vec Vec Vec))
+            (= (vec-add (vec $a $b) (vec $c $d)) (vec (+ $a $c) (+ $b $d)))
+            (: vec-neg (-> Vec Vec))
+            (= (vec-neg (vec $a $b)) (vec (- 0 $a) (- 0 $b)))
+            (: str-add (-> String String String))
+            (= (str-add $a $b) added)
+
+            (@class (Group $t) ((: <> (-> $t $t $t)) (: inv (-> $t $t))))
+            (@instance (Group Vec) <> vec-add)
+            (@instance (Group Vec) inv vec-neg)
+            (@instance (Group String) <> str-add)
+            (@instance (Group Number) <> str-add)
+            (@instance (Group Number) inv str-add)
+        ";
+        assert_eq!(run_program(&format!("{header} !(inv (<> (vec 1 2) (vec 3 4)))")),
+            Ok(vec![vec![expr!("vec" {Number::Integer(-4)} {Number::Integer(-6)})]]));
+        let typ = run_program(&format!("{header} !(get-type inv)")).unwrap()[0][0].clone();
+        assert!(atoms_are_equivalent(&typ, &expr!("=>" ("Group" t) ("->" t t))));
+        assert_eq!(run_program(&format!("{header} !(<> \"a\" \"b\")")),
+            Ok(vec![vec![Atom::expr([ERROR_SYMBOL, expr!("<>" {Str::from_str("a")} {Str::from_str("b")}),
+                Atom::sym("(Group String) instance doesn't implement inv method")])]]));
+        assert_eq!(run_program(&format!("{header} !(<> 1 2)")),
+            Ok(vec![vec![Atom::expr([ERROR_SYMBOL, expr!("<>" {Number::Integer(1)} {Number::Integer(2)}),
+                Atom::sym("<> method of (Group Number) instance should have type (-> Number Number Number)")])]]));
+    }
+
+    #[test]
+    fn metta_type_class_grounded_method() {
+        let program = "
+            (: Vec Type)
+            (: vec (-> Number Number Vec))
+            (: vec-add (-> Vec Vec Vec))
+            (= (vec-add (vec $a $b) (vec $c $d)) (vec (+ $a $c) (+ $b $d)))
+
+            (: + (=> (Num $t) (-> $t $t $t)))
+            (@instance (Num Vec) + vec-add)
+
+            !(+ (vec 1 2) (vec 3 4))
+            !(+ 1 2)
+            !(+ 1 \"a\")
+        ";
+        assert_eq!(run_program(program), Ok(vec![
+            vec![expr!("vec" {Number::Integer(4)} {Number::Integer(6)})],
+            vec![expr!({Number::Integer(3)})],
+            vec![expr!("Error" {Str::from_str("a")} "BadType")],
+        ]));
+    }
+
     #[test]
     fn metta_interpret_tuple() {
         assert_eq!(run_program("!(metta () %Undefined% &self)"), Ok(vec![vec![expr!(())]]));
@@ -194,6 +194,85 @@ impl CustomExecute for ModSpaceOp {
     }
 }
 
+/// unload-module! removes a loaded module from the runner and from all modules which imported it
+#[derive(Clone, Debug)]
+pub struct UnloadModuleOp {
+    //TODO-HACK: This is a terrible horrible ugly hack that should be fixed ASAP
+    context: std::sync::Arc<std::sync::Mutex<Vec<std::sync::Arc<std::sync::Mutex<&'static mut RunContext<'static, 'static>>>>>>,
+}
+
+grounded_op!(UnloadModuleOp, "unload-module!");
+
+impl UnloadModuleOp {
+    pub fn new(metta: Metta) -> Self {
+        Self{ context: metta.0.context.clone() }
+    }
+}
+
+impl Grounded for UnloadModuleOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_ATOM, UNIT_TYPE])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for UnloadModuleOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = "unload-module! expects a module name argument";
+        let mod_name = args.first().and_then(expect_string_like_atom).ok_or_else(|| ExecError::from(arg_error))?;
+
+        //TODO: Remove this hack to access the RunContext, when it's part of the arguments to `execute`
+        let ctx_ref = self.context.lock().unwrap().last().unwrap().clone();
+        let context = ctx_ref.lock().unwrap();
+        let mod_id = context.get_module_by_name(&mod_name)?;
+        context.metta().unload_module(mod_id)?;
+        unit_result()
+    }
+}
+
+/// reload-module! loads a module again using its loader, and replaces the module in all modules
+/// which imported it
+#[derive(Clone, Debug)]
+pub struct ReloadModuleOp {
+    //TODO-HACK: This is a terrible horrible ugly hack that should be fixed ASAP
+    context: std::sync::Arc<std::sync::Mutex<Vec<std::sync::Arc<std::sync::Mutex<&'static mut RunContext<'static, 'static>>>>>>,
+}
+
+grounded_op!(ReloadModuleOp, "reload-module!");
+
+impl ReloadModuleOp {
+    pub fn new(metta: Metta) -> Self {
+        Self{ context: metta.0.context.clone() }
+    }
+}
+
+impl Grounded for ReloadModuleOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_ATOM, UNIT_TYPE])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for ReloadModuleOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = "reload-module! expects a module name argument";
+        let mod_name = args.first().and_then(expect_string_like_atom).ok_or_else(|| ExecError::from(arg_error))?;
+
+        //TODO: Remove this hack to access the RunContext, when it's part of the arguments to `execute`
+        let ctx_ref = self.context.lock().unwrap().last().unwrap().clone();
+        let context = ctx_ref.lock().unwrap();
+        let mod_id = context.get_module_by_name(&mod_name)?;
+        context.metta().reload_module(mod_id)?;
+        unit_result()
+    }
+}
+
 /// This operation prints the modules loaded from the top of the runner
 ///
 /// NOTE: This is a temporary stop-gap to help MeTTa users inspect which modules they have loaded and
@@ -278,6 +357,10 @@ pub fn register_common_tokens(tref: &mut Tokenizer, metta: &Metta) {
     tref.register_token(regex(r"mod-space!"), move |_| { mod_space_op.clone() });
     let print_mods_op = Atom::gnd(PrintModsOp::new(metta.clone()));
     tref.register_token(regex(r"print-mods!"), move |_| { print_mods_op.clone() });
+    let unload_module_op = Atom::gnd(UnloadModuleOp::new(metta.clone()));
+    tref.register_token(regex(r"unload-module!"), move |_| { unload_module_op.clone() });
+    let reload_module_op = Atom::gnd(ReloadModuleOp::new(metta.clone()));
+    tref.register_token(regex(r"reload-module!"), move |_| { reload_module_op.clone() });
 }
 
 #[cfg(test)]
@@ -0,0 +1,417 @@
+use crate::*;
+use crate::space::*;
+use crate::metta::*;
+use crate::metta::text::Tokenizer;
+use crate::metta::runner::Metta;
+use crate::metta::runner::number::*;
+use crate::metta::runner::bool::*;
+use crate::metta::runner::str::*;
+use crate::atom::matcher::{match_atoms, apply_bindings_to_atom_move};
+use super::{grounded_op, regex, interpret_no_error, unit_result};
+use super::random::{RandomGenerator, ATOM_TYPE_RANDOM_GENERATOR};
+use super::debug::report_assertion;
+
+/// Maximal depth of the generated expressions
+const MAX_DEPTH: usize = 4;
+/// Maximal number of successful shrinking steps
+const MAX_SHRINK_STEPS: usize = 1000;
+
+const SYMBOL_NAMES: &[&str] = &["a", "b", "c", "d", "e"];
+const STRING_CHARS: &[char] = &['a', 'b', 'c', 'x', 'y', 'z', '0', '1', ' '];
+
+/// Generates random atoms of the given type and shrinks them. Values of the
+/// builtin types are generated directly, values of the custom types are
+/// constructed using type declarations of the space: `(: Nil (List $t))`,
+/// `(: Cons (-> $t (List $t) (List $t)))`. Declarations of the functions,
+/// which have `=` definitions, are not considered as constructors.
+struct AtomGenerator<'a> {
+    space: &'a DynSpace,
+    rng: RandomGenerator,
+}
+
+impl AtomGenerator<'_> {
+    fn chance(&self, percent: u32) -> bool {
+        self.rng.random_range(0..100) < percent
+    }
+
+    fn choose<'b, T>(&self, items: &'b [T]) -> &'b T {
+        &items[self.rng.random_range(0..items.len())]
+    }
+
+    fn generate(&self, typ: &Atom, depth: usize) -> Result<Atom, String> {
+        if *typ == ATOM_TYPE_NUMBER {
+            Ok(self.number())
+        } else if *typ == ATOM_TYPE_STRING {
+            let len = self.rng.random_range(0..=8);
+            Ok(Atom::gnd(Str::from_string((0..len).map(|_| *self.choose(STRING_CHARS)).collect())))
+        } else if *typ == ATOM_TYPE_BOOL {
+            Ok(Atom::gnd(Bool(self.chance(50))))
+        } else if *typ == ATOM_TYPE_SYMBOL {
+            Ok(Atom::sym(*self.choose(SYMBOL_NAMES)))
+        } else if *typ == ATOM_TYPE_EXPRESSION {
+            let len = if depth >= MAX_DEPTH { 0 } else { self.rng.random_range(0..=3) };
+            (0..len).map(|_| self.generate(&ATOM_TYPE_ATOM, depth + 1))
+                .collect::<Result<Vec<_>, _>>().map(Atom::expr)
+        } else if *typ == ATOM_TYPE_ATOM || *typ == ATOM_TYPE_UNDEFINED {
+            let types = [ATOM_TYPE_NUMBER, ATOM_TYPE_STRING, ATOM_TYPE_SYMBOL, ATOM_TYPE_EXPRESSION];
+            let typ = if depth >= MAX_DEPTH { &types[..3] } else { &types[..] };
+            self.generate(self.choose(typ), depth)
+        } else {
+            self.construct(typ, depth)
+        }
+    }
+
+    fn number(&self) -> Atom {
+        let number = if self.chance(20) {
+            Number::Integer(*self.choose(&[0, 1, -1]))
+        } else if self.chance(80) {
+            Number::Integer(self.rng.random_range(-100..=100))
+        } else {
+            Number::Float(self.rng.random_range(-100.0..100.0))
+        };
+        Atom::gnd(number)
+    }
+
+    fn construct(&self, typ: &Atom, depth: usize) -> Result<Atom, String> {
+        let mut constructors = self.constructors(typ);
+        if constructors.is_empty() {
+            return Err(format!("Cannot generate values of type {}", typ));
+        }
+        if depth >= MAX_DEPTH {
+            let min_arity = constructors.iter().map(|(_, args)| args.len()).min().unwrap_or(0);
+            constructors.retain(|(_, args)| args.len() == min_arity);
+        }
+        let (name, arg_types) = self.choose(&constructors);
+        if arg_types.is_empty() {
+            Ok(name.clone())
+        } else {
+            let mut children = vec![name.clone()];
+            for arg_type in arg_types {
+                children.push(self.generate(arg_type, depth + 1)?);
+            }
+            Ok(Atom::expr(children))
+        }
+    }
+
+    /// Returns constructors of the type and types of their arguments
+    fn constructors(&self, typ: &Atom) -> Vec<(Atom, Vec<Atom>)> {
+        let var_c = VariableAtom::new("c").make_unique();
+        let var_t = VariableAtom::new("t").make_unique();
+        let query = Atom::expr([HAS_TYPE_SYMBOL, Atom::Variable(var_c.clone()), Atom::Variable(var_t.clone())]);
+        let mut constructors = vec![];
+        for bindings in self.space.borrow().query(&query) {
+            let name = match bindings.resolve(&var_c) {
+                Some(name @ Atom::Symbol(_)) => name,
+                _ => continue,
+            };
+            let decl = match bindings.resolve(&var_t) {
+                Some(decl) => make_variables_unique(decl),
+                None => continue,
+            };
+            let (arg_types, ret_type) = match &decl {
+                Atom::Expression(expr) if expr.children().first() == Some(&ARROW_SYMBOL) => {
+                    let (ret, args) = expr.children()[1..].split_last().unwrap_or((&decl, &[]));
+                    (args.to_vec(), ret.clone())
+                },
+                _ => (vec![], decl.clone()),
+            };
+            if self.is_function(&name, arg_types.len()) {
+                continue;
+            }
+            if let Some(bindings) = match_atoms(&ret_type, typ).next() {
+                let arg_types = arg_types.into_iter()
+                    .map(|arg| apply_bindings_to_atom_move(arg, &bindings))
+                    .collect();
+                constructors.push((name, arg_types));
+            }
+        }
+        constructors
+    }
+
+    fn is_function(&self, name: &Atom, arity: usize) -> bool {
+        let args = (0..arity).map(|_| Atom::Variable(VariableAtom::new("a").make_unique()));
+        let call = if arity == 0 { name.clone() } else { Atom::expr(std::iter::once(name.clone()).chain(args).collect::<Vec<_>>()) };
+        let query = Atom::expr([EQUAL_SYMBOL, call, Atom::Variable(VariableAtom::new("b").make_unique())]);
+        !self.space.borrow().query(&query).is_empty()
+    }
+
+    /// Returns the candidates which are simpler than the atom in order of
+    /// preference
+    fn shrink(&self, atom: &Atom, typ: &Atom) -> Vec<Atom> {
+        let mut candidates = if let Some(number) = Number::from_atom(atom) {
+            match number {
+                Number::Integer(n) => vec![0, n / 2, n - n.signum()].into_iter()
+                    .map(|n| Atom::gnd(Number::Integer(n))).collect(),
+                Number::Float(0.0) => vec![],
+                Number::Float(f) if f.fract() != 0.0 => vec![Atom::gnd(Number::Integer(0)), Atom::gnd(Number::Float(f.trunc()))],
+                Number::Float(f) => vec![0.0, (f / 2.0).trunc(), f - f.signum()].into_iter()
+                    .map(|f| Atom::gnd(Number::Float(f))).collect(),
+                _ => vec![Atom::gnd(Number::Integer(0))],
+            }
+        } else if let Some(s) = Str::from_atom(atom) {
+            let chars: Vec<char> = s.as_str().chars().collect();
+            let mut candidates = vec![String::new(), chars[..chars.len() / 2].iter().collect()];
+            candidates.extend((0..chars.len()).map(|i| chars[..i].iter().chain(chars[i + 1..].iter()).collect()));
+            candidates.into_iter().map(|s| Atom::gnd(Str::from_string(s))).collect()
+        } else if let Some(Bool(b)) = Bool::from_atom(atom) {
+            if b { vec![Atom::gnd(Bool(false))] } else { vec![] }
+        } else if *typ == ATOM_TYPE_ATOM || *typ == ATOM_TYPE_UNDEFINED || *typ == ATOM_TYPE_EXPRESSION
+            || *typ == ATOM_TYPE_SYMBOL {
+            match atom {
+                Atom::Symbol(_) => vec![Atom::sym(SYMBOL_NAMES[0])],
+                Atom::Expression(expr) => {
+                    let children = expr.children();
+                    let mut candidates = vec![Atom::expr([])];
+                    candidates.extend((0..children.len()).map(|i| Atom::expr(children[..i].iter()
+                        .chain(children[i + 1..].iter()).cloned().collect::<Vec<_>>())));
+                    candidates.extend(self.shrink_children(children, &vec![ATOM_TYPE_ATOM; children.len()]));
+                    candidates
+                },
+                _ => vec![],
+            }
+        } else {
+            self.shrink_constructed(atom, typ)
+        };
+        let mut unique = Vec::with_capacity(candidates.len());
+        for candidate in candidates.drain(..) {
+            if candidate != *atom && !unique.contains(&candidate) {
+                unique.push(candidate);
+            }
+        }
+        unique
+    }
+
+    fn shrink_constructed(&self, atom: &Atom, typ: &Atom) -> Vec<Atom> {
+        let constructors = self.constructors(typ);
+        let mut candidates: Vec<Atom> = constructors.iter()
+            .filter(|(_, args)| args.is_empty())
+            .map(|(name, _)| name.clone())
+            .collect();
+        if let Atom::Expression(expr) = atom {
+            let children = expr.children();
+            let arg_types = constructors.iter()
+                .find(|(name, args)| Some(name) == children.first() && args.len() + 1 == children.len())
+                .map(|(_, args)| args);
+            if let Some(arg_types) = arg_types {
+                candidates.extend(children[1..].iter().zip(arg_types.iter())
+                    .filter(|(_, arg_type)| *arg_type == typ)
+                    .map(|(child, _)| child.clone()));
+                let shrunk = self.shrink_children(&children[1..], arg_types);
+                candidates.extend(shrunk.into_iter().map(|args| match args {
+                    Atom::Expression(args) => Atom::expr(std::iter::once(children[0].clone())
+                        .chain(args.children().iter().cloned()).collect::<Vec<_>>()),
+                    _ => unreachable!(),
+                }));
+            }
+        }
+        candidates
+    }
+
+    /// Returns expressions where one of the children is replaced by its
+    /// shrinking candidate
+    fn shrink_children(&self, children: &[Atom], types: &[Atom]) -> Vec<Atom> {
+        let mut candidates = vec![];
+        for (i, (child, typ)) in children.iter().zip(types.iter()).enumerate() {
+            for candidate in self.shrink(child, typ) {
+                let mut shrunk = children.to_vec();
+                shrunk[i] = candidate;
+                candidates.push(Atom::expr(shrunk));
+            }
+        }
+        candidates
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct CheckPropertyOp {
+    space: DynSpace,
+    metta: Metta,
+}
+
+grounded_op!(CheckPropertyOp, "check-property");
+
+impl CheckPropertyOp {
+    pub fn new(space: DynSpace, metta: Metta) -> Self {
+        Self{ space, metta }
+    }
+
+    fn holds(&self, property: &Atom, args: &[Atom]) -> bool {
+        let call = Atom::expr(std::iter::once(property.clone()).chain(args.iter().cloned()).collect::<Vec<_>>());
+        match interpret_no_error(self.space.clone(), &call) {
+            Ok(results) => !results.is_empty() && results.iter().all(|r| *r == Atom::gnd(Bool(true))),
+            Err(_) => false,
+        }
+    }
+
+    fn check(&self, rng: &RandomGenerator, property: &Atom, arg_types: &[Atom], trials: usize) -> Result<Vec<Atom>, ExecError> {
+        let generator = AtomGenerator{ space: &self.space, rng: rng.clone() };
+        for trial in 1..=trials {
+            let args = arg_types.iter()
+                .map(|typ| generator.generate(typ, 0))
+                .collect::<Result<Vec<_>, _>>()?;
+            if self.holds(property, &args) {
+                continue;
+            }
+
+            let original = Atom::expr(std::iter::once(property.clone()).chain(args.iter().cloned()).collect::<Vec<_>>());
+            let mut args = args;
+            let mut steps = 0;
+            'shrink: while steps < MAX_SHRINK_STEPS {
+                for (i, typ) in arg_types.iter().enumerate() {
+                    for candidate in generator.shrink(&args[i], typ) {
+                        let mut shrunk = args.clone();
+                        shrunk[i] = candidate;
+                        if !self.holds(property, &shrunk) {
+                            args = shrunk;
+                            steps += 1;
+                            continue 'shrink;
+                        }
+                    }
+                }
+                break;
+            }
+            let counterexample = Atom::expr(std::iter::once(property.clone()).chain(args).collect::<Vec<_>>());
+            return Err(ExecError::Runtime(format!("\nProperty failed after {} trials\nCounterexample: {}\nOriginal counterexample: {}\nShrinking steps: {}",
+                trial, counterexample, original, steps)));
+        }
+        unit_result()
+    }
+}
+
+impl Grounded for CheckPropertyOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_RANDOM_GENERATOR, ATOM_TYPE_ATOM, ATOM_TYPE_UNDEFINED, ATOM_TYPE_NUMBER, UNIT_TYPE])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for CheckPropertyOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("check-property expects four arguments: random generator, property, signature (-> <arg-types> ... <ret-type>) and number of trials");
+        let rng = args.first().and_then(Atom::as_gnd::<RandomGenerator>).ok_or_else(arg_error)?;
+        let property = args.get(1).ok_or_else(arg_error)?;
+        let arg_types = match args.get(2) {
+            Some(Atom::Expression(expr)) if expr.children().first() == Some(&ARROW_SYMBOL) && expr.children().len() > 1 =>
+                &expr.children()[1..expr.children().len() - 1],
+            _ => return Err(arg_error()),
+        };
+        let trials = args.get(3).and_then(Number::from_atom)
+            .and_then(|n| usize::try_from(Into::<i64>::into(n)).ok())
+            .ok_or_else(arg_error)?;
+        report_assertion(&self.metta, "check-property", args, self.check(rng, property, arg_types, trials))
+    }
+}
+
+pub fn register_runner_tokens(tref: &mut Tokenizer, space: &DynSpace, metta: &Metta) {
+    let check_property_op = Atom::gnd(CheckPropertyOp::new(space.clone(), metta.clone()));
+    tref.register_token(regex(r"check-property"), move |_| { check_property_op.clone() });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metta::runner::stdlib::tests::run_program;
+
+    fn generator(space: &DynSpace, seed: u64) -> AtomGenerator<'_> {
+        AtomGenerator{ space, rng: RandomGenerator::from_seed_u64(seed) }
+    }
+
+    #[test]
+    fn generate_builtin_types() {
+        let space = DynSpace::new(GroundingSpace::new());
+        let generator = generator(&space, 1);
+        for _ in 0..20 {
+            assert!(Number::from_atom(&generator.generate(&ATOM_TYPE_NUMBER, 0).unwrap()).is_some());
+            assert!(Str::from_atom(&generator.generate(&ATOM_TYPE_STRING, 0).unwrap()).is_some());
+            assert!(matches!(generator.generate(&ATOM_TYPE_SYMBOL, 0).unwrap(), Atom::Symbol(_)));
+            assert!(matches!(generator.generate(&ATOM_TYPE_EXPRESSION, 0).unwrap(), Atom::Expression(_)));
+        }
+        assert_eq!(generator.generate(&sym!("Foo"), 0), Err("Cannot generate values of type Foo".into()));
+    }
+
+    #[test]
+    fn generate_and_shrink_constructed_types() {
+        let space = DynSpace::new(crate::common::test_utils::metta_space("
+            (: Nil (List $t))
+            (: Cons (-> $t (List $t) (List $t)))
+            (: length (-> (List $t) Number))
+            (= (length Nil) 0)
+        "));
+        let generator = generator(&space, 2);
+        let typ = expr!("List" "Number");
+        assert_eq!(generator.constructors(&typ).len(), 2);
+        for _ in 0..20 {
+            let mut list = generator.generate(&typ, 0).unwrap();
+            while let Atom::Expression(expr) = &list {
+                assert_eq!(expr.children()[0], sym!("Cons"));
+                assert!(Number::from_atom(&expr.children()[1]).is_some());
+                list = expr.children()[2].clone();
+            }
+            assert_eq!(list, sym!("Nil"));
+        }
+
+        let list = expr!("Cons" {Number::Integer(5)} ("Cons" {Number::Integer(0)} "Nil"));
+        let candidates = generator.shrink(&list, &typ);
+        assert_eq!(candidates[..2], [sym!("Nil"), expr!("Cons" {Number::Integer(0)} "Nil")]);
+    }
+
+    #[test]
+    fn shrink_builtin_types() {
+        let space = DynSpace::new(GroundingSpace::new());
+        let generator = generator(&space, 3);
+        assert_eq!(generator.shrink(&Atom::gnd(Number::Integer(10)), &ATOM_TYPE_NUMBER),
+            vec![Atom::gnd(Number::Integer(0)), Atom::gnd(Number::Integer(5)), Atom::gnd(Number::Integer(9))]);
+        assert_eq!(generator.shrink(&Atom::gnd(Number::Integer(0)), &ATOM_TYPE_NUMBER), vec![]);
+        assert_eq!(generator.shrink(&Atom::gnd(Number::Float(4.0)), &ATOM_TYPE_NUMBER),
+            vec![Atom::gnd(Number::Float(0.0)), Atom::gnd(Number::Float(2.0)), Atom::gnd(Number::Float(3.0))]);
+        assert_eq!(generator.shrink(&Atom::gnd(Number::Float(0.0)), &ATOM_TYPE_NUMBER), vec![]);
+        assert_eq!(generator.shrink(&Atom::gnd(Number::Float(-0.0)), &ATOM_TYPE_NUMBER), vec![]);
+        assert_eq!(generator.shrink(&Atom::gnd(Str::from_str("ab")), &ATOM_TYPE_STRING),
+            vec![Atom::gnd(Str::from_str("")), Atom::gnd(Str::from_str("a")), Atom::gnd(Str::from_str("b"))]);
+    }
+
+    #[test]
+    fn metta_check_property() {
+        let program = "
+            (: add-commutes (-> Number Number Bool))
+            (= (add-commutes $a $b) (== (+ $a $b) (+ $b $a)))
+            !(check-property (new-random-generator 1) add-commutes (get-type add-commutes) 50)
+        ";
+        assert_eq!(run_program(program), Ok(vec![vec![UNIT_ATOM]]));
+    }
+
+    #[test]
+    fn check_property_shrinks_counterexample() {
+        let metta = Metta::new(Some(crate::metta::runner::EnvBuilder::test_env()));
+        metta.run(crate::metta::text::SExprParser::new("
+            (: small (-> Number Bool))
+            (= (small $x) (< $x 10))
+        ")).unwrap();
+        let op = CheckPropertyOp::new(metta.space().clone(), metta.clone());
+        let rng = Atom::gnd(RandomGenerator::from_seed_u64(4));
+        let result = op.execute(&[rng, sym!("small"), expr!("->" "Number" "Bool"), Atom::gnd(Number::Integer(100))]);
+        match result {
+            Err(ExecError::Runtime(msg)) => assert!(msg.contains("\nCounterexample: (small 10)\n"), "{}", msg),
+            _ => panic!("Error is expected, found: {:?}", result),
+        }
+
+        metta.run(crate::metta::text::SExprParser::new("
+            (: Nil (List $t))
+            (: Cons (-> $t (List $t) (List $t)))
+            (: length (-> (List $t) Number))
+            (= (length Nil) 0)
+            (= (length (Cons $h $t)) (+ 1 (length $t)))
+            (: short (-> (List Number) Bool))
+            (= (short $l) (< (length $l) 2))
+        ")).unwrap();
+        let rng = Atom::gnd(RandomGenerator::from_seed_u64(5));
+        let result = op.execute(&[rng, sym!("short"), expr!("->" ("List" "Number") "Bool"), Atom::gnd(Number::Integer(100))]);
+        match result {
+            Err(ExecError::Runtime(msg)) => assert!(msg.contains("\nCounterexample: (short (Cons 0 (Cons 0 Nil)))\n"), "{}", msg),
+            _ => panic!("Error is expected, found: {:?}", result),
+        }
+    }
+}
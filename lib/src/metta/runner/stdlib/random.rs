@@ -9,6 +9,10 @@ use crate::metta::runner::bool::*;
 use std::fmt::{Display, Formatter};
 use std::cell::RefCell;
 use rand::{Rng, SeedableRng, rngs::StdRng};
+use rand::distr::{Bernoulli, Distribution};
+use rand::distr::weighted::WeightedIndex;
+use rand::seq::SliceRandom;
+use rand_distr::{Exp, Normal};
 use std::rc::Rc;
 
 //TODO: In the current version of rand it is possible for rust to hang if range end's value is too
@@ -27,7 +31,7 @@ impl RandomGenerator {
         Self(Rc::new(RefCell::new(StdRng::from_os_rng())))
     }
 
-    fn from_seed_u64(seed: u64) -> Self {
+    pub(crate) fn from_seed_u64(seed: u64) -> Self {
         Self(Rc::new(RefCell::new(StdRng::seed_from_u64(seed))))
     }
 
@@ -39,13 +43,21 @@ impl RandomGenerator {
         *self.0.borrow_mut() = StdRng::from_os_rng();
     }
 
-    fn random_range<T, R>(&self, range: R) -> T 
+    pub(crate) fn random_range<T, R>(&self, range: R) -> T 
         where
             T: rand::distr::uniform::SampleUniform,
             R: rand::distr::uniform::SampleRange<T>,
     {
         self.0.borrow_mut().random_range(range)
     }
+
+    fn sample<T, D: Distribution<T>>(&self, distr: &D) -> T {
+        distr.sample(&mut *self.0.borrow_mut())
+    }
+
+    fn shuffle<T>(&self, items: &mut [T]) {
+        items.shuffle(&mut *self.0.borrow_mut())
+    }
 }
 
 impl Grounded for RandomGenerator {
@@ -201,6 +213,215 @@ impl CustomExecute for ResetRandomGeneratorOp {
     }
 }
 
+fn generator_arg(arg: Option<&Atom>) -> Option<&RandomGenerator> {
+    arg.and_then(Atom::as_gnd::<RandomGenerator>)
+}
+
+fn float_arg(arg: Option<&Atom>) -> Option<f64> {
+    arg.and_then(Number::from_atom).map(Into::<f64>::into)
+}
+
+fn expression_arg(arg: Option<&Atom>) -> Option<&[Atom]> {
+    arg.and_then(|atom| TryInto::<&ExpressionAtom>::try_into(atom).ok())
+        .map(ExpressionAtom::children)
+}
+
+fn weighted_index(weights: &[Atom]) -> Result<WeightedIndex<f64>, ExecError> {
+    let weights = weights.iter()
+        .map(|w| Number::from_atom(w).map(Into::<f64>::into))
+        .collect::<Option<Vec<f64>>>()
+        .ok_or("weights are expected to be numbers")?;
+    WeightedIndex::new(weights).map_err(|_| ExecError::from("InvalidWeights"))
+}
+
+#[derive(Clone, Debug)]
+pub struct RandomNormalOp {}
+
+grounded_op!(RandomNormalOp, "random-normal");
+
+impl Grounded for RandomNormalOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_RANDOM_GENERATOR, ATOM_TYPE_NUMBER, ATOM_TYPE_NUMBER, ATOM_TYPE_NUMBER])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for RandomNormalOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("random-normal expects three arguments: random generator, number (mean) and number (standard deviation)");
+        let generator = generator_arg(args.first()).ok_or_else(arg_error)?;
+        let mean = float_arg(args.get(1)).ok_or_else(arg_error)?;
+        let std_dev = float_arg(args.get(2)).ok_or_else(arg_error)?;
+        if std_dev < 0.0 {
+            return Err(ExecError::from("InvalidDistributionParameters"));
+        }
+        let normal = Normal::new(mean, std_dev).map_err(|_| ExecError::from("InvalidDistributionParameters"))?;
+        Ok(vec![Atom::gnd(Number::Float(generator.sample(&normal)))])
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct RandomExponentialOp {}
+
+grounded_op!(RandomExponentialOp, "random-exponential");
+
+impl Grounded for RandomExponentialOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_RANDOM_GENERATOR, ATOM_TYPE_NUMBER, ATOM_TYPE_NUMBER])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for RandomExponentialOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("random-exponential expects two arguments: random generator and number (rate)");
+        let generator = generator_arg(args.first()).ok_or_else(arg_error)?;
+        let rate = float_arg(args.get(1)).ok_or_else(arg_error)?;
+        let exp = Exp::new(rate).map_err(|_| ExecError::from("InvalidDistributionParameters"))?;
+        Ok(vec![Atom::gnd(Number::Float(generator.sample(&exp)))])
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct RandomBernoulliOp {}
+
+grounded_op!(RandomBernoulliOp, "random-bernoulli");
+
+impl Grounded for RandomBernoulliOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_RANDOM_GENERATOR, ATOM_TYPE_NUMBER, ATOM_TYPE_BOOL])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for RandomBernoulliOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("random-bernoulli expects two arguments: random generator and number (probability)");
+        let generator = generator_arg(args.first()).ok_or_else(arg_error)?;
+        let p = float_arg(args.get(1)).ok_or_else(arg_error)?;
+        let bernoulli = Bernoulli::new(p).map_err(|_| ExecError::from("InvalidDistributionParameters"))?;
+        Ok(vec![Atom::gnd(Bool(generator.sample(&bernoulli)))])
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct RandomCategoricalOp {}
+
+grounded_op!(RandomCategoricalOp, "random-categorical");
+
+impl Grounded for RandomCategoricalOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_RANDOM_GENERATOR, ATOM_TYPE_EXPRESSION, ATOM_TYPE_NUMBER])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for RandomCategoricalOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("random-categorical expects two arguments: random generator and expression of weights");
+        let generator = generator_arg(args.first()).ok_or_else(arg_error)?;
+        let weights = expression_arg(args.get(1)).ok_or_else(arg_error)?;
+        let index = generator.sample(&weighted_index(weights)?);
+        Ok(vec![Atom::gnd(Number::Integer(index as i64))])
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct RandomChoiceOp {}
+
+grounded_op!(RandomChoiceOp, "random-choice");
+
+impl Grounded for RandomChoiceOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_RANDOM_GENERATOR, ATOM_TYPE_EXPRESSION, ATOM_TYPE_ATOM])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for RandomChoiceOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("random-choice expects two arguments: random generator and non-empty expression");
+        let generator = generator_arg(args.first()).ok_or_else(arg_error)?;
+        let children = expression_arg(args.get(1)).ok_or_else(arg_error)?;
+        if children.is_empty() {
+            return Err(arg_error());
+        }
+        Ok(vec![children[generator.random_range(0..children.len())].clone()])
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ShuffleOp {}
+
+grounded_op!(ShuffleOp, "shuffle");
+
+impl Grounded for ShuffleOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_RANDOM_GENERATOR, ATOM_TYPE_EXPRESSION, ATOM_TYPE_EXPRESSION])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for ShuffleOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("shuffle expects two arguments: random generator and expression");
+        let generator = generator_arg(args.first()).ok_or_else(arg_error)?;
+        let mut children = expression_arg(args.get(1)).ok_or_else(arg_error)?.to_vec();
+        generator.shuffle(&mut children);
+        Ok(vec![Atom::expr(children)])
+    }
+}
+
+/// Chooses one of the alternatives using given weights. Alternatives are not
+/// evaluated before the choice, the chosen one is returned unevaluated and
+/// thus evaluated by the interpreter afterwards.
+#[derive(Clone, Debug)]
+pub struct SampleSuperposeOp {}
+
+grounded_op!(SampleSuperposeOp, "sample-superpose");
+
+impl Grounded for SampleSuperposeOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_RANDOM_GENERATOR, ATOM_TYPE_EXPRESSION, ATOM_TYPE_EXPRESSION, ATOM_TYPE_UNDEFINED])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for SampleSuperposeOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("sample-superpose expects three arguments: random generator, expression of alternatives and expression of weights");
+        let generator = generator_arg(args.first()).ok_or_else(arg_error)?;
+        let alternatives = expression_arg(args.get(1)).ok_or_else(arg_error)?;
+        let weights = expression_arg(args.get(2)).ok_or_else(arg_error)?;
+        if alternatives.len() != weights.len() {
+            return Err(ExecError::from("sample-superpose expects the same number of alternatives and weights"));
+        }
+        let index = generator.sample(&weighted_index(weights)?);
+        Ok(vec![alternatives[index].clone()])
+    }
+}
+
 
 // NOTE: flip is absent in Python intentionally for conversion testing
 #[derive(Clone, PartialEq, Debug)]
@@ -239,6 +460,20 @@ pub fn register_common_tokens(tref: &mut Tokenizer) {
     tref.register_token(regex(r"new-random-generator"), move |_| { new_random_generator_op.clone() });
     let reset_random_generator_op = Atom::gnd(ResetRandomGeneratorOp{});
     tref.register_token(regex(r"reset-random-generator"), move |_| { reset_random_generator_op.clone() });
+    let random_normal_op = Atom::gnd(RandomNormalOp{});
+    tref.register_token(regex(r"random-normal"), move |_| { random_normal_op.clone() });
+    let random_exponential_op = Atom::gnd(RandomExponentialOp{});
+    tref.register_token(regex(r"random-exponential"), move |_| { random_exponential_op.clone() });
+    let random_bernoulli_op = Atom::gnd(RandomBernoulliOp{});
+    tref.register_token(regex(r"random-bernoulli"), move |_| { random_bernoulli_op.clone() });
+    let random_categorical_op = Atom::gnd(RandomCategoricalOp{});
+    tref.register_token(regex(r"random-categorical"), move |_| { random_categorical_op.clone() });
+    let random_choice_op = Atom::gnd(RandomChoiceOp{});
+    tref.register_token(regex(r"random-choice"), move |_| { random_choice_op.clone() });
+    let shuffle_op = Atom::gnd(ShuffleOp{});
+    tref.register_token(regex(r"shuffle"), move |_| { shuffle_op.clone() });
+    let sample_superpose_op = Atom::gnd(SampleSuperposeOp{});
+    tref.register_token(regex(r"sample-superpose"), move |_| { sample_superpose_op.clone() });
     let generator = RandomGenerator::from_os_rng();
     tref.register_token(regex(r"&rng"), move |_| { Atom::gnd(generator.clone()) });
     let flip_op = Atom::gnd(FlipOp{});
@@ -336,4 +571,57 @@ mod tests {
         let res2 = RandomFloatOp{}.execute(&mut vec![expr!({gen.clone()}), expr!({Number::Integer(0)}), expr!({Number::Integer(5)})]);
         assert_eq!(res1, res2);
     }
+
+    #[test]
+    fn metta_random_distributions() {
+        assert_eq!(run_program("
+            !(bind! &g (new-random-generator 0))
+            !(let $x (random-normal &g 10 0) (== $x 10.0))
+            !(let $x (random-exponential &g 2) (>= $x 0.0))
+            !(random-bernoulli &g 1)
+            !(random-bernoulli &g 0)
+            !(random-categorical &g (0 0 5 0))
+        "), Ok(vec![vec![UNIT_ATOM], vec![expr!({Bool(true)})], vec![expr!({Bool(true)})],
+            vec![expr!({Bool(true)})], vec![expr!({Bool(false)})], vec![expr!({Number::Integer(2)})]]));
+
+        assert_eq!(run_program("!(assertEqual (random-normal &rng 0 -1) (Error (random-normal &rng 0 -1) InvalidDistributionParameters))"),
+            Ok(vec![vec![UNIT_ATOM]]));
+        assert_eq!(run_program("!(assertEqual (random-bernoulli &rng 2) (Error (random-bernoulli &rng 2) InvalidDistributionParameters))"),
+            Ok(vec![vec![UNIT_ATOM]]));
+        assert_eq!(run_program("!(assertEqual (random-categorical &rng (0 0)) (Error (random-categorical &rng (0 0)) InvalidWeights))"),
+            Ok(vec![vec![UNIT_ATOM]]));
+    }
+
+    #[test]
+    fn metta_random_choice_and_shuffle() {
+        assert_eq!(run_program("!(random-choice &rng (a))"), Ok(vec![vec![expr!("a")]]));
+        assert_eq!(run_program("!(case (random-choice &rng (a b c)) ((a True) (b True) (c True) ($_ False)))"),
+            Ok(vec![vec![expr!({Bool(true)})]]));
+        assert_eq!(RandomChoiceOp{}.execute(&[expr!({RandomGenerator::from_os_rng()}), expr!()]),
+            Err(ExecError::from("random-choice expects two arguments: random generator and non-empty expression")));
+
+        let shuffled = run_program("!(shuffle &rng (1 2 3 4 5))").unwrap();
+        let mut children: Vec<i64> = TryInto::<&ExpressionAtom>::try_into(&shuffled[0][0]).unwrap()
+            .children().iter().map(|n| Number::from_atom(n).unwrap().into()).collect();
+        children.sort();
+        assert_eq!(children, vec![1, 2, 3, 4, 5]);
+
+        assert_eq!(run_program("
+            !(bind! &g (new-random-generator 3))
+            !(shuffle &g (1 2 3 4 5 6 7 8))
+        "), run_program("
+            !(bind! &g (new-random-generator 3))
+            !(shuffle &g (1 2 3 4 5 6 7 8))
+        "));
+    }
+
+    #[test]
+    fn metta_sample_superpose() {
+        assert_eq!(run_program("
+            (= (foo) bar)
+            !(sample-superpose &rng ((foo) baz) (1 0))
+        "), Ok(vec![vec![expr!("bar")]]));
+        assert_eq!(SampleSuperposeOp{}.execute(&[expr!({RandomGenerator::from_os_rng()}), expr!("a" "b"), expr!(({Number::Integer(1)}))]),
+            Err(ExecError::from("sample-superpose expects the same number of alternatives and weights")));
+    }
 }
@@ -2,15 +2,30 @@ use crate::*;
 use crate::metta::*;
 use crate::metta::text::Tokenizer;
 use crate::metta::runner::str::*;
+use crate::metta::runner::number::*;
+use crate::metta::runner::bool::*;
+use crate::metta::runner::Metta;
+use crate::metta::runner::output::OutputStream;
 use super::{grounded_op, unit_result, regex};
 
 use std::convert::TryInto;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
 
 #[derive(Clone, Debug)]
-pub struct PrintlnOp {}
+pub struct PrintlnOp {
+    metta: Metta,
+}
 
 grounded_op!(PrintlnOp, "println!");
 
+impl PrintlnOp {
+    pub fn new(metta: Metta) -> Self {
+        Self{ metta }
+    }
+}
+
 impl Grounded for PrintlnOp {
     fn type_(&self) -> Atom {
         Atom::expr([ARROW_SYMBOL, ATOM_TYPE_UNDEFINED, UNIT_TYPE])
@@ -24,12 +39,87 @@ impl Grounded for PrintlnOp {
 impl CustomExecute for PrintlnOp {
     fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
         let arg_error = || ExecError::from("println! expects single atom as an argument");
-        let atom = args.get(0).ok_or_else(arg_error)?;
-        println!("{}", atom_to_string(atom));
+        let atom = args.first().ok_or_else(arg_error)?;
+        self.metta.output_sink().write(OutputStream::Stdout, &format!("{}\n", atom_to_string(atom)));
         unit_result()
     }
 }
 
+fn input_error(err: std::io::Error) -> ExecError {
+    ExecError::from(format!("Cannot read input: {}", err))
+}
+
+#[derive(Clone, Debug)]
+pub struct ReadLineOp {
+    metta: Metta,
+}
+
+grounded_op!(ReadLineOp, "read-line");
+
+impl ReadLineOp {
+    pub fn new(metta: Metta) -> Self {
+        Self{ metta }
+    }
+}
+
+impl Grounded for ReadLineOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_STRING])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for ReadLineOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        if !args.is_empty() {
+            return Err(ExecError::from("read-line expects no arguments"));
+        }
+        match self.metta.input_source().read_line().map_err(input_error)? {
+            Some(line) => Ok(vec![Atom::gnd(Str::from_string(line))]),
+            None => {
+                let call = Atom::expr([Atom::gnd(self.clone())]);
+                Ok(vec![Atom::expr([ERROR_SYMBOL, call, END_OF_INPUT_SYMBOL])])
+            },
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ReadAllOp {
+    metta: Metta,
+}
+
+grounded_op!(ReadAllOp, "read-all");
+
+impl ReadAllOp {
+    pub fn new(metta: Metta) -> Self {
+        Self{ metta }
+    }
+}
+
+impl Grounded for ReadAllOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_STRING])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for ReadAllOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        if !args.is_empty() {
+            return Err(ExecError::from("read-all expects no arguments"));
+        }
+        let text = self.metta.input_source().read_all().map_err(input_error)?;
+        Ok(vec![Atom::gnd(Str::from_string(text))])
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct FormatArgsOp {}
 
@@ -60,9 +150,723 @@ impl CustomExecute for FormatArgsOp {
     }
 }
 
-pub fn register_runner_tokens(tref: &mut Tokenizer) {
-    let println_op = Atom::gnd(PrintlnOp{});
+fn str_atom(s: String) -> Atom {
+    Atom::gnd(Str::from_string(s))
+}
+
+fn integer_arg(atom: &Atom) -> Option<i64> {
+    match Number::from_atom(atom)? {
+        n if n.is_integer() => Some(n.into()),
+        _ => None,
+    }
+}
+
+/// Converts possibly negative char index into the index inside the string
+/// of `len` chars. Negative index is counted from the end of the string.
+fn char_index(index: i64, len: usize) -> usize {
+    if index < 0 {
+        len.saturating_sub(index.unsigned_abs() as usize)
+    } else {
+        (index as usize).min(len)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct StringLengthOp {}
+
+grounded_op!(StringLengthOp, "string-length");
+
+impl Grounded for StringLengthOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_STRING, ATOM_TYPE_NUMBER])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for StringLengthOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("string-length expects one argument: string");
+        let s = args.first().and_then(Str::from_atom).ok_or_else(arg_error)?;
+        Ok(vec![Atom::gnd(Number::Integer(s.as_str().chars().count() as i64))])
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct StringConcatOp {}
+
+grounded_op!(StringConcatOp, "string-concat");
+
+impl Grounded for StringConcatOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_STRING, ATOM_TYPE_STRING, ATOM_TYPE_STRING])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for StringConcatOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("string-concat expects two arguments: string and string");
+        let a = args.first().and_then(Str::from_atom).ok_or_else(arg_error)?;
+        let b = args.get(1).and_then(Str::from_atom).ok_or_else(arg_error)?;
+        Ok(vec![str_atom(format!("{}{}", a.as_str(), b.as_str()))])
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct SubstringOp {}
+
+grounded_op!(SubstringOp, "substring");
+
+impl Grounded for SubstringOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_STRING, ATOM_TYPE_NUMBER, ATOM_TYPE_NUMBER, ATOM_TYPE_STRING])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for SubstringOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("substring expects three arguments: string, start index (integer) and end index (integer)");
+        let s = args.first().and_then(Str::from_atom).ok_or_else(arg_error)?;
+        let start = args.get(1).and_then(integer_arg).ok_or_else(arg_error)?;
+        let end = args.get(2).and_then(integer_arg).ok_or_else(arg_error)?;
+        let len = s.as_str().chars().count();
+        let start = char_index(start, len);
+        let end = char_index(end, len).max(start);
+        let res: String = s.as_str().chars().skip(start).take(end - start).collect();
+        Ok(vec![str_atom(res)])
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct StringSplitOp {}
+
+grounded_op!(StringSplitOp, "string-split");
+
+impl Grounded for StringSplitOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_STRING, ATOM_TYPE_STRING, ATOM_TYPE_EXPRESSION])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for StringSplitOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("string-split expects two arguments: string and separator (string)");
+        let s = args.first().and_then(Str::from_atom).ok_or_else(arg_error)?;
+        let sep = args.get(1).and_then(Str::from_atom).ok_or_else(arg_error)?;
+        let parts: Vec<Atom> = match sep.as_str() {
+            "" => s.as_str().split_whitespace().map(|p| str_atom(p.into())).collect(),
+            sep => s.as_str().split(sep).map(|p| str_atom(p.into())).collect(),
+        };
+        Ok(vec![Atom::expr(parts)])
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct StringJoinOp {}
+
+grounded_op!(StringJoinOp, "string-join");
+
+impl Grounded for StringJoinOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_STRING, ATOM_TYPE_EXPRESSION, ATOM_TYPE_STRING])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for StringJoinOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("string-join expects two arguments: separator (string) and expression of strings");
+        let sep = args.first().and_then(Str::from_atom).ok_or_else(arg_error)?;
+        let parts = TryInto::<&ExpressionAtom>::try_into(args.get(1).ok_or_else(arg_error)?)?;
+        let parts: Vec<String> = parts.children().iter()
+            .map(atom_to_string)
+            .collect();
+        Ok(vec![str_atom(parts.join(sep.as_str()))])
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct StringTrimOp {}
+
+grounded_op!(StringTrimOp, "string-trim");
+
+impl Grounded for StringTrimOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_STRING, ATOM_TYPE_STRING])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for StringTrimOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("string-trim expects one argument: string");
+        let s = args.first().and_then(Str::from_atom).ok_or_else(arg_error)?;
+        Ok(vec![str_atom(s.as_str().trim().into())])
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct StringUpperOp {}
+
+grounded_op!(StringUpperOp, "string-upper");
+
+impl Grounded for StringUpperOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_STRING, ATOM_TYPE_STRING])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for StringUpperOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("string-upper expects one argument: string");
+        let s = args.first().and_then(Str::from_atom).ok_or_else(arg_error)?;
+        Ok(vec![str_atom(s.as_str().to_uppercase())])
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct StringLowerOp {}
+
+grounded_op!(StringLowerOp, "string-lower");
+
+impl Grounded for StringLowerOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_STRING, ATOM_TYPE_STRING])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for StringLowerOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("string-lower expects one argument: string");
+        let s = args.first().and_then(Str::from_atom).ok_or_else(arg_error)?;
+        Ok(vec![str_atom(s.as_str().to_lowercase())])
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct StringStartsWithOp {}
+
+grounded_op!(StringStartsWithOp, "string-starts-with");
+
+impl Grounded for StringStartsWithOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_STRING, ATOM_TYPE_STRING, ATOM_TYPE_BOOL])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for StringStartsWithOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("string-starts-with expects two arguments: string and prefix (string)");
+        let s = args.first().and_then(Str::from_atom).ok_or_else(arg_error)?;
+        let prefix = args.get(1).and_then(Str::from_atom).ok_or_else(arg_error)?;
+        Ok(vec![Atom::gnd(Bool(s.as_str().starts_with(prefix.as_str())))])
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct StringEndsWithOp {}
+
+grounded_op!(StringEndsWithOp, "string-ends-with");
+
+impl Grounded for StringEndsWithOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_STRING, ATOM_TYPE_STRING, ATOM_TYPE_BOOL])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for StringEndsWithOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("string-ends-with expects two arguments: string and suffix (string)");
+        let s = args.first().and_then(Str::from_atom).ok_or_else(arg_error)?;
+        let suffix = args.get(1).and_then(Str::from_atom).ok_or_else(arg_error)?;
+        Ok(vec![Atom::gnd(Bool(s.as_str().ends_with(suffix.as_str())))])
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct StringContainsOp {}
+
+grounded_op!(StringContainsOp, "string-contains");
+
+impl Grounded for StringContainsOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_STRING, ATOM_TYPE_STRING, ATOM_TYPE_BOOL])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for StringContainsOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("string-contains expects two arguments: string and substring (string)");
+        let s = args.first().and_then(Str::from_atom).ok_or_else(arg_error)?;
+        let sub = args.get(1).and_then(Str::from_atom).ok_or_else(arg_error)?;
+        Ok(vec![Atom::gnd(Bool(s.as_str().contains(sub.as_str())))])
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct StringIndexOfOp {}
+
+grounded_op!(StringIndexOfOp, "string-index-of");
+
+impl Grounded for StringIndexOfOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_STRING, ATOM_TYPE_STRING, ATOM_TYPE_NUMBER])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for StringIndexOfOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("string-index-of expects two arguments: string and substring (string)");
+        let s = args.first().and_then(Str::from_atom).ok_or_else(arg_error)?;
+        let sub = args.get(1).and_then(Str::from_atom).ok_or_else(arg_error)?;
+        let index = s.as_str().find(sub.as_str())
+            .map_or(-1, |byte_idx| s.as_str()[..byte_idx].chars().count() as i64);
+        Ok(vec![Atom::gnd(Number::Integer(index))])
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct StringReplaceOp {}
+
+grounded_op!(StringReplaceOp, "string-replace");
+
+impl Grounded for StringReplaceOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_STRING, ATOM_TYPE_STRING, ATOM_TYPE_STRING, ATOM_TYPE_STRING])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for StringReplaceOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("string-replace expects three arguments: string, pattern (string) and replacement (string)");
+        let s = args.first().and_then(Str::from_atom).ok_or_else(arg_error)?;
+        let from = args.get(1).and_then(Str::from_atom).ok_or_else(arg_error)?;
+        let to = args.get(2).and_then(Str::from_atom).ok_or_else(arg_error)?;
+        Ok(vec![str_atom(s.as_str().replace(from.as_str(), to.as_str()))])
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct StringToNumberOp {}
+
+grounded_op!(StringToNumberOp, "string->number");
+
+impl Grounded for StringToNumberOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_STRING, ATOM_TYPE_NUMBER])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for StringToNumberOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("string->number expects one argument: string");
+        let s = args.first().and_then(Str::from_atom).ok_or_else(arg_error)?;
+        let num = Number::from_int_str(s.as_str())
+            .or_else(|_| Number::from_float_str(s.as_str()))
+            .map_err(|_| ExecError::from(format!("Could not parse number: {}", s)))?;
+        Ok(vec![Atom::gnd(num)])
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct NumberToStringOp {}
+
+grounded_op!(NumberToStringOp, "number->string");
+
+impl Grounded for NumberToStringOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_NUMBER, ATOM_TYPE_STRING])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for NumberToStringOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("number->string expects one argument: number");
+        let num = args.first().and_then(Number::from_atom).ok_or_else(arg_error)?;
+        Ok(vec![str_atom(num.to_string())])
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct StringToSymbolOp {}
+
+grounded_op!(StringToSymbolOp, "string->symbol");
+
+impl Grounded for StringToSymbolOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_STRING, ATOM_TYPE_UNDEFINED])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for StringToSymbolOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("string->symbol expects one argument: string");
+        let s = args.first().and_then(Str::from_atom).ok_or_else(arg_error)?;
+        Ok(vec![Atom::sym(s.as_str())])
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct SymbolToStringOp {}
+
+grounded_op!(SymbolToStringOp, "symbol->string");
+
+impl Grounded for SymbolToStringOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_UNDEFINED, ATOM_TYPE_STRING])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for SymbolToStringOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("symbol->string expects one argument: symbol");
+        match args.first() {
+            Some(Atom::Symbol(sym)) => Ok(vec![str_atom(sym.name().into())]),
+            _ => Err(arg_error()),
+        }
+    }
+}
+
+pub const ATOM_TYPE_REGEX : Atom = sym!("Regex");
+
+/// Compiled regular expression. It is returned by `regex` operation and can
+/// be passed to the `regex-*` operations instead of the pattern string to
+/// not compile the same pattern on each call.
+#[derive(Clone, Debug)]
+pub struct RegexPattern(regex::Regex);
+
+impl RegexPattern {
+    pub fn new(pattern: &str) -> Result<Self, ExecError> {
+        regex::Regex::new(pattern)
+            .map(RegexPattern)
+            .map_err(|e| ExecError::from(format!("Invalid regular expression: {}", e)))
+    }
+
+    pub fn as_regex(&self) -> &regex::Regex {
+        &self.0
+    }
+
+}
+
+/// Cache of compiled regular expressions keyed by the pattern string. It is
+/// shared by the `regex-*` operations to not compile the same pattern passed
+/// as a string on each call. The cache is cleared when it is full.
+#[derive(Clone, Debug, Default)]
+pub struct RegexCache(Rc<RefCell<HashMap<String, RegexPattern>>>);
+
+impl RegexCache {
+    const CAPACITY: usize = 256;
+
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Returns number of patterns in the cache.
+    pub fn len(&self) -> usize {
+        self.0.borrow().len()
+    }
+
+    /// Returns true if cache doesn't contain patterns.
+    pub fn is_empty(&self) -> bool {
+        self.0.borrow().is_empty()
+    }
+
+    /// Returns compiled regular expression for the pattern compiling it
+    /// only if it is not in the cache yet.
+    pub fn get(&self, pattern: &str) -> Result<RegexPattern, ExecError> {
+        if let Some(regex) = self.0.borrow().get(pattern) {
+            return Ok(regex.clone())
+        }
+        let regex = RegexPattern::new(pattern)?;
+        let mut cache = self.0.borrow_mut();
+        if cache.len() >= Self::CAPACITY {
+            cache.clear();
+        }
+        cache.insert(pattern.into(), regex.clone());
+        Ok(regex)
+    }
+
+    /// Gets compiled regular expression from `Regex` atom or from the cache
+    /// using `String` atom as a pattern.
+    fn get_by_atom(&self, atom: &Atom) -> Option<Result<RegexPattern, ExecError>> {
+        match atom.as_gnd::<RegexPattern>() {
+            Some(regex) => Some(Ok(regex.clone())),
+            None => Str::from_atom(atom).map(|s| self.get(s.as_str())),
+        }
+    }
+}
+
+impl PartialEq for RegexPattern {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.as_str() == other.0.as_str()
+    }
+}
+
+impl Grounded for RegexPattern {
+    fn type_(&self) -> Atom {
+        ATOM_TYPE_REGEX
+    }
+}
+
+impl std::fmt::Display for RegexPattern {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "(regex {:?})", self.0.as_str())
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct RegexOp {
+    cache: RegexCache,
+}
+
+impl RegexOp {
+    pub fn new(cache: RegexCache) -> Self {
+        Self{ cache }
+    }
+}
+
+grounded_op!(RegexOp, "regex");
+
+impl Grounded for RegexOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_STRING, ATOM_TYPE_REGEX])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for RegexOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("regex expects one argument: pattern (string)");
+        let pattern = args.first().and_then(Str::from_atom).ok_or_else(arg_error)?;
+        Ok(vec![Atom::gnd(self.cache.get(pattern.as_str())?)])
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct RegexMatchOp {
+    cache: RegexCache,
+}
+
+impl RegexMatchOp {
+    pub fn new(cache: RegexCache) -> Self {
+        Self{ cache }
+    }
+}
+
+grounded_op!(RegexMatchOp, "regex-match");
+
+impl Grounded for RegexMatchOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_UNDEFINED, ATOM_TYPE_STRING, ATOM_TYPE_BOOL])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for RegexMatchOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("regex-match expects two arguments: pattern (regex or string) and string");
+        let regex = args.first().and_then(|arg| self.cache.get_by_atom(arg)).ok_or_else(arg_error)??;
+        let s = args.get(1).and_then(Str::from_atom).ok_or_else(arg_error)?;
+        Ok(vec![Atom::gnd(Bool(regex.as_regex().is_match(s.as_str())))])
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct RegexCapturesOp {
+    cache: RegexCache,
+}
+
+impl RegexCapturesOp {
+    pub fn new(cache: RegexCache) -> Self {
+        Self{ cache }
+    }
+}
+
+grounded_op!(RegexCapturesOp, "regex-captures");
+
+impl Grounded for RegexCapturesOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_UNDEFINED, ATOM_TYPE_STRING, ATOM_TYPE_EXPRESSION])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for RegexCapturesOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("regex-captures expects two arguments: pattern (regex or string) and string");
+        let regex = args.first().and_then(|arg| self.cache.get_by_atom(arg)).ok_or_else(arg_error)??;
+        let s = args.get(1).and_then(Str::from_atom).ok_or_else(arg_error)?;
+        let matches = regex.as_regex().captures_iter(s.as_str())
+            .map(|captures| {
+                let groups: Vec<Atom> = captures.iter()
+                    .map(|group| group.map_or(UNIT_ATOM, |m| str_atom(m.as_str().into())))
+                    .collect();
+                Atom::expr(groups)
+            }).collect();
+        Ok(matches)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct RegexReplaceOp {
+    cache: RegexCache,
+}
+
+impl RegexReplaceOp {
+    pub fn new(cache: RegexCache) -> Self {
+        Self{ cache }
+    }
+}
+
+grounded_op!(RegexReplaceOp, "regex-replace");
+
+impl Grounded for RegexReplaceOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_UNDEFINED, ATOM_TYPE_STRING, ATOM_TYPE_STRING, ATOM_TYPE_STRING])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for RegexReplaceOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("regex-replace expects three arguments: pattern (regex or string), string and replacement (string)");
+        let regex = args.first().and_then(|arg| self.cache.get_by_atom(arg)).ok_or_else(arg_error)??;
+        let s = args.get(1).and_then(Str::from_atom).ok_or_else(arg_error)?;
+        let replacement = args.get(2).and_then(Str::from_atom).ok_or_else(arg_error)?;
+        let res = regex.as_regex().replace_all(s.as_str(), replacement.as_str());
+        Ok(vec![str_atom(res.into_owned())])
+    }
+}
+
+pub fn register_common_tokens(tref: &mut Tokenizer) {
+    let string_length_op = Atom::gnd(StringLengthOp{});
+    tref.register_token(regex(r"string-length"), move |_| { string_length_op.clone() });
+    let string_concat_op = Atom::gnd(StringConcatOp{});
+    tref.register_token(regex(r"string-concat"), move |_| { string_concat_op.clone() });
+    let substring_op = Atom::gnd(SubstringOp{});
+    tref.register_token(regex(r"substring"), move |_| { substring_op.clone() });
+    let string_split_op = Atom::gnd(StringSplitOp{});
+    tref.register_token(regex(r"string-split"), move |_| { string_split_op.clone() });
+    let string_join_op = Atom::gnd(StringJoinOp{});
+    tref.register_token(regex(r"string-join"), move |_| { string_join_op.clone() });
+    let string_trim_op = Atom::gnd(StringTrimOp{});
+    tref.register_token(regex(r"string-trim"), move |_| { string_trim_op.clone() });
+    let string_upper_op = Atom::gnd(StringUpperOp{});
+    tref.register_token(regex(r"string-upper"), move |_| { string_upper_op.clone() });
+    let string_lower_op = Atom::gnd(StringLowerOp{});
+    tref.register_token(regex(r"string-lower"), move |_| { string_lower_op.clone() });
+    let string_starts_with_op = Atom::gnd(StringStartsWithOp{});
+    tref.register_token(regex(r"string-starts-with"), move |_| { string_starts_with_op.clone() });
+    let string_ends_with_op = Atom::gnd(StringEndsWithOp{});
+    tref.register_token(regex(r"string-ends-with"), move |_| { string_ends_with_op.clone() });
+    let string_contains_op = Atom::gnd(StringContainsOp{});
+    tref.register_token(regex(r"string-contains"), move |_| { string_contains_op.clone() });
+    let string_index_of_op = Atom::gnd(StringIndexOfOp{});
+    tref.register_token(regex(r"string-index-of"), move |_| { string_index_of_op.clone() });
+    let string_replace_op = Atom::gnd(StringReplaceOp{});
+    tref.register_token(regex(r"string-replace"), move |_| { string_replace_op.clone() });
+    let string_to_number_op = Atom::gnd(StringToNumberOp{});
+    tref.register_token(regex(r"string->number"), move |_| { string_to_number_op.clone() });
+    let number_to_string_op = Atom::gnd(NumberToStringOp{});
+    tref.register_token(regex(r"number->string"), move |_| { number_to_string_op.clone() });
+    let string_to_symbol_op = Atom::gnd(StringToSymbolOp{});
+    tref.register_token(regex(r"string->symbol"), move |_| { string_to_symbol_op.clone() });
+    let symbol_to_string_op = Atom::gnd(SymbolToStringOp{});
+    tref.register_token(regex(r"symbol->string"), move |_| { symbol_to_string_op.clone() });
+    let regex_cache = RegexCache::new();
+    let regex_op = Atom::gnd(RegexOp::new(regex_cache.clone()));
+    tref.register_token(regex(r"regex"), move |_| { regex_op.clone() });
+    let regex_match_op = Atom::gnd(RegexMatchOp::new(regex_cache.clone()));
+    tref.register_token(regex(r"regex-match"), move |_| { regex_match_op.clone() });
+    let regex_captures_op = Atom::gnd(RegexCapturesOp::new(regex_cache.clone()));
+    tref.register_token(regex(r"regex-captures"), move |_| { regex_captures_op.clone() });
+    let regex_replace_op = Atom::gnd(RegexReplaceOp::new(regex_cache));
+    tref.register_token(regex(r"regex-replace"), move |_| { regex_replace_op.clone() });
+}
+
+pub fn register_runner_tokens(tref: &mut Tokenizer, metta: &Metta) {
+    let println_op = Atom::gnd(PrintlnOp::new(metta.clone()));
     tref.register_token(regex(r"println!"), move |_| { println_op.clone() });
+    let read_line_op = Atom::gnd(ReadLineOp::new(metta.clone()));
+    tref.register_token(regex(r"read-line"), move |_| { read_line_op.clone() });
+    let read_all_op = Atom::gnd(ReadAllOp::new(metta.clone()));
+    tref.register_token(regex(r"read-all"), move |_| { read_all_op.clone() });
     let format_args_op = Atom::gnd(FormatArgsOp{});
     tref.register_token(regex(r"format-args"), move |_| { format_args_op.clone() });
 }
@@ -75,9 +879,136 @@ pub fn register_rust_stdlib_tokens(tref: &mut Tokenizer) {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::metta::runner::stdlib::tests::run_program;
+    use crate::metta::runner::EnvBuilder;
+    use crate::metta::runner::output::BufferOutputSink;
+    use crate::metta::runner::input::ScriptedInputSource;
+    use crate::metta::text::SExprParser;
+    use std::sync::Arc;
+
+    fn str(s: &str) -> Atom {
+        Atom::gnd(Str::from_string(s.into()))
+    }
 
     #[test]
     fn println_op() {
-        assert_eq!(PrintlnOp{}.execute(&mut vec![sym!("A")]), unit_result());
+        let metta = Metta::new(Some(EnvBuilder::test_env()));
+        let output = Arc::new(BufferOutputSink::new());
+        metta.set_output_sink(output.clone());
+        assert_eq!(PrintlnOp::new(metta).execute(&mut vec![sym!("A")]), unit_result());
+        assert_eq!(output.text(OutputStream::Stdout), "A\n");
+    }
+
+    fn run_with_input(input: &str, program: &str) -> Result<Vec<Vec<Atom>>, String> {
+        let metta = Metta::new(Some(EnvBuilder::test_env()
+            .set_input_source(Arc::new(ScriptedInputSource::new(input)))));
+        metta.run(SExprParser::new(program))
+    }
+
+    #[test]
+    fn metta_read_line_and_read_all() {
+        assert_eq!(run_with_input("Alice\nBob\nCarol\n", r#"
+            !(read-line)
+            !(string-concat "Hello, " (read-line))
+            !(read-all)
+            !(read-all)
+        "#), Ok(vec![vec![str("Alice")], vec![str("Hello, Bob")], vec![str("Carol\n")], vec![str("")]]));
+        assert_eq!(run_with_input("", "!(read-line)"),
+            Ok(vec![vec![expr!("Error" ({ReadLineOp::new(Metta::new(Some(EnvBuilder::test_env())))}) "EndOfInput")]]));
+    }
+
+    #[test]
+    fn metta_string_length_and_concat() {
+        assert_eq!(run_program("!(string-length \"\u{43f}\u{440}\u{438}\u{432}\u{435}\u{442}\")"), Ok(vec![vec![expr!({Number::Integer(6)})]]));
+        assert_eq!(run_program("!(string-concat \"ab\" \"cd\")"), Ok(vec![vec![str("abcd")]]));
+        assert_eq!(run_program("!(string-length A)"), Ok(vec![vec![expr!("Error" ({ StringLengthOp{} } "A") "string-length expects one argument: string")]]));
+    }
+
+    #[test]
+    fn metta_substring() {
+        assert_eq!(run_program("!(substring \"hello\" 1 3)"), Ok(vec![vec![str("el")]]));
+        assert_eq!(run_program("!(substring \"hello\" -3 10)"), Ok(vec![vec![str("llo")]]));
+        assert_eq!(run_program("!(substring \"hello\" 4 2)"), Ok(vec![vec![str("")]]));
+        assert_eq!(run_program("!(substring \"\u{442}\u{435}\u{441}\u{442}\" 1 3)"), Ok(vec![vec![str("\u{435}\u{441}")]]));
+    }
+
+    #[test]
+    fn metta_string_split_and_join() {
+        assert_eq!(run_program("!(string-split \"a,b,,c\" \",\")"), Ok(vec![vec![expr!({Str::from_str("a")} {Str::from_str("b")} {Str::from_str("")} {Str::from_str("c")})]]));
+        assert_eq!(run_program("!(string-split \" a  b \" \"\")"), Ok(vec![vec![expr!({Str::from_str("a")} {Str::from_str("b")})]]));
+        assert_eq!(run_program("!(string-join \", \" (\"a\" \"b\" \"c\"))"), Ok(vec![vec![str("a, b, c")]]));
+    }
+
+    #[test]
+    fn metta_string_case_and_trim() {
+        assert_eq!(run_program("!(string-trim \"  a b \")"), Ok(vec![vec![str("a b")]]));
+        assert_eq!(run_program("!(string-upper \"aBc\")"), Ok(vec![vec![str("ABC")]]));
+        assert_eq!(run_program("!(string-lower \"aBc\")"), Ok(vec![vec![str("abc")]]));
+    }
+
+    #[test]
+    fn metta_string_search() {
+        assert_eq!(run_program("!(string-starts-with \"hello\" \"he\")"), Ok(vec![vec![expr!({Bool(true)})]]));
+        assert_eq!(run_program("!(string-ends-with \"hello\" \"he\")"), Ok(vec![vec![expr!({Bool(false)})]]));
+        assert_eq!(run_program("!(string-contains \"hello\" \"ll\")"), Ok(vec![vec![expr!({Bool(true)})]]));
+        assert_eq!(run_program("!(string-index-of \"\u{442}\u{435}\u{441}\u{442}\" \"\u{441}\")"), Ok(vec![vec![expr!({Number::Integer(2)})]]));
+        assert_eq!(run_program("!(string-index-of \"hello\" \"x\")"), Ok(vec![vec![expr!({Number::Integer(-1)})]]));
+        assert_eq!(run_program("!(string-replace \"a-b-c\" \"-\" \"+\")"), Ok(vec![vec![str("a+b+c")]]));
+    }
+
+    #[test]
+    fn metta_string_conversions() {
+        assert_eq!(run_program("!(string->number \"42\")"), Ok(vec![vec![expr!({Number::Integer(42)})]]));
+        assert_eq!(run_program("!(string->number \"4.5\")"), Ok(vec![vec![expr!({Number::Float(4.5)})]]));
+        assert_eq!(run_program("!(string->number \"abc\")"), Ok(vec![vec![expr!("Error" ({ StringToNumberOp{} } {Str::from_str("abc")}) "Could not parse number: \"abc\"")]]));
+        assert_eq!(run_program("!(number->string 42)"), Ok(vec![vec![str("42")]]));
+        assert_eq!(run_program("!(string->symbol \"foo\")"), Ok(vec![vec![expr!("foo")]]));
+        assert_eq!(run_program("!(symbol->string foo)"), Ok(vec![vec![str("foo")]]));
+    }
+
+    #[test]
+    fn metta_regex_match() {
+        assert_eq!(run_program("!(regex-match \"^a+b$\" \"aaab\")"), Ok(vec![vec![expr!({Bool(true)})]]));
+        assert_eq!(run_program("!(regex-match (regex \"^a+b$\") \"aaba\")"), Ok(vec![vec![expr!({Bool(false)})]]));
+        let error = run_program("!(regex-match \"(\" \"a\")").unwrap().remove(0).remove(0);
+        assert_eq!(<&[Atom]>::try_from(&error).unwrap()[0], sym!("Error"));
+    }
+
+    #[test]
+    fn metta_regex_captures() {
+        assert_eq!(run_program("!(regex-captures \"(\\\\w)=(\\\\d)?\" \"a=1 b=\")"), Ok(vec![vec![
+            expr!({Str::from_str("a=1")} {Str::from_str("a")} {Str::from_str("1")}),
+            expr!({Str::from_str("b=")} {Str::from_str("b")} ()),
+        ]]));
+        assert_eq!(run_program("!(regex-captures \"x\" \"abc\")"), Ok(vec![vec![]]));
+    }
+
+    #[test]
+    fn metta_regex_replace() {
+        assert_eq!(run_program("!(let $r (regex \"(\\\\w+)@(\\\\w+)\") (regex-replace $r \"me@host you@box\" \"$2:$1\"))"),
+            Ok(vec![vec![str("host:me box:you")]]));
+    }
+
+    #[test]
+    fn regex_op_returns_compiled_pattern() {
+        let regex = RegexOp::new(RegexCache::new()).execute(&[str("a+")]).unwrap().remove(0);
+        assert_eq!(regex, Atom::gnd(RegexPattern::new("a+").unwrap()));
+        assert_eq!(RegexMatchOp::new(RegexCache::new()).execute(&[regex, str("baa")]), Ok(vec![Atom::gnd(Bool(true))]));
+        assert!(RegexOp::new(RegexCache::new()).execute(&[str("(")]).is_err());
+    }
+
+    #[test]
+    fn regex_string_pattern_is_compiled_once() {
+        let cache = RegexCache::new();
+        let regex_match = RegexMatchOp::new(cache.clone());
+        let regex_replace = RegexReplaceOp::new(cache.clone());
+
+        assert_eq!(regex_match.execute(&[str("a+"), str("baa")]), Ok(vec![Atom::gnd(Bool(true))]));
+        assert_eq!(regex_match.execute(&[str("a+"), str("bb")]), Ok(vec![Atom::gnd(Bool(false))]));
+        assert_eq!(regex_replace.execute(&[str("a+"), str("baa"), str("c")]), Ok(vec![str("bc")]));
+        assert_eq!(cache.len(), 1);
+
+        assert!(regex_match.execute(&[str("("), str("a")]).is_err());
+        assert_eq!(cache.len(), 1);
     }
 }
@@ -0,0 +1,116 @@
+use crate::*;
+use crate::metta::*;
+use crate::metta::text::Tokenizer;
+use crate::metta::runner::Metta;
+use crate::metta::runner::str::*;
+use super::{grounded_op, regex};
+
+fn args_atom(metta: &Metta) -> Atom {
+    Atom::expr(metta.environment().args().iter()
+        .map(|arg| Atom::gnd(Str::from_string(arg.clone())))
+        .collect::<Vec<_>>())
+}
+
+#[derive(Clone, Debug)]
+pub struct GetArgsOp {
+    metta: Metta,
+}
+
+grounded_op!(GetArgsOp, "get-args");
+
+impl GetArgsOp {
+    pub fn new(metta: Metta) -> Self {
+        Self{ metta }
+    }
+}
+
+impl Grounded for GetArgsOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_EXPRESSION])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for GetArgsOp {
+    fn execute(&self, _args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        Ok(vec![args_atom(&self.metta)])
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct GetEnvOp {}
+
+grounded_op!(GetEnvOp, "get-env");
+
+impl Grounded for GetEnvOp {
+    fn type_(&self) -> Atom {
+        Atom::expr([ARROW_SYMBOL, ATOM_TYPE_STRING, ATOM_TYPE_STRING])
+    }
+
+    fn as_execute(&self) -> Option<&dyn CustomExecute> {
+        Some(self)
+    }
+}
+
+impl CustomExecute for GetEnvOp {
+    fn execute(&self, args: &[Atom]) -> Result<Vec<Atom>, ExecError> {
+        let arg_error = || ExecError::from("get-env expects one argument: string (variable name)");
+        let name = args.first().and_then(Str::from_atom).ok_or_else(arg_error)?;
+        match std::env::var(name.as_str()) {
+            Ok(value) => Ok(vec![Atom::gnd(Str::from_string(value))]),
+            Err(std::env::VarError::NotPresent) => Ok(vec![]),
+            Err(err) => Err(format!("Cannot read {}: {}", name.as_str(), err).into()),
+        }
+    }
+}
+
+pub fn register_common_tokens(tref: &mut Tokenizer, metta: &Metta) {
+    let get_args_op = Atom::gnd(GetArgsOp::new(metta.clone()));
+    tref.register_token(regex(r"get-args"), move |_| { get_args_op.clone() });
+    let get_env_op = Atom::gnd(GetEnvOp{});
+    tref.register_token(regex(r"get-env"), move |_| { get_env_op.clone() });
+    let args = args_atom(metta);
+    tref.register_token(regex(r"&args"), move |_| { args.clone() });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metta::runner::{EnvBuilder, SandboxProfile};
+    use crate::metta::text::SExprParser;
+
+    fn str_atom(s: &str) -> Atom {
+        Atom::gnd(Str::from_string(s.into()))
+    }
+
+    #[test]
+    fn args_are_passed_to_program() {
+        let metta = Metta::new(Some(EnvBuilder::test_env().set_args(["a", "--flag"])));
+        let result = metta.run(SExprParser::new("!(get-args) ! &args"));
+        let args = Atom::expr([str_atom("a"), str_atom("--flag")]);
+        assert_eq!(result, Ok(vec![vec![args.clone()], vec![args]]));
+
+        let metta = Metta::new(Some(EnvBuilder::test_env()));
+        assert_eq!(metta.run(SExprParser::new("!(get-args)")), Ok(vec![vec![Atom::expr([])]]));
+    }
+
+    #[test]
+    fn get_env_op() {
+        //PATH is read instead of setting a variable, because the environment is shared by
+        // the tests running in parallel
+        let path = std::env::var("PATH").expect("PATH is expected to be set");
+        let op = GetEnvOp{};
+        assert_eq!(op.execute(&[str_atom("PATH")]), Ok(vec![str_atom(&path)]));
+        assert_eq!(op.execute(&[str_atom("HYPERON_GET_ENV_TEST_NOT_SET")]), Ok(vec![]));
+    }
+
+    #[test]
+    fn get_env_is_denied_by_untrusted_profile() {
+        let metta = Metta::new(Some(EnvBuilder::test_env().set_sandbox(SandboxProfile::untrusted())));
+        let result = metta.run(SExprParser::new("!(get-env \"HOME\")")).unwrap();
+        assert_eq!(result[0][0].to_string(), "(Error (get-env \"HOME\") get-env is denied by the sandbox profile)");
+    }
+}
@@ -4,7 +4,6 @@ use crate::*;
 
 use core::ops::Range;
 use std::iter::Peekable;
-use std::iter::Enumerate;
 use regex::Regex;
 use std::rc::Rc;
 use unicode_reader::CodePoints;
@@ -30,6 +29,26 @@ impl std::fmt::Debug for TokenDescr {
 
 type AtomConstr = dyn Fn(&str) -> Result<Atom, String>;
 
+/// Identifies the Tokenizer entry by its regex and constructor function pointer, see
+/// [Tokenizer::remove_imported]
+#[derive(Clone)]
+pub(crate) struct TokenKey {
+    regex: String,
+    constr: Rc<AtomConstr>,
+}
+
+impl TokenKey {
+    pub(crate) fn new(regex: String, constr: Rc<AtomConstr>) -> Self {
+        Self{ regex, constr }
+    }
+}
+
+impl std::fmt::Debug for TokenKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "TokenKey{{ regex: {:?}, constr: {:?} }}", self.regex, Rc::as_ptr(&self.constr))
+    }
+}
+
 impl Tokenizer {
 
     pub fn new() -> Self {
@@ -80,6 +99,27 @@ impl Tokenizer {
         self.tokens.push(TokenDescr{ regex, constr: constr })
     }
 
+    /// Returns the keys of all entries, in order to remove them by [Tokenizer::remove_imported]
+    /// after they are copied into another Tokenizer
+    pub(crate) fn token_keys(&self) -> Vec<TokenKey> {
+        self.tokens.iter()
+            .map(|descr| TokenKey::new(descr.regex.as_str().to_string(), descr.constr.clone()))
+            .collect()
+    }
+
+    /// Removes one entry for each key of `imported`, which was previously copied into `self`.
+    /// Entries are matched by their regex and the constructor function pointer
+    pub(crate) fn remove_imported(&mut self, imported: &[TokenKey]) {
+        for key in imported.iter() {
+            let found = self.tokens.iter().position(|token| {
+                token.regex.as_str() == key.regex && Rc::ptr_eq(&token.constr, &key.constr)
+            });
+            if let Some(idx) = found {
+                self.tokens.remove(idx);
+            }
+        }
+    }
+
     /// Returns the constructor function associated with an exact regex string, or None if the Tokenizer
     /// does not contain the specified regex
     pub(crate) fn find_exact(&self, regex_str: &str) -> Option<Rc<AtomConstr>> {
@@ -323,14 +363,34 @@ impl<'a> From<&'a String> for CharReader<std::iter::Map<std::str::Chars<'a>, fn(
 /// NOTE: The SExprParser type is short-lived, and can be created cheaply to evaluate a specific block
 /// of MeTTa source code.
 pub struct SExprParser<R: Iterator<Item=io::Result<char>>> {
-    it: Peekable<Enumerate<CharReader<R>>>,
-    last_idx: usize,
+    it: Peekable<ByteOffsets<CharReader<R>>>,
+    end_idx: usize,
+    exprs: ExpressionTable,
+}
+
+/// Returns each char read together with its byte offset in the input, thus
+/// source ranges of the syntax nodes can be used to slice the source text
+struct ByteOffsets<I> {
+    it: I,
+    offset: usize,
+}
+
+impl<I: Iterator<Item=io::Result<char>>> Iterator for ByteOffsets<I> {
+    type Item = (usize, io::Result<char>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.it.next()?;
+        let offset = self.offset;
+        self.offset += item.as_ref().map_or(1, |c| c.len_utf8());
+        Some((offset, item))
+    }
 }
 
 impl<R: Iterator<Item=io::Result<char>>> SExprParser<R> {
 
     pub fn new<I: Into<CharReader<R>>>(chars: I) -> Self {
-        Self{ it: chars.into().enumerate().peekable(), last_idx: 0 }
+        let it = ByteOffsets{ it: chars.into(), offset: 0 }.peekable();
+        Self{ it, end_idx: 0, exprs: ExpressionTable::new() }
     }
 
     pub fn parse(&mut self, tokenizer: &Tokenizer) -> Result<Option<Atom>, String> {
@@ -338,7 +398,7 @@ impl<R: Iterator<Item=io::Result<char>>> SExprParser<R> {
             match self.parse_to_syntax_tree()? {
                 Some(node) => {
                     if let Some(atom) = node.as_atom(tokenizer)? {
-                        return Ok(Some(atom))
+                        return Ok(Some(self.exprs.share(atom)))
                     }
                 },
                 None => {
@@ -359,12 +419,12 @@ impl<R: Iterator<Item=io::Result<char>>> SExprParser<R> {
     fn next(&mut self) -> Result<Option<(usize, char)>, String> {
         match self.it.next() {
             Some((idx, Ok(c))) => {
-                self.last_idx = idx;
+                self.end_idx = idx + c.len_utf8();
                 Ok(Some((idx, c)))
             },
             None => Ok(None),
             Some((idx, Err(err))) => {
-                self.last_idx = idx;
+                self.end_idx = idx + 1;
                 Err(format!("Input read error at position {}: {}", idx, err))
             },
         }
@@ -372,7 +432,7 @@ impl<R: Iterator<Item=io::Result<char>>> SExprParser<R> {
 
     fn skip_next(&mut self) {
         match self.it.next() {
-            Some((idx, _)) => self.last_idx = idx,
+            Some((idx, c)) => self.end_idx = idx + c.map_or(1, |c| c.len_utf8()),
             _ => {},
         }
     }
@@ -384,7 +444,7 @@ impl<R: Iterator<Item=io::Result<char>>> SExprParser<R> {
                     return self.parse_comment();
                 },
                 _ if c.is_whitespace() => {
-                    let whispace_node = SyntaxNode::new(SyntaxNodeType::Whitespace, idx..idx+1, vec![]);
+                    let whispace_node = SyntaxNode::new(SyntaxNodeType::Whitespace, idx..idx+c.len_utf8(), vec![]);
                     self.skip_next();
                     return Ok(Some(whispace_node));
                 },
@@ -414,7 +474,7 @@ impl<R: Iterator<Item=io::Result<char>>> SExprParser<R> {
         if let Some(&(idx, _)) = self.it.peek() {
             idx
         } else {
-            self.last_idx + 1
+            self.end_idx
         }
     }
 
@@ -455,7 +515,7 @@ impl<R: Iterator<Item=io::Result<char>>> SExprParser<R> {
                     child_nodes.push(comment_node);
                 },
                 _ if c.is_whitespace() => {
-                    let whitespace_node = SyntaxNode::new(SyntaxNodeType::Whitespace, idx..idx+1, vec![]);
+                    let whitespace_node = SyntaxNode::new(SyntaxNodeType::Whitespace, idx..idx+c.len_utf8(), vec![]);
                     child_nodes.push(whitespace_node);
                     self.skip_next();
                 },
@@ -743,6 +803,20 @@ mod tests {
         assert_eq!(Ok(Some((1, ';'))), parser.next());
     }
 
+    #[test]
+    fn test_src_range_is_byte_range() {
+        let text = "; ы\n(f \"строка\" $х)";
+        let mut parser = SExprParser::new(text);
+
+        let comment = parser.parse_to_syntax_tree().unwrap().unwrap();
+        assert_eq!("; ы", &text[comment.src_range]);
+        let _newline = parser.parse_to_syntax_tree().unwrap().unwrap();
+        let expr = parser.parse_to_syntax_tree().unwrap().unwrap();
+        assert_eq!("(f \"строка\" $х)", &text[expr.src_range.clone()]);
+        let leaves: Vec<&str> = expr.sub_nodes.iter().map(|node| &text[node.src_range.clone()]).collect();
+        assert_eq!(leaves, vec!["(", "f", " ", "\"строка\"", " ", "$х", ")"]);
+    }
+
     #[test]
     fn test_next_string_errors() {
         let mut parser = SExprParser::new("a");
@@ -17,12 +17,31 @@
 //!
 //! When atom has no type assigned by user it has type `%Undefined%`. The value
 //! of `%Undefined%` type can be matched with any type required.
+//!
+//! Subtyping is declared using `:<`, for example `(:< Cat Animal)` designates
+//! each value of type `Cat` has type `Animal` as well. Relation is transitive
+//! and it is taken into account when symbols, grounded values and results of
+//! function calls are type checked.
+//!
+//! Type class is declared with the signatures of its methods:
+//! `(@class (Semigroup $t) ((: <> (-> $t $t $t))))`. Each method gets the
+//! constrained type `(=> (Semigroup $t) (-> $t $t $t))`, which can also be
+//! declared for a function directly. Implementations of the methods for the
+//! specific types are declared by instances:
+//! `(@instance (Semigroup Vec) <> vec-append)`. Interpreter chooses the
+//! implementation using the types of the actual arguments and checks the
+//! instance provides all methods of the class. Grounded functions like `+`
+//! can also be extended this way.
 
 use super::*;
 use crate::atom::matcher::{Bindings, BindingsSet, apply_bindings_to_atom_move};
-use crate::space::Space;
+use crate::space::{Space, DynSpace, SpaceEvent, SpaceObserver, SpaceObserverRef};
+use crate::space::module::ModuleSpace;
 
 use std::fmt::{Display, Debug};
+use std::collections::HashMap;
+use std::cell::RefCell;
+use std::rc::Rc;
 use itertools::Itertools;
 
 fn typeof_query(atom: &Atom, typ: &Atom) -> Atom {
@@ -39,17 +58,24 @@ fn query_has_type(space: &dyn Space, sub_type: &Atom, super_type: &Atom) -> Bind
 
 fn query_super_types(space: &dyn Space, sub_type: &Atom) -> Vec<Atom> {
     // TODO: query should check that sub type is a type and not another typed symbol
+    if let Atom::Variable(_) = sub_type {
+        // unknown type would match any sub type declaration
+        return vec![];
+    }
     let var_x = VariableAtom::new("X").make_unique();
     let super_types = space.query(&isa_query(&sub_type, &Atom::Variable(var_x.clone())));
     let atom_x = Atom::Variable(var_x);
     super_types.into_iter().map(|bindings| { apply_bindings_to_atom_move(atom_x.clone(), &bindings) }).collect()
 }
 
+// Loops in the `:<` relation are possible (for instance `(:< A B) (:< B A)`),
+// thus each type is added only once which guarantees termination. The runner
+// rejects declarations which make a loop, see [find_declared_subtype_cycle].
 fn add_super_types(space: &dyn Space, sub_types: &mut Vec<Atom>, from: usize) {
     let mut types = Vec::new();
     sub_types.iter().skip(from).for_each(|typ| {
         for typ in query_super_types(space, typ) {
-            if !sub_types.contains(&typ) {
+            if !sub_types.contains(&typ) && !types.contains(&typ) {
                 types.push(typ);
             }
         }
@@ -61,6 +87,114 @@ fn add_super_types(space: &dyn Space, sub_types: &mut Vec<Atom>, from: usize) {
     }
 }
 
+/// Returns the passed `typ` followed by all of its super types. Super types
+/// are declared in the `space` using `(:< <sub-type> <super-type>)` atoms,
+/// the relation is transitive. Loops in the relation don't prevent termination,
+/// see [find_subtype_cycle].
+///
+/// # Examples
+///
+/// ```
+/// use hyperon::{expr, sym};
+/// use hyperon::metta::runner::*;
+/// use hyperon::metta::text::SExprParser;
+/// use hyperon::metta::types::get_super_types;
+///
+/// let metta = Metta::new(None);
+/// metta.run(SExprParser::new("(:< Cat Mammal) (:< Mammal Animal)")).unwrap();
+///
+/// assert_eq!(get_super_types(&metta.space(), &sym!("Cat")),
+///     vec![sym!("Cat"), sym!("Mammal"), sym!("Animal")]);
+/// ```
+pub fn get_super_types(space: &dyn Space, typ: &Atom) -> Vec<Atom> {
+    let mut types = vec![typ.clone()];
+    add_super_types(space, &mut types, 0);
+    types
+}
+
+/// Looks for a cycle in the `:<` relation which passes through `typ`. Returns
+/// the types of the cycle starting and ending with `typ`, or `None` if `typ`
+/// is not a super type of itself.
+///
+/// # Examples
+///
+/// ```
+/// use hyperon::{expr, sym};
+/// use hyperon::space::grounding::GroundingSpace;
+/// use hyperon::metta::types::find_subtype_cycle;
+///
+/// let mut space = GroundingSpace::new();
+/// space.add(expr!(":<" "A" "B"));
+/// space.add(expr!(":<" "B" "A"));
+/// space.add(expr!(":<" "C" "A"));
+///
+/// assert_eq!(find_subtype_cycle(&space, &sym!("A")), Some(vec![sym!("A"), sym!("B"), sym!("A")]));
+/// assert_eq!(find_subtype_cycle(&space, &sym!("C")), None);
+/// ```
+pub fn find_subtype_cycle(space: &dyn Space, typ: &Atom) -> Option<Vec<Atom>> {
+    find_subtype_path(space, typ, typ)
+}
+
+/// Checks whether adding the `(:< <sub-type> <super-type>)` declaration `atom`
+/// into the `space` makes a cycle in the `:<` relation. Returns the types of
+/// the cycle starting and ending with the sub type, or `None` if there is no
+/// cycle or `atom` is not a subtype declaration.
+///
+/// # Examples
+///
+/// ```
+/// use hyperon::{expr, sym};
+/// use hyperon::metta::runner::*;
+/// use hyperon::metta::text::SExprParser;
+/// use hyperon::metta::types::find_declared_subtype_cycle;
+///
+/// let metta = Metta::new(None);
+/// metta.run(SExprParser::new("(:< A B) (:< B C)")).unwrap();
+///
+/// assert_eq!(find_declared_subtype_cycle(&metta.space(), &expr!(":<" "C" "A")),
+///     Some(vec![sym!("C"), sym!("A"), sym!("B"), sym!("C")]));
+/// assert_eq!(find_declared_subtype_cycle(&metta.space(), &expr!(":<" "A" "C")), None);
+/// ```
+pub fn find_declared_subtype_cycle(space: &dyn Space, atom: &Atom) -> Option<Vec<Atom>> {
+    let (sub_type, super_type) = match atom {
+        Atom::Expression(expr) => match expr.children() {
+            [op, sub_type, super_type] if *op == SUB_TYPE_SYMBOL => (sub_type, super_type),
+            _ => return None,
+        },
+        _ => return None,
+    };
+    if sub_type == super_type {
+        return Some(vec![sub_type.clone(), super_type.clone()]);
+    }
+    let mut cycle = find_subtype_path(space, super_type, sub_type)?;
+    cycle.insert(0, sub_type.clone());
+    Some(cycle)
+}
+
+// Returns the chain of the `:<` declarations which leads from the `from` type
+// to the `to` type
+fn find_subtype_path(space: &dyn Space, from: &Atom, to: &Atom) -> Option<Vec<Atom>> {
+    fn find_path(space: &dyn Space, target: &Atom, path: &mut Vec<Atom>, visited: &mut Vec<Atom>) -> bool {
+        for super_type in query_super_types(space, path.last().unwrap()) {
+            if super_type == *target {
+                path.push(super_type);
+                return true;
+            }
+            if !visited.contains(&super_type) {
+                visited.push(super_type.clone());
+                path.push(super_type);
+                if find_path(space, target, path, visited) {
+                    return true;
+                }
+                path.pop();
+            }
+        }
+        false
+    }
+    let mut path = vec![from.clone()];
+    find_path(space, to, &mut path, &mut vec![from.clone()]).then_some(path)
+}
+
 fn check_arg_types(actual: &[Vec<AtomType>], meta: &[Vec<Atom>], expected: &[Atom]) -> BindingsSet {
     if actual.len() != expected.len() {
         BindingsSet::empty()
@@ -114,16 +248,162 @@ pub fn is_func(typ: &Atom) -> bool {
     match typ {
         Atom::Expression(expr) => {
             expr.children().first() == Some(&ARROW_SYMBOL)
+                || split_constrained_type(typ).is_some_and(|(_, typ)| is_func(typ))
         },
         _ => false,
     }
 }
 
+/// Splits constrained function type `(=> <constraint> <type>)` on the
+/// constraint and the function type. Returns `None` if type is not
+/// constrained.
+///
+/// Constraint is an application of a type class to the type variables of
+/// the function type, for example `(: <> (=> (Semigroup $t) (-> $t $t $t)))`
+/// designates `<>` is a method of the `Semigroup` class. Implementation of
+/// the method for the specific type is declared by the instance:
+/// `(@instance (Semigroup String) <> concat)`.
+///
+/// # Examples
+///
+/// ```
+/// use hyperon::expr;
+/// use hyperon::metta::types::split_constrained_type;
+///
+/// let typ = expr!("=>" ("Semigroup" t) ("->" t t t));
+/// assert_eq!(split_constrained_type(&typ), Some((&expr!("Semigroup" t), &expr!("->" t t t))));
+/// assert_eq!(split_constrained_type(&expr!("->" t t t)), None);
+/// ```
+pub fn split_constrained_type(typ: &Atom) -> Option<(&Atom, &Atom)> {
+    match typ {
+        Atom::Expression(expr) => match expr.children() {
+            [op, constraint, typ] if *op == CONSTRAINT_SYMBOL => Some((constraint, typ)),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Returns implementations of the type class method `op` from the instances
+/// which match the `constraint`.
+///
+/// # Examples
+///
+/// ```
+/// use hyperon::{expr, sym};
+/// use hyperon::metta::runner::*;
+/// use hyperon::metta::text::SExprParser;
+/// use hyperon::metta::types::get_instance_methods;
+///
+/// let metta = Metta::new(None);
+/// metta.run(SExprParser::new("(@instance (Semigroup String) <> concat)")).unwrap();
+///
+/// let space = metta.space();
+/// assert_eq!(get_instance_methods(&space, &expr!("Semigroup" "String"), &sym!("<>")), vec![sym!("concat")]);
+/// assert!(get_instance_methods(&space, &expr!("Semigroup" "Number"), &sym!("<>")).is_empty());
+/// ```
+pub fn get_instance_methods(space: &dyn Space, constraint: &Atom, op: &Atom) -> Vec<Atom> {
+    let var_x = VariableAtom::new("X").make_unique();
+    let query = Atom::expr([INSTANCE_SYMBOL, constraint.clone(), op.clone(), Atom::Variable(var_x.clone())]);
+    let atom_x = Atom::Variable(var_x);
+    space.query(&query).into_iter()
+        .map(|bindings| apply_bindings_to_atom_move(atom_x.clone(), &bindings))
+        .collect()
+}
+
+/// Type class declaration `(@class <constraint> ((: <method> <type>) ...))`
+struct TypeClass {
+    constraint: Atom,
+    methods: Vec<(Atom, Atom)>,
+}
+
+fn query_classes(space: &dyn Space) -> Vec<TypeClass> {
+    let constraint = Atom::Variable(VariableAtom::new("constraint").make_unique());
+    let methods = Atom::Variable(VariableAtom::new("methods").make_unique());
+    let query = Atom::expr([CLASS_SYMBOL, constraint.clone(), methods.clone()]);
+    space.query(&query).into_iter().filter_map(|bindings| {
+        let class = apply_bindings_to_atom_move(Atom::expr([constraint.clone(), methods.clone()]), &bindings);
+        let class = make_variables_unique(class);
+        match <&[Atom]>::try_from(&class).ok()? {
+            [constraint, Atom::Expression(decls)] => {
+                let methods = decls.children().iter()
+                    .map(|decl| match <&[Atom]>::try_from(decl).ok() {
+                        Some([op, method, typ]) if *op == HAS_TYPE_SYMBOL => Some((method.clone(), typ.clone())),
+                        _ => None,
+                    })
+                    .collect::<Option<Vec<_>>>();
+                match methods {
+                    Some(methods) => Some(TypeClass{ constraint: constraint.clone(), methods }),
+                    None => {
+                        log::warn!("Incorrect type class declaration: {}", class);
+                        None
+                    },
+                }
+            },
+            _ => None,
+        }
+    }).collect()
+}
+
+/// Returns the constrained types of the `op` declared by the type classes
+fn query_class_method_types(space: &dyn Space, op: &Atom) -> Vec<Atom> {
+    query_classes(space).into_iter()
+        .flat_map(|class| {
+            let constraint = class.constraint;
+            class.methods.into_iter()
+                .filter(|(method, _)| method == op)
+                .map(move |(_, typ)| Atom::expr([CONSTRAINT_SYMBOL, constraint.clone(), typ]))
+        })
+        .collect()
+}
+
+/// Checks that the instance of the type class provides all methods declared
+/// by the class, and each method implementation has the type of the method
+/// with the class parameters substituted. Instances of the classes which
+/// are not declared by `@class` are not checked.
+///
+/// # Examples
+///
+/// ```
+/// use hyperon::expr;
+/// use hyperon::metta::runner::*;
+/// use hyperon::metta::text::SExprParser;
+/// use hyperon::metta::types::check_instance;
+///
+/// let metta = Metta::new(None);
+/// metta.run(SExprParser::new("
+///     (@class (Ord $t) ((: lt (-> $t $t Bool)) (: gt (-> $t $t Bool))))
+///     (: num-lt (-> Number Number Bool))
+///     (@instance (Ord Number) lt num-lt)
+/// ")).unwrap();
+///
+/// let space = metta.space();
+/// assert_eq!(check_instance(&space, &expr!("Ord" "Number")),
+///     Err("(Ord Number) instance doesn't implement gt method".into()));
+/// ```
+pub fn check_instance(space: &dyn Space, constraint: &Atom) -> Result<(), String> {
+    for class in query_classes(space) {
+        for bindings in matcher::match_atoms(&class.constraint, constraint) {
+            for (method, typ) in class.methods.iter() {
+                let typ = apply_bindings_to_atom_move(typ.clone(), &bindings);
+                let impls = get_instance_methods(space, constraint, method);
+                if impls.is_empty() {
+                    return Err(format!("{} instance doesn't implement {} method", constraint, method));
+                }
+                if !impls.iter().any(|imp| check_type(space, imp, &typ)) {
+                    return Err(format!("{} method of {} instance should have type {}", method, constraint, typ));
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
 fn query_types(space: &dyn Space, atom: &Atom) -> Vec<Atom> {
     let var_x = VariableAtom::new("X").make_unique();
     let types = query_has_type(space, atom, &Atom::Variable(var_x.clone()));
     let atom_x = Atom::Variable(var_x);
-    let mut types = types.into_iter().filter_map(|bindings| {
+    let mut types: Vec<Atom> = types.into_iter().filter_map(|bindings| {
         let atom = apply_bindings_to_atom_move(atom_x.clone(), &bindings);
         if atom_x == atom {
             None
@@ -131,21 +411,32 @@ fn query_types(space: &dyn Space, atom: &Atom) -> Vec<Atom> {
             Some(atom)
         }
     }).collect();
+    // Methods of the type classes have no `:` declarations, thus classes are
+    // queried for the untyped symbols only
+    if types.is_empty() && matches!(atom, Atom::Symbol(_)) {
+        types.extend(query_class_method_types(space, atom));
+    }
     add_super_types(space, &mut types, 0);
     types
 }
 
 /// Splits function type on array of argument types and return type.
+/// Constraint of the constrained function type is skipped.
 fn get_arg_types<'a>(fn_typ: &'a AtomType) -> (&'a [Atom], &'a Atom) {
-    match fn_typ.as_atom() {
-        Atom::Expression(expr) => {
-            let children = expr.children();
-            match children {
-                [op,  args @ .., res] if *op == ARROW_SYMBOL => (args, res),
-                _ => panic!("Incorrect function type: {}", fn_typ)
-            }
+    split_func_type(fn_typ.as_atom())
+        .unwrap_or_else(|| panic!("Incorrect function type: {}", fn_typ))
+}
+
+fn split_func_type(fn_typ: &Atom) -> Option<(&[Atom], &Atom)> {
+    if let Some((_, typ)) = split_constrained_type(fn_typ) {
+        return split_func_type(typ);
+    }
+    match fn_typ {
+        Atom::Expression(expr) => match expr.children() {
+            [op,  args @ .., res] if *op == ARROW_SYMBOL => Some((args, res)),
+            _ => None,
         },
-        _ => panic!("Incorrect function type: {}", fn_typ)
+        _ => None,
     }
 }
 
@@ -311,7 +602,16 @@ pub fn get_atom_types_v2(space: &dyn Space, atom: &Atom) -> Vec<AtomType> {
         // and `(: + (-> Num Num Num))`then type checker can find that
         // `{ $r = $t = $tt = Num }`.
         Atom::Variable(_) => vec![],
-        Atom::Grounded(gnd) => vec![AtomType::value(make_variables_unique(gnd.type_()))],
+        Atom::Grounded(gnd) => {
+            let mut types = get_super_types(space, &make_variables_unique(gnd.type_()));
+            if gnd.as_grounded().as_execute().is_some() {
+                // Grounded function can be made a method of a type class
+                // by declaring additional constrained type for it
+                types.extend(query_types(space, atom).into_iter()
+                    .filter(|typ| split_constrained_type(typ).is_some()));
+            }
+            types.into_iter().map(AtomType::value).collect()
+        },
         Atom::Symbol(_) => query_types(space, atom).into_iter()
             .map(AtomType::value).collect(),
         // FIXME: incorrect type
@@ -319,7 +619,7 @@ pub fn get_atom_types_v2(space: &dyn Space, atom: &Atom) -> Vec<AtomType> {
         Atom::Expression(expr) => {
             let type_info = ExprTypeInfo::new(space, expr);
             let mut types = get_tuple_types(space, atom, &type_info);
-            let applications = get_application_types(atom, expr, type_info);
+            let applications = get_application_types(space, atom, expr, type_info);
             types.extend(applications.into_iter());
             types
         },
@@ -428,18 +728,31 @@ fn get_tuple_types(space: &dyn Space, atom: &Atom, type_info: &ExprTypeInfo) ->
 // This is a tricky logic. To simplify it we could  separate tuple and
 // function application using separate Atom types. Or use an embedded atom
 // to designate function application.
-fn get_application_types(atom: &Atom, expr: &ExpressionAtom, type_info: ExprTypeInfo) -> Vec<AtomType> {
+fn get_application_types(space: &dyn Space, atom: &Atom, expr: &ExpressionAtom, type_info: ExprTypeInfo) -> Vec<AtomType> {
     let args = get_args(expr);
     let meta_arg_types: Vec<Vec<Atom>> = args.iter().map(|a| vec![get_meta_type(a), ATOM_TYPE_ATOM]).collect();
     let mut types = Vec::with_capacity(type_info.op_func_types.len());
     for fn_type in type_info.op_func_types.into_iter() {
         let (expected_arg_types, ret_typ) = get_arg_types(&fn_type);
-        let correct = check_arg_types(&type_info.arg_types, meta_arg_types.as_slice(), expected_arg_types);
+        let mut correct = check_arg_types(&type_info.arg_types, meta_arg_types.as_slice(), expected_arg_types);
+        if let Some((constraint, _)) = split_constrained_type(fn_type.as_atom()) {
+            // Type class method is applicable only when instance is found
+            // or argument types are not known yet
+            let op = &expr.children()[0];
+            correct = correct.into_iter().filter(|bindings| {
+                let constraint = apply_bindings_to_atom_move(constraint.clone(), bindings);
+                constraint.iter().any(|a| matches!(a, Atom::Variable(_)))
+                    || !get_instance_methods(space, &constraint, op).is_empty()
+            }).collect();
+        }
         if correct.is_empty() {
             types.push(AtomType::application(fn_type.into_atom(), true));
         } else {
             for bindings in correct {
-                types.push(AtomType::application(apply_bindings_to_atom_move(ret_typ.clone(), &bindings), false));
+                let ret_typ = apply_bindings_to_atom_move(ret_typ.clone(), &bindings);
+                for typ in get_super_types(space, &ret_typ) {
+                    types.push(AtomType::application(typ, false));
+                }
             }
         }
     }
@@ -593,6 +906,485 @@ pub fn validate_atom(space: &dyn Space, atom: &Atom) -> bool {
     !get_atom_types(space, atom).is_empty()
 }
 
+const IF_SYMBOL : Atom = sym!("if");
+const LET_SYMBOL : Atom = sym!("let");
+const ATOM_TYPE_BOOL : Atom = sym!("Bool");
+
+fn is_meta_type(typ: &Atom) -> bool {
+    *typ == ATOM_TYPE_ATOM
+        || *typ == ATOM_TYPE_SYMBOL
+        || *typ == ATOM_TYPE_VARIABLE
+        || *typ == ATOM_TYPE_EXPRESSION
+        || *typ == ATOM_TYPE_GROUNDED
+}
+
+fn fresh_type_var() -> Atom {
+    Atom::Variable(VariableAtom::new("t").make_unique())
+}
+
+/// Replaces `%Undefined%` and meta-types by fresh type variables. Such types
+/// put no constraints on the inferred type.
+fn replace_unknown_types(mut typ: Atom) -> Atom {
+    typ.iter_mut().filter(|t| **t == ATOM_TYPE_UNDEFINED || is_meta_type(t))
+        .for_each(|t| *t = fresh_type_var());
+    typ
+}
+
+/// Renames type variables of the inferred type to `$t`, `$t1`, `$t2`, ...
+fn rename_type_vars(mut typ: Atom) -> Atom {
+    let mut names: Vec<VariableAtom> = Vec::new();
+    typ.iter_mut().filter_type::<&mut VariableAtom>().for_each(|var| {
+        let n = match names.iter().position(|v| v == var) {
+            Some(n) => n,
+            None => { names.push(var.clone()); names.len() - 1 },
+        };
+        *var = match n {
+            0 => VariableAtom::new("t"),
+            n => VariableAtom::new(format!("t{}", n)),
+        };
+    });
+    typ
+}
+
+/// Hindley-Milner style inference of the types of the functions which are
+/// defined by `=` atoms only. Types of the called functions are taken from
+/// their declarations, untyped callees are inferred recursively. Types
+/// inferred for the callees are generalized unless callee is mutually
+/// recursive with the function being inferred.
+struct TypeInference<'a> {
+    space: &'a dyn Space,
+    definitions: Rc<Definitions>,
+    bindings: Bindings,
+    in_progress: Vec<(SymbolAtom, Atom)>,
+    min_ref: usize,
+    inferred: HashMap<SymbolAtom, Option<Atom>>,
+}
+
+/// Definitions of the functions grouped by the function name
+type Definitions = HashMap<SymbolAtom, Vec<(Atom, Atom)>>;
+
+fn query_definitions(space: &dyn Space) -> Definitions {
+    let lhs = Atom::Variable(VariableAtom::new("lhs").make_unique());
+    let rhs = Atom::Variable(VariableAtom::new("rhs").make_unique());
+    let mut definitions: Definitions = HashMap::new();
+    for bindings in space.query(&Atom::expr([EQUAL_SYMBOL, lhs.clone(), rhs.clone()])) {
+        let lhs = apply_bindings_to_atom_move(lhs.clone(), &bindings);
+        let head = match &lhs {
+            Atom::Symbol(name) => Some(name.clone()),
+            Atom::Expression(expr) => match expr.children().first() {
+                Some(Atom::Symbol(name)) => Some(name.clone()),
+                _ => None,
+            },
+            _ => None,
+        };
+        if let Some(name) = head {
+            let rhs = apply_bindings_to_atom_move(rhs.clone(), &bindings);
+            definitions.entry(name).or_default().push((lhs, rhs));
+        }
+    }
+    definitions
+}
+
+impl<'a> TypeInference<'a> {
+    fn new(space: &'a dyn Space) -> Self {
+        Self::with_definitions(space, Rc::new(query_definitions(space)), HashMap::new())
+    }
+
+    fn with_definitions(space: &'a dyn Space, definitions: Rc<Definitions>, inferred: HashMap<SymbolAtom, Option<Atom>>) -> Self {
+        Self{ space, definitions, bindings: Bindings::new(), in_progress: Vec::new(),
+            min_ref: usize::MAX, inferred }
+    }
+
+    fn unify(&mut self, left: &Atom, right: &Atom) -> bool {
+        let left = apply_bindings_to_atom_move(left.clone(), &self.bindings);
+        let right = apply_bindings_to_atom_move(right.clone(), &self.bindings);
+        let unified = matcher::match_atoms(&left, &right)
+            .flat_map(|b| b.merge(&self.bindings))
+            .find(|b| !b.has_loops());
+        log::trace!("TypeInference::unify: {} ~ {}: {:?}", left, right, unified);
+        match unified {
+            Some(bindings) => {
+                self.bindings = bindings;
+                true
+            },
+            None => false,
+        }
+    }
+
+    fn infer_definitions(&mut self, name: &SymbolAtom) -> Option<Atom> {
+        if let Some(typ) = self.inferred.get(name) {
+            return typ.clone().map(make_variables_unique);
+        }
+        let definitions = self.definitions.get(name)?.clone();
+        let typ = fresh_type_var();
+        let index = self.in_progress.len();
+        self.in_progress.push((name.clone(), typ.clone()));
+        let outer_min_ref = std::mem::replace(&mut self.min_ref, usize::MAX);
+        let is_typed = definitions.iter()
+            .all(|(lhs, rhs)| self.infer_definition(&typ, lhs, rhs));
+        self.in_progress.pop();
+        let refers_outer = self.min_ref < index;
+        self.min_ref = self.min_ref.min(outer_min_ref);
+
+        let typ = match is_typed {
+            true => Some(apply_bindings_to_atom_move(typ, &self.bindings)),
+            false => None,
+        };
+        log::debug!("TypeInference::infer_definitions: {} inferred type: {:?}", name, typ);
+        if refers_outer {
+            typ
+        } else {
+            self.inferred.insert(name.clone(), typ.clone());
+            typ.map(make_variables_unique)
+        }
+    }
+
+    fn infer_definition(&mut self, typ: &Atom, lhs: &Atom, rhs: &Atom) -> bool {
+        let mut locals = HashMap::new();
+        let actual = match lhs {
+            Atom::Expression(expr) => {
+                let mut children = vec![ARROW_SYMBOL];
+                for arg in get_args(expr) {
+                    match self.infer(arg, &mut locals) {
+                        Some(arg_typ) => children.push(arg_typ),
+                        None => return false,
+                    }
+                }
+                match self.infer(rhs, &mut locals) {
+                    Some(ret_typ) => children.push(ret_typ),
+                    None => return false,
+                }
+                Atom::expr(children)
+            },
+            _ => match self.infer(rhs, &mut locals) {
+                Some(typ) => typ,
+                None => return false,
+            },
+        };
+        self.unify(typ, &actual)
+    }
+
+    fn infer(&mut self, atom: &Atom, locals: &mut HashMap<VariableAtom, Atom>) -> Option<Atom> {
+        match atom {
+            Atom::Variable(var) => Some(locals.entry(var.clone()).or_insert_with(fresh_type_var).clone()),
+            Atom::Grounded(gnd) => Some(replace_unknown_types(make_variables_unique(gnd.type_()))),
+            Atom::Symbol(_) => {
+                let mut types = self.op_types(atom);
+                match types.len() {
+                    1 => types.pop(),
+                    _ => Some(fresh_type_var()),
+                }
+            },
+            Atom::Expression(expr) if expr.children().is_empty() => Some(fresh_type_var()),
+            Atom::Expression(expr) => self.infer_application(expr, locals),
+        }
+    }
+
+    fn op_types(&mut self, op: &Atom) -> Vec<Atom> {
+        match op {
+            Atom::Symbol(name) => {
+                if let Some(index) = self.in_progress.iter().position(|(n, _)| n == name) {
+                    self.min_ref = self.min_ref.min(index);
+                    return vec![self.in_progress[index].1.clone()];
+                }
+                let declared = query_types(self.space, op);
+                if !declared.is_empty() {
+                    declared.into_iter().map(make_variables_unique).collect()
+                } else {
+                    self.infer_definitions(name).into_iter().collect()
+                }
+            },
+            Atom::Grounded(gnd) => vec![make_variables_unique(gnd.type_())],
+            _ => vec![],
+        }
+    }
+
+    fn infer_application(&mut self, expr: &ExpressionAtom, locals: &mut HashMap<VariableAtom, Atom>) -> Option<Atom> {
+        let (op, args) = expr.children().split_first().unwrap();
+        match args {
+            [cond, then, other] if *op == IF_SYMBOL => {
+                let cond_typ = self.infer(cond, locals)?;
+                let then_typ = self.infer(then, locals)?;
+                let else_typ = self.infer(other, locals)?;
+                return match self.unify(&cond_typ, &ATOM_TYPE_BOOL) && self.unify(&then_typ, &else_typ) {
+                    true => Some(then_typ),
+                    false => None,
+                };
+            },
+            [pattern, value, body] if *op == LET_SYMBOL => {
+                let pattern_typ = self.infer(pattern, locals)?;
+                let value_typ = self.infer(value, locals)?;
+                return match self.unify(&pattern_typ, &value_typ) {
+                    true => self.infer(body, locals),
+                    false => None,
+                };
+            },
+            _ => {},
+        }
+
+        let op_types = match op {
+            // Higher order function argument
+            Atom::Variable(_) => vec![self.infer(op, locals)?],
+            _ => self.op_types(op),
+        };
+        let fn_types: Vec<Atom> = op_types.into_iter()
+            .filter(|t| is_func(t) || matches!(t, Atom::Variable(_)))
+            .collect();
+        if fn_types.is_empty() {
+            // Unknown function or a tuple: collect constraints from the
+            // arguments but return unknown type.
+            for arg in args {
+                self.infer(arg, locals)?;
+            }
+            return Some(fresh_type_var());
+        }
+        for fn_type in fn_types {
+            let bindings = self.bindings.clone();
+            let saved_locals = locals.clone();
+            if let Some(ret_typ) = self.apply_function_type(fn_type, args, locals) {
+                return Some(ret_typ);
+            }
+            self.bindings = bindings;
+            *locals = saved_locals;
+        }
+        None
+    }
+
+    fn apply_function_type(&mut self, fn_type: Atom, args: &[Atom], locals: &mut HashMap<VariableAtom, Atom>) -> Option<Atom> {
+        if let Atom::Variable(_) = fn_type {
+            // Function which type is being inferred right now
+            let mut children = vec![ARROW_SYMBOL];
+            for arg in args {
+                children.push(self.infer(arg, locals)?);
+            }
+            let ret_typ = fresh_type_var();
+            children.push(ret_typ.clone());
+            return match self.unify(&fn_type, &Atom::expr(children)) {
+                true => Some(ret_typ),
+                false => None,
+            };
+        }
+        let fn_type = AtomType::value(fn_type);
+        let (arg_types, ret_typ) = get_arg_types(&fn_type);
+        if arg_types.len() != args.len() {
+            return None;
+        }
+        for (arg_typ, arg) in arg_types.iter().zip(args) {
+            // Arguments of meta-types are not evaluated, thus they put no
+            // constraints on the types of the variables inside
+            if is_meta_type(arg_typ) {
+                continue;
+            }
+            let actual = self.infer(arg, locals)?;
+            if !self.unify(&replace_unknown_types(arg_typ.clone()), &actual) {
+                return None;
+            }
+        }
+        Some(replace_unknown_types(ret_typ.clone()))
+    }
+}
+
+/// Infers the type of the function `op` which is defined by `=` atoms in the
+/// `space` but has no type declared. Returns `None` if `op` has declared type,
+/// has no definitions or definitions are not typed consistently. Type
+/// variables of the returned type are type parameters of the function.
+///
+/// # Examples
+///
+/// ```
+/// use hyperon::{expr, sym};
+/// use hyperon::metta::runner::*;
+/// use hyperon::metta::text::SExprParser;
+/// use hyperon::metta::types::infer_function_type;
+///
+/// let metta = Metta::new(None);
+/// metta.run(SExprParser::new("
+///     (: Z Nat)
+///     (: S (-> Nat Nat))
+///     (= (inc $n) (S $n))
+///     (= (twice $f $x) ($f ($f $x)))
+/// ")).unwrap();
+///
+/// let space = metta.space();
+/// assert_eq!(infer_function_type(&space, &sym!("inc")), Some(expr!("->" "Nat" "Nat")));
+/// assert_eq!(infer_function_type(&space, &sym!("twice")), Some(expr!("->" ("->" t t) t t)));
+/// assert_eq!(infer_function_type(&space, &sym!("S")), None);
+/// ```
+pub fn infer_function_type(space: &dyn Space, op: &Atom) -> Option<Atom> {
+    match op {
+        Atom::Symbol(name) if query_types(space, op).is_empty() =>
+            TypeInference::new(space).infer_definitions(name).map(rename_type_vars),
+        _ => None,
+    }
+}
+
+/// Caches the function definitions of a space and the types inferred from
+/// them, so repeated [InferredTypesCache::infer_function_type] calls don't
+/// query all `=` atoms of the space again. The cache is dropped when atoms of
+/// the space or of its dependencies are modified, or when dependencies of the
+/// [ModuleSpace] or of its dependencies are added or removed.
+///
+/// # Examples
+///
+/// ```
+/// use hyperon::{expr, sym};
+/// use hyperon::space::DynSpace;
+/// use hyperon::metta::text::SExprParser;
+/// use hyperon::metta::runner::*;
+/// use hyperon::metta::types::InferredTypesCache;
+///
+/// let metta = Metta::new(Some(EnvBuilder::test_env()));
+/// metta.run(SExprParser::new("
+///     (: S (-> Nat Nat))
+///     (= (inc $n) (S $n))
+/// ")).unwrap();
+///
+/// let space = metta.space();
+/// let cache = InferredTypesCache::new();
+/// assert_eq!(cache.infer_function_type(&space, &sym!("inc")), Some(expr!("->" "Nat" "Nat")));
+/// assert_eq!(cache.infer_function_type(&space, &sym!("A")), None);
+/// ```
+#[derive(Default)]
+pub struct InferredTypesCache {
+    state: RefCell<Option<CachedTypes>>,
+}
+
+struct CachedTypes {
+    space: DynSpace,
+    deps: Vec<DynSpace>,
+    observers: Vec<SpaceObserverRef<ModificationObserver>>,
+    definitions: Rc<Definitions>,
+    inferred: HashMap<SymbolAtom, Option<Atom>>,
+}
+
+#[derive(Default)]
+struct ModificationObserver {
+    modified: bool,
+}
+
+impl SpaceObserver for ModificationObserver {
+    fn notify(&mut self, _event: &SpaceEvent) {
+        self.modified = true;
+    }
+}
+
+/// Returns the dependencies of the module space or empty vector if space is
+/// not a module space
+pub(crate) fn module_space_deps(space: &DynSpace) -> Vec<DynSpace> {
+    space.borrow().as_any()
+        .and_then(|space| space.downcast_ref::<ModuleSpace>())
+        .map_or(vec![], |space| space.deps().clone())
+}
+
+/// Returns the dependencies of the module space and all their dependencies
+fn transitive_module_space_deps(space: &DynSpace) -> Vec<DynSpace> {
+    let mut deps: Vec<DynSpace> = Vec::new();
+    let mut stack = module_space_deps(space);
+    while let Some(dep) = stack.pop() {
+        if dep != *space && !deps.contains(&dep) {
+            stack.extend(module_space_deps(&dep));
+            deps.push(dep);
+        }
+    }
+    deps
+}
+
+impl CachedTypes {
+    fn new(space: &DynSpace) -> Self {
+        let deps = transitive_module_space_deps(space);
+        let observers = std::iter::once(space).chain(deps.iter())
+            // Module spaces wrap another DynSpace, thus the observer is
+            // registered through the nested reference
+            .map(|space| space.borrow().common().register_observer(ModificationObserver::default()))
+            .collect();
+        let definitions = Rc::new(query_definitions(space));
+        Self{ space: space.clone(), deps, observers, definitions, inferred: HashMap::new() }
+    }
+
+    fn is_valid_for(&self, space: &DynSpace) -> bool {
+        self.space == *space
+            && !self.observers.iter().any(|observer| observer.borrow().modified)
+            && self.deps == transitive_module_space_deps(space)
+    }
+}
+
+impl InferredTypesCache {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Works as [infer_function_type] but keeps the definitions and the
+    /// inferred types for the next calls on the same `space`. Symbols which
+    /// have no definitions are not inferred at all.
+    pub fn infer_function_type(&self, space: &DynSpace, op: &Atom) -> Option<Atom> {
+        let name = match op {
+            Atom::Symbol(name) => name,
+            _ => return None,
+        };
+        self.with_inference(space, |inference| {
+            if !inference.definitions.contains_key(name) || !query_types(space, op).is_empty() {
+                return None;
+            }
+            inference.infer_definitions(name)
+        }).map(rename_type_vars)
+    }
+
+    /// Infers the type of the symbol as [InferredTypesCache::infer_function_type]
+    /// does and the type of the expression from the types of the called
+    /// functions. Returns `None` if the type cannot be inferred or is not
+    /// constrained at all.
+    pub fn infer_atom_type(&self, space: &DynSpace, atom: &Atom) -> Option<Atom> {
+        match atom {
+            Atom::Symbol(_) => self.infer_function_type(space, atom),
+            Atom::Expression(_) => self.with_inference(space, |inference| {
+                let typ = inference.infer(atom, &mut HashMap::new())?;
+                match apply_bindings_to_atom_move(typ, &inference.bindings) {
+                    Atom::Variable(_) => None,
+                    typ => Some(typ),
+                }
+            }).map(rename_type_vars),
+            _ => None,
+        }
+    }
+
+    fn with_inference<F>(&self, space: &DynSpace, f: F) -> Option<Atom>
+        where F: FnOnce(&mut TypeInference) -> Option<Atom>
+    {
+        let mut state = self.state.borrow_mut();
+        if !state.as_ref().is_some_and(|state| state.is_valid_for(space)) {
+            *state = Some(CachedTypes::new(space));
+        }
+        let state = state.as_mut().unwrap();
+        let inferred = std::mem::take(&mut state.inferred);
+        let mut inference = TypeInference::with_definitions(space, state.definitions.clone(), inferred);
+        let typ = f(&mut inference);
+        state.inferred = inference.inferred;
+        typ
+    }
+}
+
+impl Debug for InferredTypesCache {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let cached = self.state.borrow().as_ref().map_or(0, |state| state.inferred.len());
+        write!(f, "InferredTypesCache{{ cached: {} }}", cached)
+    }
+}
+
+/// Infers types of all functions which are defined in the `space` by `=`
+/// atoms but have no type declared. Returns `(: <function> <type>)` atoms
+/// which can be added into the space to make inferred types visible to the
+/// type checker.
+pub fn infer_types(space: &dyn Space) -> Vec<Atom> {
+    let mut inference = TypeInference::new(space);
+    let mut names: Vec<SymbolAtom> = inference.definitions.keys().cloned().collect();
+    names.sort_by(|a, b| a.name().cmp(b.name()));
+    names.into_iter()
+        .filter(|name| query_types(space, &Atom::Symbol(name.clone())).is_empty())
+        .filter_map(|name| inference.infer_definitions(&name)
+            .map(|typ| Atom::expr([HAS_TYPE_SYMBOL, Atom::Symbol(name), rename_type_vars(typ)])))
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -698,6 +1490,71 @@ mod tests {
         assert!(check_type(&space, &atom("a"), &atom("C")));
     }
 
+    #[test]
+    fn super_types_loop() {
+        let space = metta_space("
+            (:< A B)
+            (:< B C)
+            (:< C A)
+            (:< B D)
+        ");
+
+        assert_eq!(get_super_types(&space, &atom("A")), vec![atom("A"), atom("B"), atom("C"), atom("D")]);
+        assert_eq!(get_super_types(&space, &expr!(t)), vec![expr!(t)]);
+    }
+
+    #[test]
+    fn subtype_cycle_detection() {
+        let space = metta_space("
+            (:< A B)
+            (:< B C)
+            (:< C A)
+            (:< B D)
+            (:< D E)
+            (:< X D)
+            (:< X E)
+        ");
+
+        assert_eq!(find_subtype_cycle(&space, &atom("A")), Some(vec![atom("A"), atom("B"), atom("C"), atom("A")]));
+        assert_eq!(find_subtype_cycle(&space, &atom("C")), Some(vec![atom("C"), atom("A"), atom("B"), atom("C")]));
+        assert_eq!(find_subtype_cycle(&space, &atom("D")), None);
+        assert_eq!(find_subtype_cycle(&space, &atom("X")), None);
+        assert_eq!(find_subtype_cycle(&space, &expr!(t)), None);
+    }
+
+    #[test]
+    fn declared_subtype_cycle_detection() {
+        let space = metta_space("
+            (:< A B)
+            (:< B C)
+            (:< X D)
+        ");
+
+        assert_eq!(find_declared_subtype_cycle(&space, &expr!(":<" "C" "A")),
+            Some(vec![atom("C"), atom("A"), atom("B"), atom("C")]));
+        assert_eq!(find_declared_subtype_cycle(&space, &expr!(":<" "D" "D")), Some(vec![atom("D"), atom("D")]));
+        assert_eq!(find_declared_subtype_cycle(&space, &expr!(":<" "D" "X")), Some(vec![atom("D"), atom("X"), atom("D")]));
+        assert_eq!(find_declared_subtype_cycle(&space, &expr!(":<" "C" "D")), None);
+        assert_eq!(find_declared_subtype_cycle(&space, &expr!(":" "C" "A")), None);
+    }
+
+    #[test]
+    fn check_type_grounded_and_application_subtypes() {
+        let space = metta_space("
+            (:< Number Real)
+            (:< Cat Animal)
+            (: make-cat (-> Cat))
+            (: name (-> Animal Symbol))
+        ");
+
+        assert!(check_type(&space, &atom("1"), &atom("Real")));
+        assert!(check_type(&space, &atom("(make-cat)"), &atom("Animal")));
+        assert!(!check_type(&space, &atom("(make-cat)"), &atom("Real")));
+        assert!(validate_atom(&space, &atom("(name (make-cat))")));
+        assert_eq!(get_type_bindings(&space, &atom("(make-cat)"), &expr!(t)),
+            vec![(atom("Cat"), bind!{ t: atom("Cat") }), (atom("Animal"), bind!{ t: atom("Animal") })]);
+    }
+
     #[test]
     fn test_validate_atom() {
         let space = grammar_space();
@@ -1244,4 +2101,143 @@ mod tests {
         assert!(validate_atom(&space, &atom("(varF (varR a))")));
         assert!(!validate_atom(&space, &atom("(varF (atomR a))")));
     }
+
+    #[test]
+    fn infer_function_type_from_callee_types() {
+        let space = metta_space("
+            (: Z Nat)
+            (: S (-> Nat Nat))
+            (= (inc $n) (S $n))
+            (= (add2 $n) (inc (inc $n)))
+            (= (sum $a $b) (+ $a $b))
+            (= (zero) Z)
+        ");
+
+        assert_eq!(infer_function_type(&space, &sym!("inc")), Some(expr!("->" "Nat" "Nat")));
+        assert_eq!(infer_function_type(&space, &sym!("add2")), Some(expr!("->" "Nat" "Nat")));
+        assert_eq!(infer_function_type(&space, &sym!("sum")), Some(expr!("->" "Number" "Number" "Number")));
+        assert_eq!(infer_function_type(&space, &sym!("zero")), Some(expr!("->" "Nat")));
+        assert_eq!(infer_function_type(&space, &sym!("S")), None);
+        assert_eq!(infer_function_type(&space, &sym!("unknown")), None);
+    }
+
+    #[test]
+    fn inferred_types_cache_is_dropped_on_space_modification() {
+        let space = DynSpace::new(metta_space("
+            (: S (-> Nat Nat))
+            (= (inc $n) (S $n))
+        "));
+        let cache = InferredTypesCache::new();
+        assert_eq!(cache.infer_function_type(&space, &sym!("inc")), Some(expr!("->" "Nat" "Nat")));
+        assert_eq!(cache.infer_function_type(&space, &sym!("add2")), None);
+
+        space.borrow_mut().add(expr!("=" ("add2" n) ("inc" ("inc" n))));
+        assert_eq!(cache.infer_function_type(&space, &sym!("add2")), Some(expr!("->" "Nat" "Nat")));
+
+        space.borrow_mut().add(expr!(":" "inc" ("->" "Int" "Int")));
+        assert_eq!(cache.infer_function_type(&space, &sym!("inc")), None);
+    }
+
+    #[test]
+    fn inferred_types_cache_is_dropped_on_dependency_change() {
+        let space = DynSpace::new(ModuleSpace::new(metta_space("(= (inc $n) (S $n))")));
+        let cache = InferredTypesCache::new();
+        assert_eq!(cache.infer_function_type(&space, &sym!("inc")), Some(expr!("->" t t1)));
+
+        let dep = DynSpace::new(ModuleSpace::new(metta_space("(: S (-> Nat Nat))")));
+        space.borrow_mut().as_any_mut().unwrap().downcast_mut::<ModuleSpace>().unwrap().add_dep(dep);
+        assert_eq!(cache.infer_function_type(&space, &sym!("inc")), Some(expr!("->" "Nat" "Nat")));
+    }
+
+    #[test]
+    fn inferred_types_cache_is_dropped_on_transitive_dependency_change() {
+        let space = DynSpace::new(ModuleSpace::new(metta_space("(= (inc $n) (S $n))")));
+        let dep = DynSpace::new(ModuleSpace::new(metta_space("(: Z Nat)")));
+        space.borrow_mut().as_any_mut().unwrap().downcast_mut::<ModuleSpace>().unwrap().add_dep(dep.clone());
+        let cache = InferredTypesCache::new();
+        assert_eq!(cache.infer_function_type(&space, &sym!("inc")), Some(expr!("->" t t1)));
+
+        let nested = DynSpace::new(ModuleSpace::new(metta_space("(: S (-> Nat Nat))")));
+        dep.borrow_mut().as_any_mut().unwrap().downcast_mut::<ModuleSpace>().unwrap().add_dep(nested.clone());
+        assert_eq!(transitive_module_space_deps(&space), vec![dep, nested]);
+        assert!(!cache.state.borrow().as_ref().unwrap().is_valid_for(&space));
+    }
+
+    #[test]
+    fn inferred_types_cache_infers_expression_type() {
+        let space = DynSpace::new(metta_space("
+            (: Z Nat)
+            (: S (-> Nat Nat))
+            (= (inc $n) (S $n))
+        "));
+        let cache = InferredTypesCache::new();
+        assert_eq!(cache.infer_atom_type(&space, &expr!("inc" "Z")), Some(expr!("Nat")));
+        assert_eq!(cache.infer_atom_type(&space, &expr!("inc" x)), Some(expr!("Nat")));
+        assert_eq!(cache.infer_atom_type(&space, &expr!("unknown" "Z")), None);
+    }
+
+    #[test]
+    fn infer_function_type_polymorphic() {
+        let space = metta_space("
+            (: Nil (List $a))
+            (: Cons (-> $a (List $a) (List $a)))
+            (= (id $x) $x)
+            (= (single $x) (Cons $x Nil))
+            (= (pair $x $y) (Cons (id $x) (Cons (id $y) Nil)))
+            (= (use-id) (single (id 1)))
+        ");
+
+        assert_eq!(infer_function_type(&space, &sym!("id")), Some(expr!("->" t t)));
+        assert_eq!(infer_function_type(&space, &sym!("single")), Some(expr!("->" t ("List" t))));
+        assert_eq!(infer_function_type(&space, &sym!("pair")), Some(expr!("->" t t ("List" t))));
+        assert_eq!(infer_function_type(&space, &sym!("use-id")), Some(expr!("->" ("List" "Number"))));
+    }
+
+    #[test]
+    fn infer_function_type_recursive() {
+        let space = metta_space("
+            (: Z Nat)
+            (: S (-> Nat Nat))
+            (= (plus Z $y) $y)
+            (= (plus (S $k) $y) (S (plus $k $y)))
+            (= (even Z) True)
+            (= (even (S $n)) (odd $n))
+            (= (odd Z) False)
+            (= (odd (S $n)) (even $n))
+        ");
+
+        assert_eq!(infer_function_type(&space, &sym!("plus")), Some(expr!("->" "Nat" "Nat" "Nat")));
+        assert_eq!(infer_function_type(&space, &sym!("even")), Some(expr!("->" "Nat" "Bool")));
+        assert_eq!(infer_function_type(&space, &sym!("odd")), Some(expr!("->" "Nat" "Bool")));
+    }
+
+    #[test]
+    fn infer_function_type_inconsistent_definitions() {
+        let space = metta_space("
+            (: Z Nat)
+            (: S (-> Nat Nat))
+            (= (bad Z) 1)
+            (= (bad (S $n)) \"one\")
+            (= (arity $x) $x)
+            (= (arity $x $y) $x)
+            (= (uses-bad $x) (bad $x))
+        ");
+
+        assert_eq!(infer_function_type(&space, &sym!("bad")), None);
+        assert_eq!(infer_function_type(&space, &sym!("arity")), None);
+        assert!(infer_function_type(&space, &sym!("uses-bad")).is_some());
+    }
+
+    #[test]
+    fn infer_types_skips_typed_functions() {
+        let space = metta_space("
+            (: Z Nat)
+            (: S (-> Nat Nat))
+            (: typed (-> Nat Nat))
+            (= (typed $x) $x)
+            (= (inc $n) (S $n))
+        ");
+
+        assert_eq!(infer_types(&space), vec![expr!(":" "inc" ("->" "Nat" "Nat"))]);
+    }
 }
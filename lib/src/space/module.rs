@@ -54,6 +54,13 @@ impl ModuleSpace {
         self.deps.push(space)
     }
 
+    /// Removes the dependency space, returns `false` if it is not found
+    pub fn remove_dep(&mut self, space: &DynSpace) -> bool {
+        let len = self.deps.len();
+        self.deps.retain(|dep| dep != space);
+        self.deps.len() != len
+    }
+
     pub fn deps(&self) -> &Vec<DynSpace> {
         &self.deps
     }
@@ -0,0 +1,77 @@
+//! Implements the non-interactive mode of metta-repl, which runs a whole program from a file, stdin
+//! or the `--eval` argument, prints the results in the requested format and reports the outcome
+//! through the process exit code
+//!
+
+use clap::ValueEnum;
+use hyperon::common::collections::VecDisplay;
+
+use crate::metta_shim::MettaShim;
+
+/// Format of the results printed in the non-interactive mode
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// One line with the list of results per `!` statement
+    #[default]
+    Text,
+    /// One JSON record per `!` statement with the statement and its results
+    Json,
+}
+
+/// Results of a single `!` statement
+pub struct StatementResult {
+    /// Text of the evaluated atom, or `None` if it is not known
+    pub statement: Option<String>,
+    pub results: Vec<String>,
+    /// `true` if any of the results is an `Error` atom
+    pub is_error: bool,
+}
+
+/// Results of running a program
+pub struct ProgramRun {
+    pub statements: Vec<StatementResult>,
+    pub failed_assertions: usize,
+    /// Error which stopped the run, for example a syntax error
+    pub error: Option<String>,
+    /// `true` if the run was interrupted with Ctrl-C
+    pub interrupted: bool,
+}
+
+impl ProgramRun {
+    /// Returns the process exit code: 130 if the run was interrupted, 1 if the program produced an
+    /// `Error` atom or a failed assertion, or if the run was stopped by an error, otherwise 0
+    pub fn exit_code(&self) -> i32 {
+        if self.interrupted {
+            return 130;
+        }
+        let failed = self.error.is_some() || self.failed_assertions > 0
+            || self.statements.iter().any(|statement| statement.is_error);
+        if failed { 1 } else { 0 }
+    }
+}
+
+/// Runs the program, prints its results and returns the process exit code
+pub fn run_program(metta: &mut MettaShim, program: &str, format: OutputFormat) -> i32 {
+    let run = metta.run_program(program);
+    for statement in run.statements.iter() {
+        match format {
+            OutputFormat::Text => println!("{}", VecDisplay(&statement.results)),
+            OutputFormat::Json => println!("{}", json_record(statement)),
+        }
+    }
+    if let Some(error) = &run.error {
+        eprintln!("Error: {error}");
+    }
+    if run.interrupted {
+        eprintln!("Interrupted");
+    }
+    run.exit_code()
+}
+
+fn json_record(statement: &StatementResult) -> serde_json::Value {
+    serde_json::json!({
+        "statement": statement.statement,
+        "results": statement.results,
+        "error": statement.is_error,
+    })
+}
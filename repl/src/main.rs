@@ -1,4 +1,5 @@
 
+use std::io::{IsTerminal, Read};
 use std::path::PathBuf;
 use std::process::exit;
 use std::sync::{Arc, Mutex};
@@ -7,7 +8,7 @@ use rustyline::error::ReadlineError;
 use rustyline::{Cmd, CompletionType, Config, EditMode, Editor, KeyEvent, KeyCode, Modifiers, EventContext, RepeatCount, EventHandler, ConditionalEventHandler, Event};
 
 use anyhow::Result;
-use clap::Parser;
+use clap::{Parser, Subcommand};
 use ctrlc;
 
 mod metta_shim;
@@ -19,26 +20,84 @@ use config_params::*;
 mod interactive_helper;
 use interactive_helper::*;
 
+mod test_runner;
+use test_runner::*;
+
+mod watch;
+use watch::*;
+
+mod batch;
+use batch::*;
+
 static SIGINT_RECEIVED_COUNT: Mutex<usize> = Mutex::new(0);
 
 #[derive(Parser)]
-#[command(version, about)]
+#[command(version, about, args_conflicts_with_subcommands = true)]
 struct CliArgs {
-    /// .metta file to execute.  `metta` will run in interactive mode if no file is supplied
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// .metta file to execute, or `-` to read the program from stdin.  `metta` will run in interactive
+    /// mode if no file is supplied and stdin is a terminal
     file: Option<PathBuf>,
 
+    /// Arguments passed to the MeTTa program, which reads them using the `&args` token or the
+    /// `get-args` operation.  With `--eval` all the positional arguments are passed to the program
+    #[arg(trailing_var_arg = true, allow_hyphen_values = true, requires = "file")]
+    args: Vec<String>,
+
+    /// MeTTa expressions to execute instead of a file, e.g. `--eval '!(+ 1 2)'`
+    #[arg(short, long)]
+    eval: Option<String>,
+
+    /// Format of the results printed when running a file, stdin or `--eval` expressions
+    #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+    output: OutputFormat,
+
     /// Additional include directory paths
     #[arg(short, long)]
     include_paths: Vec<PathBuf>,
+
+    /// Run the file again in a fresh runner whenever the file or any module it loaded from disk changes
+    #[arg(short, long, requires = "file")]
+    watch: bool,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Run .metta test files and report the outcomes of their assertions
+    Test(TestArgs),
 }
 
 fn main() -> Result<()> {
-    let cli_args = CliArgs::parse();
+    let mut cli_args = CliArgs::parse();
     let _ = env_logger::builder().filter_level(log::LevelFilter::Info).try_init();
 
+    if let Some(Command::Test(test_args)) = cli_args.command {
+        exit(run_tests(test_args));
+    }
+
+    //There is no file with `--eval`, so the first positional argument is an argument of the program
+    if cli_args.eval.is_some() {
+        if let Some(first_arg) = cli_args.file.take() {
+            cli_args.args.insert(0, first_arg.to_string_lossy().into_owned());
+        }
+    }
+
+    //A file path of "-" means the program is read from stdin, as does a piped stdin without a file
+    let metta_file = cli_args.file.as_ref().filter(|path| path.as_os_str() != "-");
+    let read_stdin = match &cli_args.file {
+        Some(_) => metta_file.is_none(),
+        None => cli_args.eval.is_none() && !std::io::stdin().is_terminal(),
+    };
+    if cli_args.watch && metta_file.is_none() {
+        eprintln!("Error: --watch requires a .metta file");
+        exit(2);
+    }
+
     //If we have a metta_file, then the working dir is the parent of that file
-    //If we are running in interactive mode, it's the working dir at the time the repl is invoked
-    let metta_working_dir: PathBuf = match &cli_args.file {
+    //Otherwise, it's the working dir at the time the repl is invoked
+    let metta_working_dir: PathBuf = match metta_file {
         Some(metta_file) => {
             metta_file.parent().unwrap().into()
         },
@@ -51,7 +110,7 @@ fn main() -> Result<()> {
     };
 
     //Create our MeTTa runtime environment
-    let mut metta = MettaShim::new(metta_working_dir, cli_args.include_paths);
+    let mut metta = MettaShim::new(metta_working_dir, cli_args.include_paths, cli_args.args);
 
     //Init our runtime environment
     let repl_params = ReplParams::new(&metta);
@@ -72,14 +131,26 @@ fn main() -> Result<()> {
     })?;
 
 
-    //If we have .metta files to run, then run them
-    if let Some(metta_file) = &cli_args.file {
+    if let (Some(metta_file), true) = (metta_file, cli_args.watch) {
+        watch_file(metta, metta_file);
+        return Ok(());
+    }
+
+    //If we have a program to run, then run it and exit with its outcome
+    let program = if let Some(expressions) = cli_args.eval {
+        Some(expressions)
+    } else if read_stdin {
+        let mut program = String::new();
+        std::io::stdin().read_to_string(&mut program)?;
+        Some(program)
+    } else {
+        metta_file.map(std::fs::read_to_string).transpose()?
+    };
+
+    if let Some(program) = program {
 
-        //Only print the output from the primary .metta file
-        let metta_file = std::io::BufReader::new(std::fs::File::open(metta_file)?);
-        metta.exec(metta_file);
-        metta.print_result();
-        Ok(())
+        //Only print the output from the primary program
+        exit(run_program(&mut metta, &program, cli_args.output));
 
     } else {
 
@@ -97,6 +168,9 @@ fn show_welcome_message() {
 // RUST_LOG=rustyline=debug cargo run --example example 2> debug.log
 fn start_interactive_mode(repl_params: ReplParams, mut metta: MettaShim) -> rustyline::Result<()> {
 
+    //Let the MeTTa programs read the input using the line editor
+    metta.set_interactive_input();
+
     //Run the built-in repl-init code
     metta.exec(builtin_init_metta_code().as_str());
 
@@ -43,6 +43,7 @@ pub mod metta_interface_mod {
     use hyperon::common::collections::VecDisplay;
     use super::{exec_state_prepare, exec_state_should_break};
     use hyperon::metta::runner::str::unescape;
+    use hyperon::metta::runner::coverage::CoverageCollector;
     use hyperon::metta::text::CharReader;
 
     /// Load the hyperon module, and get the "__version__" attribute
@@ -79,7 +80,7 @@ pub mod metta_interface_mod {
     impl MettaShim {
         const PY_CODE: &'static str = include_str!("py_shim.py");
 
-        pub fn new(working_dir: PathBuf, include_paths: Vec<PathBuf>) -> Self {
+        pub fn new(working_dir: PathBuf, include_paths: Vec<PathBuf>, args: Vec<String>) -> Self {
 
             match || -> Result<_, String> {
                 //Confirm the hyperonpy version is compatible
@@ -87,7 +88,7 @@ pub mod metta_interface_mod {
                 confirm_hyperonpy_version(&req_str)?;
 
                 //Initialize the Hyperon environment
-                let new_shim = MettaShim::init_common_env(working_dir, include_paths)?;
+                let new_shim = MettaShim::init_common_env(working_dir, include_paths, args)?;
 
                 Ok(new_shim)
             }() {
@@ -104,7 +105,8 @@ pub mod metta_interface_mod {
             format!("=={PACKAGE_VERSION}")
         }
 
-        pub fn init_common_env(working_dir: PathBuf, include_paths: Vec<PathBuf>) -> Result<MettaShim, String> {
+        pub fn init_common_env(working_dir: PathBuf, include_paths: Vec<PathBuf>, _args: Vec<String>) -> Result<MettaShim, String> {
+            //TODO: The Python API doesn't allow passing the program arguments to the environment yet
             match Python::with_gil(|py| -> PyResult<(Py<PyModule>, Py<PyAny>)> {
                 let py_mod = PyModule::from_code(py, Self::PY_CODE, "", "")?;
                 let init_func = py_mod.getattr("init_metta")?;
@@ -170,6 +172,43 @@ pub mod metta_interface_mod {
             }
         }
 
+        pub fn run_test_file(path: &std::path::Path, _include_paths: &[PathBuf], _coverage: Option<&std::rc::Rc<CoverageCollector>>) -> crate::test_runner::TestFileResult {
+            //TODO: The Python runner doesn't report assertion outcomes, so test mode requires the native runner
+            crate::test_runner::TestFileResult {
+                path: path.to_path_buf(),
+                assertions: vec![],
+                errors: vec!["Test mode is not supported by metta-repl built with python feature".to_string()],
+                duration: std::time::Duration::ZERO,
+            }
+        }
+
+        pub fn set_interactive_input(&mut self) {
+            //The Python runner reads stdin directly, so there is nothing to configure
+        }
+
+        pub fn run_program(&mut self, program: &str) -> crate::batch::ProgramRun {
+            //TODO: The Python runner doesn't report assertion outcomes nor the evaluated statements
+            self.exec(program);
+            let statements = Python::with_gil(|py| {
+                self.result.iter().map(|results| {
+                    let results: Vec<String> = results.iter().map(|atom| atom.as_ref(py).to_string()).collect();
+                    let is_error = results.iter().any(|result| result.starts_with("(Error "));
+                    crate::batch::StatementResult{ statement: None, results, is_error }
+                }).collect()
+            });
+            crate::batch::ProgramRun{ statements, failed_assertions: 0, error: None, interrupted: false }
+        }
+
+        pub fn run_file_in_fresh_runner(&mut self, _path: &std::path::Path) -> (Vec<crate::test_runner::AssertionResult>, Option<String>) {
+            //TODO: The Python runner doesn't report assertion outcomes nor module source paths, so watch mode
+            // requires the native runner
+            (vec![], Some("Watch mode is not supported by metta-repl built with python feature".to_string()))
+        }
+
+        pub fn module_source_paths(&self) -> Vec<PathBuf> {
+            vec![]
+        }
+
         pub fn print_result(&self) {
             Python::with_gil(|py| -> PyResult<()> {
                 for result_vec in self.result.iter() {
@@ -274,6 +313,18 @@ pub mod metta_interface_mod {
     use hyperon::ExpressionAtom;
     use hyperon::Atom;
     use hyperon::metta::runner::{Metta, RunnerState, Environment, EnvBuilder};
+    use hyperon::metta::runner::modules::ModId;
+    use hyperon::metta::runner::input::{InputSource, StdInputSource};
+    use hyperon::metta::runner::assertion::AssertionCollector;
+    use hyperon::metta::runner::coverage::CoverageCollector;
+    use std::rc::Rc;
+    use std::collections::HashSet;
+    use crate::test_runner::{TestFileResult, AssertionResult};
+    use crate::batch::{ProgramRun, StatementResult};
+    use std::time::Instant;
+    use rustyline::DefaultEditor;
+    use rustyline::error::ReadlineError;
+    use std::sync::{Arc, Mutex};
     use hyperon::common::collections::VecDisplay;
     use super::{exec_state_prepare, exec_state_should_break};
     use hyperon::metta::runner::str::atom_to_string;
@@ -283,11 +334,48 @@ pub mod metta_interface_mod {
         pub result: Vec<Vec<Atom>>,
     }
 
+    /// Prompt displayed when MeTTa program reads a line in interactive mode
+    const INPUT_PROMPT: &str = "? ";
+
+    /// Reads the program input using line editor which is created on the
+    /// first read and reused by the next ones
+    #[derive(Default)]
+    struct ReplInputSource {
+        editor: Mutex<Option<DefaultEditor>>,
+    }
+
+    impl std::fmt::Debug for ReplInputSource {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "ReplInputSource")
+        }
+    }
+
+    impl InputSource for ReplInputSource {
+        fn read_line(&self) -> std::io::Result<Option<String>> {
+            let mut editor = self.editor.lock().unwrap();
+            if editor.is_none() {
+                *editor = Some(DefaultEditor::new().map_err(std::io::Error::other)?);
+            }
+            let rl = editor.as_mut().unwrap();
+            match rl.readline(INPUT_PROMPT) {
+                Ok(line) => Ok(Some(line)),
+                Err(ReadlineError::Eof) |
+                Err(ReadlineError::Interrupted) => Ok(None),
+                Err(ReadlineError::Io(err)) => Err(err),
+                Err(err) => Err(std::io::Error::other(err)),
+            }
+        }
+
+        fn read_all(&self) -> std::io::Result<String> {
+            StdInputSource.read_all()
+        }
+    }
+
     impl MettaShim {
 
-        pub fn new(working_dir: PathBuf, include_paths: Vec<PathBuf>) -> Self {
+        pub fn new(working_dir: PathBuf, include_paths: Vec<PathBuf>, args: Vec<String>) -> Self {
             match || -> Result<_, String> {
-                let new_shim = MettaShim::init_common_env(working_dir, include_paths)?;
+                let new_shim = MettaShim::init_common_env(working_dir, include_paths, args)?;
                 Ok(new_shim)
             }() {
                 Ok(shim) => shim,
@@ -298,9 +386,10 @@ pub mod metta_interface_mod {
             }
         }
 
-        pub fn init_common_env(working_dir: PathBuf, include_paths: Vec<PathBuf>) -> Result<MettaShim, String> {
+        pub fn init_common_env(working_dir: PathBuf, include_paths: Vec<PathBuf>, args: Vec<String>) -> Result<MettaShim, String> {
             let mut builder = EnvBuilder::new()
                 .set_working_dir(Some(&working_dir))
+                .set_args(args)
                 .set_create_config_dir(true);
 
             for path in include_paths.into_iter().rev() {
@@ -316,6 +405,130 @@ pub mod metta_interface_mod {
             Ok(new_shim)
         }
 
+        /// Runs the test file in a fresh runner and collects the outcomes of its assertions.  The test
+        /// file is run till the end, so errors don't hide the outcomes of the following assertions
+        pub fn run_test_file(path: &Path, include_paths: &[PathBuf], coverage: Option<&Rc<CoverageCollector>>) -> TestFileResult {
+            let start = Instant::now();
+            let collector = Arc::new(AssertionCollector::new());
+            let mut errors = vec![];
+            let results = || -> Result<Vec<Vec<Atom>>, String> {
+                let path = path.canonicalize().map_err(|err| format!("Cannot open {}: {err}", path.display()))?;
+                let program = std::fs::read_to_string(&path).map_err(|err| format!("Cannot read {}: {err}", path.display()))?;
+                let mut builder = EnvBuilder::test_env().set_working_dir(path.parent());
+                for include_path in include_paths.iter().rev() {
+                    builder = builder.push_include_path(include_path);
+                }
+                let metta = Metta::new(Some(builder));
+                metta.set_assertion_listener(Some(collector.clone()));
+                if let Some(coverage) = coverage {
+                    coverage.track_source(&path, &program, &metta.tokenizer().borrow(), &metta.module_space(ModId::TOP))?;
+                    metta.set_coverage_collector(Some(coverage.clone()));
+                }
+                let mut runner_state = RunnerState::new_with_parser(&metta, Box::new(SExprParser::new(program.as_str())));
+                runner_state.set_continue_on_error(true);
+                runner_state.run_to_completion()
+            }().unwrap_or_else(|err| {
+                errors.push(err);
+                vec![]
+            });
+
+            let outcomes = collector.take();
+            //Failed assertions are returned as errors too, the rest of errors are not related to assertions
+            let failed: HashSet<&str> = outcomes.iter()
+                .filter(|outcome| !outcome.is_passed())
+                .map(|outcome| outcome.assertion.as_str())
+                .collect();
+            let is_assertion_failure = |atom: &Atom| match atom {
+                Atom::Expression(expr) => expr.children().get(1).is_some_and(|call| failed.contains(call.to_string().as_str())),
+                _ => false,
+            };
+            errors.extend(results.iter().flatten()
+                .filter(|atom| atom_is_error(atom) && !is_assertion_failure(atom))
+                .map(|atom| atom.to_string()));
+
+            let assertions = outcomes.into_iter()
+                .map(|outcome| AssertionResult{ assertion: outcome.assertion, failure: outcome.failure })
+                .collect();
+            TestFileResult{ path: path.to_path_buf(), assertions, errors, duration: start.elapsed() }
+        }
+
+        /// Runs the program, collecting the results of each `!` statement and the outcomes of the
+        /// assertions.  The run can be interrupted with Ctrl-C
+        pub fn run_program(&mut self, program: &str) -> ProgramRun {
+            let collector = Arc::new(AssertionCollector::new());
+            self.metta.set_assertion_listener(Some(collector.clone()));
+            let parser = SExprParser::new(program);
+
+            let mut runner_state = RunnerState::new_with_parser(&self.metta, Box::new(parser));
+            exec_state_prepare();
+            let mut error = None;
+            let mut interrupted = false;
+            while !runner_state.is_complete() {
+                if exec_state_should_break() {
+                    interrupted = true;
+                    break;
+                }
+                if let Err(err) = runner_state.run_step() {
+                    error = Some(err);
+                    break;
+                }
+            }
+            let statements = runner_state.current_statements().clone();
+            self.result = runner_state.into_results();
+            self.metta.set_assertion_listener(None);
+
+            let statements = statements.iter().zip(self.result.iter()).map(|(statement, results)| StatementResult {
+                statement: Some(statement.to_string()),
+                results: results.iter().map(|atom| atom.to_string()).collect(),
+                is_error: results.iter().any(atom_is_error),
+            }).collect();
+            ProgramRun{ statements, failed_assertions: collector.failed(), error, interrupted }
+        }
+
+        /// Replaces the runner with a fresh one and runs the file in it.  Returns the outcomes of the
+        /// assertions made by the file and the error which stopped the run, if any.  The run can be
+        /// interrupted with Ctrl-C
+        pub fn run_file_in_fresh_runner(&mut self, path: &Path) -> (Vec<AssertionResult>, Option<String>) {
+            let collector = Arc::new(AssertionCollector::new());
+            self.metta = Metta::new(None);
+            self.metta.set_assertion_listener(Some(collector.clone()));
+            self.result = vec![];
+
+            let error = match std::fs::File::open(path) {
+                Ok(file) => {
+                    let parser = SExprParser::new(std::io::BufReader::new(file));
+                    let mut runner_state = RunnerState::new_with_parser(&self.metta, Box::new(parser));
+                    exec_state_prepare();
+                    let mut error = None;
+                    while !runner_state.is_complete() && !exec_state_should_break() {
+                        if let Err(err) = runner_state.run_step() {
+                            error = Some(err);
+                            break;
+                        }
+                        self.result = runner_state.current_results().clone();
+                    }
+                    error
+                },
+                Err(err) => Some(format!("Cannot open {}: {err}", path.display())),
+            };
+
+            let assertions = collector.take().into_iter()
+                .map(|outcome| AssertionResult{ assertion: outcome.assertion, failure: outcome.failure })
+                .collect();
+            (assertions, error)
+        }
+
+        /// Returns the paths of the files the modules loaded into the runner were loaded from
+        pub fn module_source_paths(&self) -> Vec<PathBuf> {
+            self.metta.module_source_paths()
+        }
+
+        /// Makes `read-line` operation read the terminal using the line editor, so the user sees
+        /// the input prompt and can edit the line
+        pub fn set_interactive_input(&mut self) {
+            self.metta.set_input_source(Arc::new(ReplInputSource::default()));
+        }
+
         pub fn parse_line(&mut self, line: &str) -> Result<(), String> {
             let mut parser = SExprParser::new(line);
             loop {
@@ -0,0 +1,348 @@
+//! Implements `metta-repl test` mode, which discovers `.metta` test files, runs each of them in a
+//! fresh runner and reports the outcomes of the assertions made by the tests
+//!
+
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::time::Duration;
+
+use clap::Args;
+use hyperon::metta::runner::coverage::CoverageCollector;
+
+use crate::metta_shim::MettaShim;
+
+#[derive(Args)]
+pub struct TestArgs {
+    /// Test files or directories.  Directories are searched recursively for .metta files
+    #[arg(required = true)]
+    pub paths: Vec<PathBuf>,
+
+    /// Additional include directory paths
+    #[arg(short, long)]
+    pub include_paths: Vec<PathBuf>,
+
+    /// Write the report in JUnit XML format into the file
+    #[arg(long)]
+    pub junit: Option<PathBuf>,
+
+    /// Write the report in TAP format into the file
+    #[arg(long)]
+    pub tap: Option<PathBuf>,
+
+    /// Write the coverage of the test files definitions in lcov format into the file
+    #[arg(long)]
+    pub lcov: Option<PathBuf>,
+}
+
+/// Outcome of a single assertion made by the test
+pub struct AssertionResult {
+    /// Text of the assertion expression
+    pub assertion: String,
+    /// Failure report or `None` if assertion passed
+    pub failure: Option<String>,
+}
+
+/// Results of running a single test file
+pub struct TestFileResult {
+    pub path: PathBuf,
+    pub assertions: Vec<AssertionResult>,
+    /// Errors which are not assertion failures
+    pub errors: Vec<String>,
+    pub duration: Duration,
+}
+
+impl TestFileResult {
+    pub fn failed(&self) -> usize {
+        self.assertions.iter().filter(|a| a.failure.is_some()).count()
+    }
+
+    pub fn passed(&self) -> usize {
+        self.assertions.len() - self.failed()
+    }
+
+    pub fn is_ok(&self) -> bool {
+        self.errors.is_empty() && self.failed() == 0
+    }
+}
+
+/// Runs `metta-repl test` mode and returns the process exit code
+pub fn run_tests(args: TestArgs) -> i32 {
+    let files = match discover_test_files(&args.paths) {
+        Ok(files) => files,
+        Err(err) => {
+            eprintln!("Error: {err}");
+            return 2;
+        }
+    };
+
+    let coverage = args.lcov.as_ref().map(|_| Rc::new(CoverageCollector::new()));
+    let mut results = vec![];
+    for file in files {
+        let result = MettaShim::run_test_file(&file, &args.include_paths, coverage.as_ref());
+        print_file_result(&result);
+        results.push(result);
+    }
+
+    let passed: usize = results.iter().map(|r| r.passed()).sum();
+    let failed: usize = results.iter().map(|r| r.failed()).sum();
+    let errors = results.iter().filter(|r| !r.errors.is_empty()).count();
+    println!("\n{} files, {passed} assertions passed, {failed} failed, {errors} files with errors", results.len());
+
+    let reports = [(&args.junit, junit_report as fn(&[TestFileResult]) -> String), (&args.tap, tap_report)];
+    for (path, report) in reports {
+        if let Some(path) = path {
+            if let Err(err) = std::fs::write(path, report(&results)) {
+                eprintln!("Error: cannot write report into {}: {err}", path.display());
+                return 2;
+            }
+        }
+    }
+    if let (Some(path), Some(coverage)) = (&args.lcov, &coverage) {
+        print_coverage(coverage);
+        if let Err(err) = std::fs::write(path, coverage.to_lcov()) {
+            eprintln!("Error: cannot write coverage into {}: {err}", path.display());
+            return 2;
+        }
+    }
+
+    if results.iter().all(|r| r.is_ok()) { 0 } else { 1 }
+}
+
+/// Returns the `.metta` files found at the given paths in a stable order
+fn discover_test_files(paths: &[PathBuf]) -> Result<Vec<PathBuf>, String> {
+    fn visit(dir: &Path, files: &mut Vec<PathBuf>) -> Result<(), String> {
+        let entries = std::fs::read_dir(dir).map_err(|err| format!("cannot read {}: {err}", dir.display()))?;
+        let mut paths = entries.map(|entry| entry.map(|entry| entry.path()))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|err| format!("cannot read {}: {err}", dir.display()))?;
+        paths.sort();
+        for path in paths {
+            if path.is_dir() {
+                visit(&path, files)?;
+            } else if path.extension().is_some_and(|ext| ext == "metta") {
+                files.push(path);
+            }
+        }
+        Ok(())
+    }
+
+    let mut files = vec![];
+    for path in paths {
+        if path.is_dir() {
+            visit(path, &mut files)?;
+        } else if path.is_file() {
+            files.push(path.clone());
+        } else {
+            return Err(format!("{} is not found", path.display()));
+        }
+    }
+    Ok(files)
+}
+
+fn print_file_result(result: &TestFileResult) {
+    let status = if result.is_ok() { "PASS" } else { "FAIL" };
+    println!("{status} {} ({} passed, {} failed, {:.2}s)", result.path.display(),
+        result.passed(), result.failed(), result.duration.as_secs_f64());
+    for assertion in result.assertions.iter() {
+        if let Some(failure) = &assertion.failure {
+            println!("  {}{}", assertion.assertion, indent(failure, "    "));
+        }
+    }
+    for error in result.errors.iter() {
+        println!("  {}", indent(error, "    ").trim_start());
+    }
+}
+
+/// Prints the number of covered definitions per file and the locations of uncovered ones
+fn print_coverage(coverage: &CoverageCollector) {
+    println!("\nCoverage:");
+    for file in coverage.files() {
+        println!("{} ({} of {} definitions covered)", file.path.display(), file.covered(), file.definitions.len());
+        for def in file.definitions.iter().filter(|def| def.hits == 0) {
+            println!("  not covered {}:{} {}", file.path.display(), def.line, def.atom);
+        }
+    }
+}
+
+pub(crate) fn indent(text: &str, prefix: &str) -> String {
+    text.lines().map(|line| if line.is_empty() { "\n".to_string() } else { format!("{prefix}{line}\n") })
+        .collect::<String>().trim_end().to_string()
+}
+
+fn xml_escape(text: &str) -> String {
+    text.chars().map(|c| match c {
+        '&' => "&amp;".to_string(),
+        '<' => "&lt;".to_string(),
+        '>' => "&gt;".to_string(),
+        '"' => "&quot;".to_string(),
+        '\'' => "&apos;".to_string(),
+        c => c.to_string(),
+    }).collect()
+}
+
+/// Formats the results as JUnit XML: one test suite per file and one test case per assertion
+fn junit_report(results: &[TestFileResult]) -> String {
+    let tests: usize = results.iter().map(|r| r.assertions.len() + r.errors.len()).sum();
+    let failures: usize = results.iter().map(|r| r.failed()).sum();
+    let errors: usize = results.iter().map(|r| r.errors.len()).sum();
+    let time: f64 = results.iter().map(|r| r.duration.as_secs_f64()).sum();
+
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str(&format!("<testsuites tests=\"{tests}\" failures=\"{failures}\" errors=\"{errors}\" time=\"{time:.3}\">\n"));
+    for result in results {
+        let name = xml_escape(&result.path.display().to_string());
+        xml.push_str(&format!("  <testsuite name=\"{name}\" tests=\"{}\" failures=\"{}\" errors=\"{}\" time=\"{:.3}\">\n",
+            result.assertions.len() + result.errors.len(), result.failed(),
+            result.errors.len(), result.duration.as_secs_f64()));
+        for assertion in result.assertions.iter() {
+            let case = format!("<testcase name=\"{}\" classname=\"{name}\"", xml_escape(&assertion.assertion));
+            match &assertion.failure {
+                None => xml.push_str(&format!("    {case}/>\n")),
+                Some(failure) => xml.push_str(&format!("    {case}>\n      <failure message=\"assertion failed\">{}</failure>\n    </testcase>\n",
+                    xml_escape(failure.trim()))),
+            }
+        }
+        for error in result.errors.iter() {
+            xml.push_str(&format!("    <testcase name=\"{name}\" classname=\"{name}\">\n      <error message=\"{}\"/>\n    </testcase>\n",
+                xml_escape(error)));
+        }
+        xml.push_str("  </testsuite>\n");
+    }
+    xml.push_str("</testsuites>\n");
+    xml
+}
+
+/// Formats the results in TAP version 13: one test point per assertion
+fn tap_report(results: &[TestFileResult]) -> String {
+    let mut points = vec![];
+    for result in results {
+        let path = result.path.display();
+        for assertion in result.assertions.iter() {
+            points.push((format!("{path}: {}", assertion.assertion), assertion.failure.as_deref()));
+        }
+        for error in result.errors.iter() {
+            points.push((path.to_string(), Some(error.as_str())));
+        }
+    }
+
+    let mut tap = format!("TAP version 13\n1..{}\n", points.len());
+    for (i, (description, failure)) in points.into_iter().enumerate() {
+        let description = description.replace('#', "\\#");
+        match failure {
+            None => tap.push_str(&format!("ok {} - {description}\n", i + 1)),
+            Some(failure) => {
+                tap.push_str(&format!("not ok {} - {description}\n  ---\n  message: |\n", i + 1));
+                for line in failure.trim().lines() {
+                    tap.push_str(&format!("    {line}\n"));
+                }
+                tap.push_str("  ...\n");
+            },
+        }
+    }
+    tap
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file_result(path: &str, assertions: &[(&str, Option<&str>)], errors: &[&str]) -> TestFileResult {
+        TestFileResult {
+            path: PathBuf::from(path),
+            assertions: assertions.iter().map(|(assertion, failure)| AssertionResult {
+                assertion: assertion.to_string(),
+                failure: failure.map(str::to_string),
+            }).collect(),
+            errors: errors.iter().map(|error| error.to_string()).collect(),
+            duration: Duration::from_millis(500),
+        }
+    }
+
+    #[test]
+    fn discover_test_files_in_sorted_order() {
+        let dir = std::env::temp_dir().join(format!("metta-repl-discover-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join("b")).unwrap();
+        for file in ["c.metta", "a.metta", "b/d.metta", "notes.txt"] {
+            std::fs::write(dir.join(file), "").unwrap();
+        }
+
+        let files = discover_test_files(&[dir.clone(), dir.join("a.metta")]);
+        let missing = discover_test_files(&[dir.join("missing")]);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(files, Ok(vec![dir.join("a.metta"), dir.join("b/d.metta"), dir.join("c.metta"), dir.join("a.metta")]));
+        assert_eq!(missing, Err(format!("{} is not found", dir.join("missing").display())));
+    }
+
+    #[cfg(not(feature = "python"))]
+    #[test]
+    fn run_test_file_continues_after_errors() {
+        let path = std::env::temp_dir().join(format!("metta-repl-run-{}.metta", std::process::id()));
+        std::fs::write(&path, "
+            (= (foo) A)
+            !(assertEqual (foo) B)
+            !(bar (Error x Boom))
+            !(assertEqual (foo) A)
+        ").unwrap();
+
+        let result = MettaShim::run_test_file(&path, &[], None);
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(result.assertions.iter().map(|a| (a.assertion.as_str(), a.failure.is_none())).collect::<Vec<_>>(),
+            vec![("(assertEqual (foo) B)", false), ("(assertEqual (foo) A)", true)]);
+        assert_eq!(result.errors, vec!["(Error x Boom)".to_string()]);
+    }
+
+    #[test]
+    fn xml_escape_special_characters() {
+        assert_eq!(xml_escape(r#"(assert <a> & "b" 'c')"#), "(assert &lt;a&gt; &amp; &quot;b&quot; &apos;c&apos;)");
+    }
+
+    #[test]
+    fn junit_report_format() {
+        let results = [
+            file_result("a.metta", &[("(assertEqual A A)", None), ("(assertEqual A B)", Some("\nExpected: [B]\nGot: [A]"))], &[]),
+            file_result("<b>.metta", &[], &["(Error x Boom)"]),
+        ];
+        assert_eq!(junit_report(&results), r#"<?xml version="1.0" encoding="UTF-8"?>
+<testsuites tests="3" failures="1" errors="1" time="1.000">
+  <testsuite name="a.metta" tests="2" failures="1" errors="0" time="0.500">
+    <testcase name="(assertEqual A A)" classname="a.metta"/>
+    <testcase name="(assertEqual A B)" classname="a.metta">
+      <failure message="assertion failed">Expected: [B]
+Got: [A]</failure>
+    </testcase>
+  </testsuite>
+  <testsuite name="&lt;b&gt;.metta" tests="1" failures="0" errors="1" time="0.500">
+    <testcase name="&lt;b&gt;.metta" classname="&lt;b&gt;.metta">
+      <error message="(Error x Boom)"/>
+    </testcase>
+  </testsuite>
+</testsuites>
+"#);
+    }
+
+    #[test]
+    fn tap_report_format() {
+        let results = [
+            file_result("a.metta", &[("(assertEqual A A)", None), ("(assertEqual A B)", Some("\nExpected: [B]\nGot: [A]"))], &[]),
+            file_result("#b.metta", &[], &["(Error x Boom)"]),
+        ];
+        assert_eq!(tap_report(&results), "TAP version 13
+1..3
+ok 1 - a.metta: (assertEqual A A)
+not ok 2 - a.metta: (assertEqual A B)
+  ---
+  message: |
+    Expected: [B]
+    Got: [A]
+  ...
+not ok 3 - \\#b.metta
+  ---
+  message: |
+    (Error x Boom)
+  ...
+");
+    }
+}
@@ -0,0 +1,70 @@
+//! Implements `metta-repl --watch` mode, which runs the file in a fresh runner each time the file or
+//! any module loaded from disk by the file changes.  Changes are detected by polling the
+//! modification times of the files
+//!
+
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use crate::metta_shim::MettaShim;
+use crate::test_runner::indent;
+use crate::SIGINT_RECEIVED_COUNT;
+
+/// Interval between the checks of the watched files
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Runs the file each time the watched files change, until Ctrl-C is pressed while waiting for
+/// the changes.  Ctrl-C pressed while the file is running interrupts the run only
+pub fn watch_file(mut metta: MettaShim, path: &Path) {
+    //Files are never removed from the watched list, so the modules are still watched after a run
+    // which failed before loading them
+    let mut files = vec![path.to_path_buf()];
+    loop {
+        println!("=== Running {} ===", path.display());
+        //The snapshot is taken before the run, so the changes made while the file is running
+        // trigger the next run
+        let started = SystemTime::now();
+        let mut snapshot = modification_times(&files);
+        let (assertions, error) = metta.run_file_in_fresh_runner(path);
+        metta.print_result();
+
+        let failed: Vec<_> = assertions.iter().filter(|a| a.failure.is_some()).collect();
+        for assertion in failed.iter() {
+            println!("FAILED {}{}", assertion.assertion, indent(assertion.failure.as_ref().unwrap(), "  "));
+        }
+        if !assertions.is_empty() {
+            println!("{} assertions passed, {} failed", assertions.len() - failed.len(), failed.len());
+        }
+        if let Some(error) = error {
+            println!("Error: {error}");
+        }
+
+        for file in metta.module_source_paths() {
+            if !files.contains(&file) {
+                //The module is loaded during the run, so it is considered changed if it was
+                // modified after the run started
+                let modified = modification_times(std::slice::from_ref(&file)).pop().flatten();
+                snapshot.push(modified.filter(|time| *time <= started));
+                files.push(file);
+            }
+        }
+        println!("Watching {} file(s) for changes, press Ctrl-C to stop", files.len());
+
+        loop {
+            std::thread::sleep(POLL_INTERVAL);
+            if *SIGINT_RECEIVED_COUNT.lock().unwrap() > 0 {
+                return;
+            }
+            if modification_times(&files) != snapshot {
+                break;
+            }
+        }
+    }
+}
+
+/// Returns the modification time of each file, or `None` if the file cannot be accessed
+fn modification_times(files: &[PathBuf]) -> Vec<Option<SystemTime>> {
+    files.iter()
+        .map(|file| std::fs::metadata(file).and_then(|metadata| metadata.modified()).ok())
+        .collect()
+}